@@ -2,7 +2,7 @@ use std::{rc::Rc, mem, cell::{Cell, OnceCell, RefCell}, fmt::Debug};
 
 use static_assertions::{assert_eq_size, assert_eq_align};
 
-use crate::{UnaryOp, BinaryOp};
+use crate::{UnaryOp, BinaryOp, Span, TypeAnn, TypeDeclaration, Signature, Comment, Comments};
 
 
 #[repr(u8)]
@@ -10,15 +10,18 @@ pub enum StatementDiscriminant {
     LocalDeclaration = 0,
     Block = 2,
     IfStatement = 3,
-    // ForStatement = 4
+    ForOfStatement = 4,
     WhileStatement = 5,
     Continue = 6,
     Break = 7,
     Return = 8,
     ReturnEmpty = 9,
     Throw = 10,
-    // TryStatement = 11,
+    TryStatement = 11,
     ExprStatement = 12,
+    SwitchStatement = 13,
+    Error = 14,
+    TypeDeclaration = 15,
 }
 
 #[repr(u8)]
@@ -31,15 +34,21 @@ pub enum Statement {
     LocalDeclaration(Box<Declaration>) = StatementDiscriminant::LocalDeclaration as u8,
     Block(Box<Block>) = StatementDiscriminant::Block as u8,
     IfStatement(Box<IfStatement>) = StatementDiscriminant::IfStatement as u8,
-    // ForStatement(ForStatement),
+    ForOfStatement(Box<ForOfStatement>) = StatementDiscriminant::ForOfStatement as u8,
     WhileStatement(Box<WhileStatement>) = StatementDiscriminant::WhileStatement as u8,
     Continue = StatementDiscriminant::Continue as u8,
     Break = StatementDiscriminant::Break as u8,
     Return(Box<Expr>) = StatementDiscriminant::Return as u8,
     ReturnEmpty = StatementDiscriminant::ReturnEmpty as u8,
     Throw(Box<Expr>) = StatementDiscriminant::Throw as u8,
-    // TryStatement(TryStatement),
+    TryStatement(Box<TryStatement>) = StatementDiscriminant::TryStatement as u8,
     ExprStatement(Box<Expr>) = StatementDiscriminant::ExprStatement as u8,
+    SwitchStatement(Box<SwitchStatement>) = StatementDiscriminant::SwitchStatement as u8,
+    // Placeholder for a statement that failed to parse in recovery mode.
+    // The location is in the enclosing Block's spans, the cause in the parser diagnostics.
+    Error = StatementDiscriminant::Error as u8,
+    // Tessie type alias or interface, erased at runtime
+    TypeDeclaration(Box<TypeDeclaration>) = StatementDiscriminant::TypeDeclaration as u8,
 }
 
 #[derive(PartialEq, Clone)]
@@ -47,11 +56,37 @@ pub struct Block {
     pub declarations: Box<[Declaration]>,
 
     pub statements: Box<[Statement]>,
+
+    // source location of each statement, parallel to statements
+    pub spans: Box<[Span]>,
+
+    // comments of each statement, then the ones before the closing brace, or empty if comments are not kept
+    pub comments: Box<[Comments]>,
 }
 
 impl Block {
     pub fn new(declarations: Box<[Declaration]>, statements: Box<[Statement]>) -> Self {
-        Block{declarations, statements}
+        let spans = vec![Span::default(); statements.len()].into_boxed_slice();
+        Block{declarations, statements, spans, comments: Box::new([])}
+    }
+
+    pub fn with_spans(declarations: Box<[Declaration]>, statements: Box<[Statement]>, spans: Box<[Span]>) -> Self {
+        debug_assert_eq!(statements.len(), spans.len());
+        Block{declarations, statements, spans, comments: Box::new([])}
+    }
+
+    pub fn with_comments(self, comments: Box<[Comments]>) -> Self {
+        debug_assert!(comments.is_empty() || comments.len() == self.statements.len() + 1);
+        Block{comments, ..self}
+    }
+
+    pub fn span_of(&self, index: usize) -> Span {
+        self.spans.get(index).cloned().unwrap_or_default()
+    }
+
+    // index == statements.len() for the comments before the closing brace
+    pub fn comments_of(&self, index: usize) -> &[Comment] {
+        self.comments.get(index).map_or(&[], |comments| comments)
     }
 }
 
@@ -76,6 +111,52 @@ pub struct WhileStatement {
     pub body: Block,
 }
 
+#[derive(PartialEq, Clone)]
+pub struct ForOfStatement {
+    pub is_const: bool,
+    // The loop binding is also listed in body.declarations, without initializer,
+    // so that it is scoped to the body and rebound on every iteration.
+    pub pattern: Pattern,
+    pub iterable: Expr,
+    pub body: Block,
+}
+
+#[derive(PartialEq, Clone)]
+pub struct SwitchStatement {
+    pub discriminant: Expr,
+    pub clauses: Box<[SwitchClause]>,
+}
+
+// Every clause body ends with break, continue, return or throw, enforced by the parser.
+// There is no fall-through between clauses.
+#[derive(PartialEq, Clone)]
+pub struct SwitchClause {
+    pub labels: Box<[CaseLabel]>,
+    pub body: Block,
+}
+
+#[derive(PartialEq, Clone)]
+pub enum CaseLabel {
+    Case(Expr),
+    Default,
+}
+
+// At least one of catch or finally exists, enforced by the parser.
+#[derive(PartialEq, Clone)]
+pub struct TryStatement {
+    pub block: Block,
+    pub catch: Option<CatchClause>,
+    pub finally: Option<Block>,
+}
+
+#[derive(PartialEq, Clone)]
+pub struct CatchClause {
+    // The parameter is bound inside the catch body, and is also listed in body.declarations
+    // so that the scope resolver allocates a local slot for it.
+    pub param: Pattern,
+    pub body: Block,
+}
+
 #[repr(u8)]
 pub enum ExprDiscriminant {
     DataLiteral = 0,
@@ -87,7 +168,7 @@ pub enum ExprDiscriminant {
     BinaryExpr = 7,
     UnaryExpr = 8,
     CallExpr = 9,
-    // QuasiExpr() = 10
+    QuasiExpr = 10,
     ParenedExpr = 11,
     Variable = 12,
     Spread = 13,
@@ -96,7 +177,7 @@ pub enum ExprDiscriminant {
 #[derive(PartialEq, Clone)]
 #[repr(u8)]
 pub enum Expr {
-    DataLiteral(Box<DataLiteral>) = ExprDiscriminant::DataLiteral as u8,
+    DataLiteral(Box<LiteralExpr>) = ExprDiscriminant::DataLiteral as u8,
     Array(Box<Array>) = ExprDiscriminant::Array as u8,
     Record(Box<Record>) = ExprDiscriminant::Record as u8,
     Function(Box<Function>) = ExprDiscriminant::Function as u8,
@@ -105,12 +186,37 @@ pub enum Expr {
     BinaryExpr(Box<BinaryExpr>) = ExprDiscriminant::BinaryExpr as u8,
     UnaryExpr(Box<UnaryExpr>) = ExprDiscriminant::UnaryExpr as u8,
     CallExpr(Box<CallExpr>) = ExprDiscriminant::CallExpr as u8,
-    // QuasiExpr() = 10
+    QuasiExpr(Box<QuasiExpr>) = ExprDiscriminant::QuasiExpr as u8,
     ParenedExpr(Box<Expr>) = ExprDiscriminant::ParenedExpr as u8,
     Variable(Box<Variable>) = ExprDiscriminant::Variable as u8,
     Spread(Box<Expr>) = ExprDiscriminant::Spread as u8, // for array elements
 }
 
+impl Expr {
+    // Best known source location of the expression. Arrays do not carry spans,
+    // so they are located by their elements, and binary and conditional expressions by their operands.
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::DataLiteral(literal) => literal.span,
+            Expr::Array(array) => array.0.iter().fold(Span::default(), |span, elem| span.to(elem.span())),
+            Expr::Record(record) => record.2,
+            Expr::Function(func) => func.span,
+            Expr::Assignment(assignment) => match &assignment.1 {
+                LValue::CallLValue(call) => call.span,
+                LValue::Variable(var) => var.span,
+            }.to(assignment.2.span()),
+            Expr::CondExpr(cond) => cond.0.span().to(cond.2.span()),
+            Expr::BinaryExpr(binary) => binary.1.span().to(binary.2.span()),
+            Expr::UnaryExpr(unary) => unary.span,
+            Expr::CallExpr(call) => call.span,
+            Expr::QuasiExpr(quasi) => quasi.span,
+            Expr::ParenedExpr(expr) => expr.span(),
+            Expr::Variable(var) => var.span,
+            Expr::Spread(expr) => expr.span(),
+        }
+    }
+}
+
 #[repr(transparent)]
 #[derive(PartialEq, Clone)]
 pub struct Array(pub Box<[Expr]>);
@@ -122,9 +228,10 @@ pub struct KeyValue {
 }
 
 
-#[repr(transparent)]
-#[derive(Debug, PartialEq, Clone)]
-pub struct Record(pub Box<[PropDef]>);
+// properties, and the comments of each property then the ones before the closing brace, or empty if comments are not kept,
+// and the span from the opening brace to the closing one
+#[derive(PartialEq, Clone)]
+pub struct Record(pub Box<[PropDef]>, pub Box<[Comments]>, pub Span);
 
 #[repr(u8)]
 #[derive(Debug, PartialEq, Clone)]
@@ -134,6 +241,9 @@ pub enum PropDef {
     Spread(Expr),
     Getter(Box<Function>),
     Setter(Box<Function>),
+    MethodDef(Box<Function>),
+    // [key]: value, methods with computed keys are stored as function values
+    Computed(Expr, Expr),
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -141,15 +251,25 @@ pub struct Field {
     pub name: Rc<str>,
 }
 
+#[derive(PartialEq, Clone)]
+pub struct LiteralExpr {
+    pub data: DataLiteral,
+    pub span: Span,
+}
+
 #[derive(PartialEq, Clone)]
 pub enum DataLiteral {
     Null,
     False,
     True,
     Integer(i64),
+    // integer part and fraction / 10^19
     Decimal(i64, u64),
+    // out of the range of Integer and Decimal
+    Float(f64),
     String(Rc<str>),
     Undefined,
+    // sign and little endian u64 limbs of the absolute value
     Bigint(bool, Box<[u64]>),
 }
 
@@ -165,6 +285,7 @@ pub struct CondExpr(pub Expr, pub Expr, pub Expr);
 pub struct UnaryExpr {
     pub op: Box<[UnaryOp]>,
     pub expr: Expr,
+    pub span: Span,
 }
 
 #[repr(u8)]
@@ -172,8 +293,28 @@ pub struct UnaryExpr {
 pub enum CallPostOp {
     Index(Expr) = 0,
     Member(Rc<str>) = 1,
-    // QuasiExpr = 2
+    QuasiExpr(Box<QuasiExpr>) = 2, // tagged template
     Call(Box<[Expr]>) = 3,
+    // ?.[expr], ?.member, ?.(args)
+    OptionalIndex(Expr) = 4,
+    OptionalMember(Rc<str>) = 5,
+    OptionalCall(Box<[Expr]>) = 6,
+}
+
+impl CallPostOp {
+    // optional operators short-circuit the rest of the chain when the value is nullish
+    pub fn is_optional(&self) -> bool {
+        matches!(self, CallPostOp::OptionalIndex(_) | CallPostOp::OptionalMember(_) | CallPostOp::OptionalCall(_))
+    }
+}
+
+// Template literal. There is always one more string chunk than the substitutions,
+// strings[0] ${exprs[0]} strings[1] ... ${exprs[n-1]} strings[n]
+#[derive(PartialEq, Clone)]
+pub struct QuasiExpr {
+    pub strings: Box<[Rc<str>]>,
+    pub exprs: Box<[Expr]>,
+    pub span: Span,
 }
 
 
@@ -181,6 +322,7 @@ pub enum CallPostOp {
 pub struct CallExpr {
     pub expr: Expr,
     pub post_ops: Box<[CallPostOp]>,
+    pub span: Span,
 }
 #[repr(C)]
 #[derive(PartialEq, Clone)]
@@ -203,6 +345,9 @@ pub enum AssignOp {
     AssignBitAnd,
     AssignBitXor,
     AssignBitOr,
+    AssignOr,
+    AssignAnd,
+    AssignCoalesce,
 }
 
 
@@ -224,6 +369,7 @@ pub enum LValueCallPostOp {
 pub struct CallLValue {
     pub expr: Expr,
     pub post_ops: Box<[LValueCallPostOp]>,
+    pub span: Span, // same layout as CallExpr
 }
 
 
@@ -269,6 +415,17 @@ impl Pattern {
     pub fn optional(variable: Variable, expr: Expr) -> Self {
         Pattern::Optional(Box::new(OptionalPattern(OptionalOp::Optional, LValueOptional::Variable(Box::new(variable)), expr)))
     }
+
+    // returns None for the patterns that cannot have a default value(rest and already optional)
+    pub fn with_default(self, expr: Expr) -> Option<Self> {
+        let left = match self {
+            Pattern::Variable(variable) => LValueOptional::Variable(variable),
+            Pattern::ArrayPattern(array) => LValueOptional::ArrayPattern(array),
+            Pattern::RecordPattern(record) => LValueOptional::RecordPattern(record),
+            Pattern::Rest(_) | Pattern::Optional(_) => return None,
+        };
+        Some(Pattern::Optional(Box::new(OptionalPattern(OptionalOp::Optional, left, expr))))
+    }
 }
 
 #[repr(C)]
@@ -284,9 +441,21 @@ pub enum OptionalOp {
 #[repr(u8)]
 #[derive(Debug, PartialEq, Clone)]
 pub enum LValueOptional {
+    ArrayPattern(Box<ArrayPattern>) = ExprDiscriminant::Array as u8,
+    RecordPattern(Box<RecordPattern>) = ExprDiscriminant::Record as u8,
     Variable(Box<Variable>) = 12, // LValue::Variable
 }
 
+impl LValueOptional {
+    pub fn into_pattern(self) -> Pattern {
+        match self {
+            LValueOptional::ArrayPattern(array) => Pattern::ArrayPattern(array),
+            LValueOptional::RecordPattern(record) => Pattern::RecordPattern(record),
+            LValueOptional::Variable(variable) => Pattern::Variable(variable),
+        }
+    }
+}
+
 // ArrayPattern is a subset of Expr::Array
 #[repr(transparent)]
 #[derive(PartialEq, Clone)]
@@ -304,6 +473,8 @@ pub enum PropParam {
     KeyValue(Box<Field>, Pattern),
     Shorthand(Box<Field>, Box<Variable>),
     Rest(Box<Variable>),
+    // [key]: pattern
+    Computed(Expr, Pattern),
 }
 
 #[derive(PartialEq, Clone)]
@@ -316,6 +487,7 @@ pub enum Declaration {
 #[derive(PartialEq, Clone)]
 pub struct VariableDeclaration {
     pub pattern: Pattern,
+    pub type_ann: Option<TypeAnn>, // Tessie
     pub value: Option<Expr>,
 }
 
@@ -338,9 +510,17 @@ pub struct Function {
 
     pub parameters: Box<[Pattern]>,
 
+    // Tessie annotations, None if the function has none
+    pub signature: Option<Box<Signature>>,
+
     pub body: ExprOrBlock,
 
     pub scope: Option<Box<FunctionScope>>,
+
+    pub span: Span,
+
+    // empty if comments are not kept
+    pub comments: Comments,
 }
 
 impl Function {
@@ -436,6 +616,8 @@ impl VariableIndex {
 pub struct Variable {
     pub name: Rc<str>,
     pub pointer: Rc<OnceCell<VariableIndex>>,
+    // where the variable is used or declared, not shared between the clones
+    pub span: Span,
 }
 
 impl Variable {
@@ -443,9 +625,14 @@ impl Variable {
         Variable {
             name,
             pointer: Rc::new(OnceCell::from(pointer)),
+            span: Span::default(),
         }
     }
 
+    pub fn with_span(self, span: Span) -> Self {
+        Variable { span, ..self }
+    }
+
     pub fn new(name: Rc<str>) -> Self {
         Variable::hoisted(name)
     }
@@ -454,6 +641,7 @@ impl Variable {
         Variable {
             name,
             pointer: Rc::new(OnceCell::new()),
+            span: Span::default(),
         }
     }
 
@@ -493,12 +681,17 @@ impl Debug for Statement {
             Statement::Block(block) => write!(f, "{:?}", block),
             Statement::IfStatement(if_statement) => write!(f, "{:?}", if_statement),
             Statement::WhileStatement(while_statement) => write!(f, "{:?}", while_statement),
+            Statement::ForOfStatement(for_of_statement) => write!(f, "{:?}", for_of_statement),
             Statement::Continue => write!(f, "continue"),
             Statement::Break => write!(f, "break"),
             Statement::Return(expr) => write!(f, "return {:?}", expr),
             Statement::ReturnEmpty => write!(f, "return"),
             Statement::Throw(expr) => write!(f, "throw {:?}", expr),
+            Statement::TryStatement(try_statement) => write!(f, "{:?}", try_statement),
             Statement::ExprStatement(expr) => write!(f, "{:?}", expr),
+            Statement::SwitchStatement(switch_statement) => write!(f, "{:?}", switch_statement),
+            Statement::Error => write!(f, "<error>"),
+            Statement::TypeDeclaration(decl) => write!(f, "{:?}", decl),
         }?;
         write!(f, ";")
     }
@@ -543,12 +736,59 @@ impl Debug for WhileStatement {
     }
 }
 
+impl Debug for ForOfStatement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "for ({} {:?} of {:?}) {:?}", if self.is_const { "const" } else { "let" }, self.pattern, self.iterable, self.body)
+    }
+}
+
+impl Debug for TryStatement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "try {:?}", self.block)?;
+        if let Some(catch) = &self.catch {
+            write!(f, " {:?}", catch)?;
+        }
+        if let Some(finally) = &self.finally {
+            write!(f, " finally {:?}", finally)?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for CatchClause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "catch ({:?}) {:?}", self.param, self.body)
+    }
+}
+
+impl Debug for SwitchStatement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "switch ({:?}) {{", self.discriminant)?;
+        for clause in self.clauses.iter() {
+            write!(f, " {:?}", clause)?;
+        }
+        write!(f, " }}")
+    }
+}
+
+impl Debug for SwitchClause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for label in self.labels.iter() {
+            match label {
+                CaseLabel::Case(expr) => write!(f, "case {:?}: ", expr)?,
+                CaseLabel::Default => write!(f, "default: ")?,
+            }
+        }
+        write!(f, "{:?}", self.body)
+    }
+}
+
 
 
 impl Debug for Expr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Expr::DataLiteral(data) => write!(f, "{:?}", data),
+            Expr::DataLiteral(literal) => write!(f, "{:?}", literal.data),
             Expr::Array(array) => write!(f, "{:?}", array),
             Expr::Record(record) => write!(f, "{:?}", record),
             Expr::Function(func) => write!(f, "{:?}", func),
@@ -557,6 +797,7 @@ impl Debug for Expr {
             Expr::BinaryExpr(binary_expr) => write!(f, "{:?}", binary_expr),
             Expr::UnaryExpr(unary_expr) => write!(f, "{:?}", unary_expr),
             Expr::CallExpr(call_expr) => write!(f, "{:?}", call_expr),
+            Expr::QuasiExpr(quasi) => write!(f, "{:?}", quasi),
             Expr::ParenedExpr(parened_expr) => write!(f, "({:?})", parened_expr),
             Expr::Variable(variable) => write!(f, "{:?}", variable),
             Expr::Spread(expr) => write!(f, "{:?}...", expr),
@@ -586,6 +827,11 @@ impl Debug for KeyValue {
 }
 
 
+impl Debug for Record {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Record").field(&self.0).field(&self.1).finish()
+    }
+}
 
 impl Debug for DataLiteral {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -594,15 +840,21 @@ impl Debug for DataLiteral {
             DataLiteral::False => write!(f, "false"),
             DataLiteral::True => write!(f, "true"),
             DataLiteral::Integer(int) => write!(f, "{}", int),
-            DataLiteral::Decimal(int, frac) => write!(f, "{}.{}", int, frac), // TODO
+            DataLiteral::Decimal(int, frac) => write!(f, "{}.{}", int, format!("{:0>19}", frac).trim_end_matches('0')),
+            DataLiteral::Float(float) => write!(f, "{:e}", float),
             DataLiteral::String(string) => write!(f, "\"{:?}\"", string),
             DataLiteral::Undefined => write!(f, "undefined"),
             DataLiteral::Bigint(sign, digits) => {
                 if *sign {
                     write!(f, "-")?;
                 }
-                for digit in digits.iter() {
-                    write!(f, "{:x}", digit)?;
+                write!(f, "0x")?;
+                for (index, digit) in digits.iter().rev().enumerate() {
+                    if index == 0 {
+                        write!(f, "{:x}", digit)?;
+                    } else {
+                        write!(f, "{:016x}", digit)?;
+                    }
                 }
                 write!(f, "n")?;
                 Ok(())
@@ -639,6 +891,13 @@ impl Debug for CallPostOp {
         match self {
             CallPostOp::Index(expr) => write!(f, "[{:?}]", expr),
             CallPostOp::Member(member) => write!(f, ".{:?}", member),
+            CallPostOp::QuasiExpr(quasi) => write!(f, "{:?}", quasi),
+            CallPostOp::OptionalIndex(expr) => write!(f, "?.[{:?}]", expr),
+            CallPostOp::OptionalMember(member) => write!(f, "?.{:?}", member),
+            CallPostOp::OptionalCall(args) => {
+                write!(f, "?.")?;
+                CallPostOp::Call(args.clone()).fmt(f)
+            }
             CallPostOp::Call(args) => {
                 write!(f, "(")?;
                 let mut iter = args.iter();
@@ -654,6 +913,16 @@ impl Debug for CallPostOp {
     }
 }
 
+impl Debug for QuasiExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}", self.strings[0])?;
+        for (expr, string) in self.exprs.iter().zip(self.strings[1..].iter()) {
+            write!(f, "${{{:?}}}{}", expr, string)?;
+        }
+        write!(f, "`")
+    }
+}
+
 impl Debug for CallExpr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self.expr)?;
@@ -687,6 +956,9 @@ impl Debug for AssignOp {
             AssignOp::AssignBitAnd => "&=",
             AssignOp::AssignBitXor => "^=",
             AssignOp::AssignBitOr => "|=",
+            AssignOp::AssignOr => "||=",
+            AssignOp::AssignAnd => "&&=",
+            AssignOp::AssignCoalesce => "??=",
         };
         write!(f, "{}", op)
     }
@@ -712,7 +984,7 @@ impl Debug for LValueCallPostOp {
 }
 impl Debug for CallLValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let CallLValue { expr, post_ops } = self;
+        let CallLValue { expr, post_ops, .. } = self;
         write!(f, "{:?}", expr)?;
         for op in post_ops.iter() {
             write!(f, "{:?}", op)?
@@ -738,6 +1010,8 @@ impl Debug for OptionalPattern {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self.1 {
             LValueOptional::Variable(variable) => write!(f, "{:?} = {:?}", variable, self.2),
+            LValueOptional::ArrayPattern(array) => write!(f, "{:?} = {:?}", array, self.2),
+            LValueOptional::RecordPattern(record) => write!(f, "{:?} = {:?}", record, self.2),
         }
     }
 }
@@ -775,9 +1049,12 @@ impl Debug for RecordPattern {
 impl Debug for PropParam {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            // shorthand with a default value
+            PropParam::KeyValue(field, Pattern::Optional(optional)) if matches!(&optional.1, LValueOptional::Variable(variable) if variable.name == field.name) => write!(f, "{:?}", optional),
             PropParam::KeyValue(field, pattern) => write!(f, "{:?}: {:?}", field, pattern),
             PropParam::Shorthand(field, variable) => write!(f, "{:?}", variable),
             PropParam::Rest(variable) => write!(f, "...{:?}", variable),
+            PropParam::Computed(key, pattern) => write!(f, "[{:?}]: {:?}", key, pattern),
         }
     }
 }
@@ -904,13 +1181,13 @@ impl Debug for LocalVariable {
 
 impl<const n: usize> From<&[Statement; n]> for Block {
     fn from(value: &[Statement; n]) -> Self {
-        Block {
-            statements: Box::from(value.as_slice()),
-            declarations: value.iter().filter_map(|stmt| match stmt {
+        Block::new(
+            value.iter().filter_map(|stmt| match stmt {
                 Statement::LocalDeclaration(decl) => Some(*decl.clone()),
                 _ => None,
             }).collect::<Vec<Declaration>>().into_boxed_slice(),
-        }
+            Box::from(value.as_slice()),
+        )
     }
 }
 
@@ -922,7 +1199,14 @@ impl From<&str> for Pattern {
 
 impl From<usize> for Expr {
     fn from(value: usize) -> Self {
-        Expr::DataLiteral(Box::new(DataLiteral::Integer(value as i64)))
+        DataLiteral::Integer(value as i64).into()
+    }
+}
+
+// a literal without a known span, e.g. for synthesized nodes
+impl From<DataLiteral> for Expr {
+    fn from(data: DataLiteral) -> Self {
+        Expr::DataLiteral(Box::new(LiteralExpr { data, span: Span::default() }))
     }
 }
 
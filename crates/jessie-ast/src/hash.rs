@@ -0,0 +1,560 @@
+// Content hashes of scripts, modules and functions, for keying code storage, parse caches and audit records.
+//
+// The hash is SHA3-256 over a canonical encoding of the syntax tree. Spans, parentheses, scoping results and block
+// declarations (which are derived from the statements) are left out, so sources that differ only in whitespace,
+// comments, quoting or number formatting hash the same. Every node writes the tag of its variant and the length of
+// its lists before its children, so two different trees never share an encoding.
+
+use std::{convert::Infallible, fmt::{self, Debug, Display}, rc::Rc};
+
+use sha3::{Digest, Sha3_256};
+
+use crate::*;
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+pub struct ContentHash(pub [u8; 32]);
+
+impl Display for ContentHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for ContentHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ContentHash({})", self)
+    }
+}
+
+impl Script {
+    pub fn content_hash(&self) -> ContentHash {
+        Encoder::hash("script", |encoder| encoder.visit_script(self))
+    }
+}
+
+impl Module {
+    pub fn content_hash(&self) -> ContentHash {
+        Encoder::hash("module", |encoder| encoder.visit_module(self))
+    }
+}
+
+impl Function {
+    pub fn content_hash(&self) -> ContentHash {
+        Encoder::hash("function", |encoder| encoder.visit_function(self))
+    }
+}
+
+struct Encoder {
+    hasher: Sha3_256,
+}
+
+impl Encoder {
+    // The kind of the root node is hashed first, so that a script never hashes the same as a function or a module.
+    fn hash(kind: &str, encode: impl FnOnce(&mut Self) -> Result<(), Infallible>) -> ContentHash {
+        let mut encoder = Encoder { hasher: Sha3_256::new() };
+        encoder.str("jessie-ast/v1");
+        encoder.str(kind);
+        let Ok(()) = encode(&mut encoder);
+        ContentHash(encoder.hasher.finalize().into())
+    }
+
+    fn tag(&mut self, tag: u8) {
+        self.hasher.update([tag]);
+    }
+
+    fn flag(&mut self, flag: bool) {
+        self.tag(flag as u8);
+    }
+
+    fn len(&mut self, len: usize) {
+        self.hasher.update((len as u64).to_le_bytes());
+    }
+
+    fn str(&mut self, str: &str) {
+        self.len(str.len());
+        self.hasher.update(str.as_bytes());
+    }
+
+    fn names(&mut self, names: &[Rc<str>]) {
+        self.len(names.len());
+        for name in names.iter() {
+            self.str(name);
+        }
+    }
+
+    fn export_clause(&mut self, export_clause: &ExportClause) {
+        self.tag(match export_clause {
+            ExportClause::NoExport => 0,
+            ExportClause::Export => 1,
+            ExportClause::ExportDefault => 2,
+        });
+    }
+
+    // operators are encoded as their source text
+    fn op(&mut self, op: &impl Debug) {
+        self.str(&format!("{:?}", op));
+    }
+}
+
+impl Visit for Encoder {
+    type Error = Infallible;
+
+    fn visit_module(&mut self, module: &Module) -> Result<(), Infallible> {
+        self.len(module.body.len());
+        for item in module.body.iter() {
+            self.visit_module_item(item)?;
+        }
+        Ok(())
+    }
+
+    fn visit_module_item(&mut self, item: &ModuleItem) -> Result<(), Infallible> {
+        match item {
+            ModuleItem::ImportDeclaration(_) => self.tag(0),
+            ModuleItem::ModuleDeclaration(decl) => {
+                self.tag(1);
+                self.export_clause(&decl.export_clause);
+            },
+            ModuleItem::Error(_) => self.tag(2),
+            ModuleItem::TypeDeclaration(export_clause, _) => {
+                self.tag(3);
+                self.export_clause(export_clause);
+            },
+        }
+        self.walk_module_item(item)
+    }
+
+    fn visit_import_declaration(&mut self, import: &ImportDeclaration) -> Result<(), Infallible> {
+        match &import.import_clause {
+            ImportClause::Namespace(_) => self.tag(0),
+            ImportClause::Named(names) => {
+                self.tag(1);
+                self.len(names.len());
+                for (exported, _) in names.iter() {
+                    self.str(exported);
+                }
+            },
+            ImportClause::Default(_) => self.tag(2),
+        }
+        self.str(&import.source);
+        self.walk_import_declaration(import)
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) -> Result<(), Infallible> {
+        let discriminant = match statement {
+            Statement::LocalDeclaration(_) => StatementDiscriminant::LocalDeclaration,
+            Statement::Block(_) => StatementDiscriminant::Block,
+            Statement::IfStatement(_) => StatementDiscriminant::IfStatement,
+            Statement::ForOfStatement(_) => StatementDiscriminant::ForOfStatement,
+            Statement::WhileStatement(_) => StatementDiscriminant::WhileStatement,
+            Statement::Continue => StatementDiscriminant::Continue,
+            Statement::Break => StatementDiscriminant::Break,
+            Statement::Return(_) => StatementDiscriminant::Return,
+            Statement::ReturnEmpty => StatementDiscriminant::ReturnEmpty,
+            Statement::Throw(_) => StatementDiscriminant::Throw,
+            Statement::TryStatement(_) => StatementDiscriminant::TryStatement,
+            Statement::ExprStatement(_) => StatementDiscriminant::ExprStatement,
+            Statement::SwitchStatement(_) => StatementDiscriminant::SwitchStatement,
+            Statement::Error => StatementDiscriminant::Error,
+            Statement::TypeDeclaration(_) => StatementDiscriminant::TypeDeclaration,
+        };
+        self.tag(discriminant as u8);
+        self.walk_statement(statement)
+    }
+
+    fn visit_block(&mut self, block: &Block) -> Result<(), Infallible> {
+        self.len(block.statements.len());
+        self.walk_block(block)
+    }
+
+    fn visit_declaration(&mut self, decl: &Declaration) -> Result<(), Infallible> {
+        match decl {
+            Declaration::Const(decls) => {
+                self.tag(0);
+                self.len(decls.len());
+            },
+            Declaration::Let(decls) => {
+                self.tag(1);
+                self.len(decls.len());
+            },
+            Declaration::Function(_) => self.tag(2),
+        }
+        self.walk_declaration(decl)
+    }
+
+    fn visit_variable_declaration(&mut self, decl: &VariableDeclaration) -> Result<(), Infallible> {
+        self.flag(decl.type_ann.is_some());
+        self.flag(decl.value.is_some());
+        self.walk_variable_declaration(decl)
+    }
+
+    fn visit_if_statement(&mut self, stmt: &IfStatement) -> Result<(), Infallible> {
+        self.tag(match stmt.alternate {
+            ElseArm::NoElse => 0,
+            ElseArm::Else(_) => 1,
+            ElseArm::ElseIf(_) => 2,
+        });
+        self.walk_if_statement(stmt)
+    }
+
+    fn visit_for_of_statement(&mut self, stmt: &ForOfStatement) -> Result<(), Infallible> {
+        self.flag(stmt.is_const);
+        self.walk_for_of_statement(stmt)
+    }
+
+    fn visit_switch_statement(&mut self, stmt: &SwitchStatement) -> Result<(), Infallible> {
+        self.len(stmt.clauses.len());
+        self.walk_switch_statement(stmt)
+    }
+
+    fn visit_switch_clause(&mut self, clause: &SwitchClause) -> Result<(), Infallible> {
+        self.len(clause.labels.len());
+        for label in clause.labels.iter() {
+            self.flag(matches!(label, CaseLabel::Default));
+        }
+        self.walk_switch_clause(clause)
+    }
+
+    fn visit_try_statement(&mut self, stmt: &TryStatement) -> Result<(), Infallible> {
+        self.flag(stmt.catch.is_some());
+        self.flag(stmt.finally.is_some());
+        self.walk_try_statement(stmt)
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) -> Result<(), Infallible> {
+        let discriminant = match expr {
+            // the grouping is already in the shape of the tree
+            Expr::ParenedExpr(expr) => return self.visit_expr(expr),
+            Expr::DataLiteral(_) => ExprDiscriminant::DataLiteral,
+            Expr::Array(_) => ExprDiscriminant::Array,
+            Expr::Record(_) => ExprDiscriminant::Record,
+            Expr::Function(_) => ExprDiscriminant::Function,
+            Expr::Assignment(_) => ExprDiscriminant::Assignment,
+            Expr::CondExpr(_) => ExprDiscriminant::CondExpr,
+            Expr::BinaryExpr(_) => ExprDiscriminant::BinaryExpr,
+            Expr::UnaryExpr(_) => ExprDiscriminant::UnaryExpr,
+            Expr::CallExpr(_) => ExprDiscriminant::CallExpr,
+            Expr::QuasiExpr(_) => ExprDiscriminant::QuasiExpr,
+            Expr::Variable(_) => ExprDiscriminant::Variable,
+            Expr::Spread(_) => ExprDiscriminant::Spread,
+        };
+        self.tag(discriminant as u8);
+        self.walk_expr(expr)
+    }
+
+    fn visit_data_literal(&mut self, literal: &DataLiteral) -> Result<(), Infallible> {
+        match literal {
+            DataLiteral::Null => self.tag(0),
+            DataLiteral::False => self.tag(1),
+            DataLiteral::True => self.tag(2),
+            DataLiteral::Integer(int) => {
+                self.tag(3);
+                self.hasher.update(int.to_le_bytes());
+            },
+            DataLiteral::Decimal(int, frac) => {
+                self.tag(4);
+                self.hasher.update(int.to_le_bytes());
+                self.hasher.update(frac.to_le_bytes());
+            },
+            DataLiteral::String(string) => {
+                self.tag(5);
+                self.str(string);
+            },
+            DataLiteral::Undefined => self.tag(6),
+            DataLiteral::Float(float) => {
+                self.tag(8);
+                self.hasher.update(float.to_bits().to_le_bytes());
+            },
+            DataLiteral::Bigint(sign, digits) => {
+                self.tag(7);
+                self.flag(*sign);
+                self.len(digits.len());
+                for digit in digits.iter() {
+                    self.hasher.update(digit.to_le_bytes());
+                }
+            },
+        }
+        Ok(())
+    }
+
+    fn visit_array(&mut self, array: &Array) -> Result<(), Infallible> {
+        self.len(array.0.len());
+        self.walk_array(array)
+    }
+
+    fn visit_record(&mut self, record: &Record) -> Result<(), Infallible> {
+        self.len(record.0.len());
+        self.walk_record(record)
+    }
+
+    fn visit_prop_def(&mut self, prop: &PropDef) -> Result<(), Infallible> {
+        match prop {
+            PropDef::KeyValue(field, _) => {
+                self.tag(0);
+                self.str(&field.name);
+            },
+            PropDef::Shorthand(field, _) => {
+                self.tag(1);
+                self.str(&field.name);
+            },
+            PropDef::Spread(_) => self.tag(2),
+            PropDef::Getter(_) => self.tag(3),
+            PropDef::Setter(_) => self.tag(4),
+            PropDef::MethodDef(_) => self.tag(5),
+            PropDef::Computed(_, _) => self.tag(6),
+        }
+        self.walk_prop_def(prop)
+    }
+
+    // the scope is filled by jessie-scope from the syntax, it is not part of the content
+    fn visit_function(&mut self, func: &Function) -> Result<(), Infallible> {
+        match &func.name {
+            FunctionName::Arrow => self.tag(0),
+            FunctionName::Anonymous => self.tag(1),
+            FunctionName::Named(name) => {
+                self.tag(2);
+                self.str(name);
+            },
+        }
+        self.len(func.parameters.len());
+        for param in func.parameters.iter() {
+            self.visit_pattern(param)?;
+        }
+        self.flag(func.signature.is_some());
+        if let Some(signature) = &func.signature {
+            self.visit_signature(signature)?;
+        }
+        match &func.body {
+            ExprOrBlock::Expr(expr) => {
+                self.tag(0);
+                self.visit_expr(expr)
+            },
+            ExprOrBlock::Block(block) => {
+                self.tag(1);
+                self.visit_block(block)
+            },
+        }
+    }
+
+    fn visit_assignment(&mut self, assignment: &Assignment) -> Result<(), Infallible> {
+        self.op(&assignment.0);
+        self.walk_assignment(assignment)
+    }
+
+    fn visit_lvalue(&mut self, lvalue: &LValue) -> Result<(), Infallible> {
+        self.flag(matches!(lvalue, LValue::Variable(_)));
+        self.walk_lvalue(lvalue)
+    }
+
+    fn visit_call_lvalue(&mut self, call: &CallLValue) -> Result<(), Infallible> {
+        self.len(call.post_ops.len());
+        self.walk_call_lvalue(call)
+    }
+
+    fn visit_lvalue_call_post_op(&mut self, op: &LValueCallPostOp) -> Result<(), Infallible> {
+        match op {
+            LValueCallPostOp::Index(_) => self.tag(0),
+            LValueCallPostOp::Member(name) => {
+                self.tag(1);
+                self.str(name);
+            },
+        }
+        self.walk_lvalue_call_post_op(op)
+    }
+
+    fn visit_binary_expr(&mut self, binary: &BinaryExpr) -> Result<(), Infallible> {
+        self.op(&binary.0);
+        self.walk_binary_expr(binary)
+    }
+
+    fn visit_unary_expr(&mut self, unary: &UnaryExpr) -> Result<(), Infallible> {
+        self.len(unary.op.len());
+        for op in unary.op.iter() {
+            self.op(op);
+        }
+        self.walk_unary_expr(unary)
+    }
+
+    fn visit_call_expr(&mut self, call: &CallExpr) -> Result<(), Infallible> {
+        self.len(call.post_ops.len());
+        self.walk_call_expr(call)
+    }
+
+    fn visit_call_post_op(&mut self, op: &CallPostOp) -> Result<(), Infallible> {
+        match op {
+            CallPostOp::Index(_) => self.tag(0),
+            CallPostOp::Member(name) => {
+                self.tag(1);
+                self.str(name);
+            },
+            CallPostOp::QuasiExpr(_) => self.tag(2),
+            CallPostOp::Call(args) => {
+                self.tag(3);
+                self.len(args.len());
+            },
+            CallPostOp::OptionalIndex(_) => self.tag(4),
+            CallPostOp::OptionalMember(name) => {
+                self.tag(5);
+                self.str(name);
+            },
+            CallPostOp::OptionalCall(args) => {
+                self.tag(6);
+                self.len(args.len());
+            },
+        }
+        self.walk_call_post_op(op)
+    }
+
+    fn visit_quasi_expr(&mut self, quasi: &QuasiExpr) -> Result<(), Infallible> {
+        self.len(quasi.strings.len());
+        for string in quasi.strings.iter() {
+            self.str(string);
+        }
+        self.len(quasi.exprs.len());
+        self.walk_quasi_expr(quasi)
+    }
+
+    fn visit_variable(&mut self, var: &Variable) -> Result<(), Infallible> {
+        self.str(&var.name);
+        Ok(())
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) -> Result<(), Infallible> {
+        self.tag(match pattern {
+            Pattern::Rest(_) => 0,
+            Pattern::Optional(_) => 1,
+            Pattern::ArrayPattern(_) => 2,
+            Pattern::RecordPattern(_) => 3,
+            Pattern::Variable(_) => 4,
+        });
+        self.walk_pattern(pattern)
+    }
+
+    fn visit_lvalue_optional(&mut self, lvalue: &LValueOptional) -> Result<(), Infallible> {
+        self.tag(match lvalue {
+            LValueOptional::ArrayPattern(_) => 0,
+            LValueOptional::RecordPattern(_) => 1,
+            LValueOptional::Variable(_) => 2,
+        });
+        self.walk_lvalue_optional(lvalue)
+    }
+
+    fn visit_array_pattern(&mut self, array: &ArrayPattern) -> Result<(), Infallible> {
+        self.len(array.0.len());
+        self.walk_array_pattern(array)
+    }
+
+    fn visit_record_pattern(&mut self, record: &RecordPattern) -> Result<(), Infallible> {
+        self.len(record.0.len());
+        self.walk_record_pattern(record)
+    }
+
+    fn visit_prop_param(&mut self, prop: &PropParam) -> Result<(), Infallible> {
+        match prop {
+            PropParam::KeyValue(field, _) => {
+                self.tag(0);
+                self.str(&field.name);
+            },
+            PropParam::Shorthand(field, _) => {
+                self.tag(1);
+                self.str(&field.name);
+            },
+            PropParam::Rest(_) => self.tag(2),
+            PropParam::Computed(_, _) => self.tag(3),
+        }
+        self.walk_prop_param(prop)
+    }
+
+    fn visit_signature(&mut self, signature: &Signature) -> Result<(), Infallible> {
+        self.names(&signature.type_params);
+        self.len(signature.parameters.len());
+        for ty in signature.parameters.iter() {
+            self.flag(ty.is_some());
+            if let Some(ty) = ty {
+                self.visit_type_ann(ty)?;
+            }
+        }
+        self.flag(signature.return_type.is_some());
+        if let Some(ty) = &signature.return_type {
+            self.visit_type_ann(ty)?;
+        }
+        Ok(())
+    }
+
+    fn visit_type_declaration(&mut self, decl: &TypeDeclaration) -> Result<(), Infallible> {
+        match decl {
+            TypeDeclaration::Alias(alias) => {
+                self.tag(0);
+                self.str(&alias.name);
+                self.names(&alias.type_params);
+            },
+            TypeDeclaration::Interface(interface) => {
+                self.tag(1);
+                self.str(&interface.name);
+                self.names(&interface.type_params);
+                self.len(interface.extends.len());
+                self.len(interface.members.len());
+            },
+        }
+        self.walk_type_declaration(decl)
+    }
+
+    fn visit_type_ann(&mut self, ty: &TypeAnn) -> Result<(), Infallible> {
+        match ty {
+            TypeAnn::Any => self.tag(0),
+            TypeAnn::Unknown => self.tag(1),
+            TypeAnn::Never => self.tag(2),
+            TypeAnn::Void => self.tag(3),
+            TypeAnn::Undefined => self.tag(4),
+            TypeAnn::Null => self.tag(5),
+            TypeAnn::Boolean => self.tag(6),
+            TypeAnn::Number => self.tag(7),
+            TypeAnn::String => self.tag(8),
+            TypeAnn::Bigint => self.tag(9),
+            TypeAnn::Literal(_) => self.tag(10),
+            TypeAnn::Named(_) => self.tag(11),
+            TypeAnn::Array(_) => self.tag(12),
+            TypeAnn::Tuple(types) => {
+                self.tag(13);
+                self.len(types.len());
+            },
+            TypeAnn::Record(props) => {
+                self.tag(14);
+                self.len(props.len());
+            },
+            TypeAnn::Function(_) => self.tag(15),
+            TypeAnn::Union(types) => {
+                self.tag(16);
+                self.len(types.len());
+            },
+            TypeAnn::Intersection(types) => {
+                self.tag(17);
+                self.len(types.len());
+            },
+        }
+        self.walk_type_ann(ty)
+    }
+
+    fn visit_named_type(&mut self, named: &NamedType) -> Result<(), Infallible> {
+        self.str(&named.name);
+        self.len(named.args.len());
+        self.walk_named_type(named)
+    }
+
+    fn visit_prop_type(&mut self, prop: &PropType) -> Result<(), Infallible> {
+        self.str(&prop.name);
+        self.flag(prop.optional);
+        self.walk_prop_type(prop)
+    }
+
+    fn visit_function_type(&mut self, func: &FunctionType) -> Result<(), Infallible> {
+        self.len(func.parameters.len());
+        for param in func.parameters.iter() {
+            self.str(&param.name);
+            self.flag(param.is_rest);
+        }
+        self.walk_function_type(func)
+    }
+}
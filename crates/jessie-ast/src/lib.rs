@@ -3,8 +3,18 @@
 pub mod ast;
 pub mod operation;
 pub mod module;
+pub mod span;
 pub mod t;
+pub mod types;
+pub mod trivia;
+pub mod visit;
+pub mod hash;
 
 pub use ast::*;
 pub use operation::*;
-pub use module::*;
\ No newline at end of file
+pub use module::*;
+pub use span::*;
+pub use types::*;
+pub use trivia::*;
+pub use visit::*;
+pub use hash::*;
\ No newline at end of file
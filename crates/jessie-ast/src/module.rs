@@ -1,6 +1,6 @@
 use std::rc::Rc;
 
-use crate::{Block, Declaration, Statement};
+use crate::{Block, Declaration, Variable, FunctionScope, Span, TypeDeclaration, Comment, Comments};
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Script{
@@ -10,25 +10,62 @@ pub struct Script{
 #[derive(Debug, PartialEq, Clone)]
 pub struct Module{
     pub body: Vec<ModuleItem>,
+
+    // comments of each item, then the ones at the end of the module, or empty if comments are not kept
+    pub comments: Box<[Comments]>,
+
+    // The module body is scoped as a function without parameters, filled by jessie-scope
+    pub scope: Option<Box<FunctionScope>>,
+}
+
+impl Module {
+    // index == body.len() for the comments at the end of the module
+    pub fn comments_of(&self, index: usize) -> &[Comment] {
+        self.comments.get(index).map_or(&[], |comments| comments)
+    }
+
+    // Import declarations in source order. Hosts resolve them and supply the
+    // imported modules to the interpreter in the same order.
+    pub fn imports(&self) -> impl Iterator<Item = &ImportDeclaration> {
+        self.body.iter().filter_map(|item| match item {
+            ModuleItem::ImportDeclaration(import) => Some(import),
+            _ => None,
+        })
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum ModuleItem {
     ImportDeclaration(ImportDeclaration),
     ModuleDeclaration(ModuleDeclaration),
+    // Tessie type alias or interface, erased at runtime
+    TypeDeclaration(ExportClause, TypeDeclaration),
+    // Placeholder for an item that failed to parse in recovery mode
+    Error(Span),
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct ImportDeclaration {
-    import_clause: ImportClause,
-    source: String,
+    pub import_clause: ImportClause,
+    pub source: String,
 }
 
+// Imported names are const bindings in the module scope.
 #[derive(Debug, PartialEq, Clone)]
 pub enum ImportClause {
-    Namespace(String), // import * as name from source
-    Named(Box<[(String, Option<String>)]>), // import { name1, name2 as name3 } from source
-    Default(String), // import name from source
+    Namespace(Variable), // import * as name from source
+    Named(Box<[(Rc<str>, Variable)]>), // import { name1, name2 as name3 } from source, (exported name, local binding)
+    Default(Variable), // import name from source
+}
+
+impl ImportClause {
+    pub fn bindings_mut(&mut self) -> Vec<&mut Variable> {
+        match self {
+            ImportClause::Namespace(var) => vec![var],
+            ImportClause::Named(names) => names.iter_mut().map(|(_, var)| var).collect(),
+            ImportClause::Default(var) => vec![var],
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -0,0 +1,40 @@
+use std::fmt::Debug;
+
+// Location of a node or token in the source text.
+// start and end are byte offsets, line and column(both starting from 1) are of the start position.
+// Spans take part in the AST equality, content_hash compares the code regardless of where it is in the source.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: u32,
+    pub end: u32,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl Span {
+    pub fn new(start: u32, end: u32, line: u32, column: u32) -> Self {
+        Span { start, end, line, column }
+    }
+
+    // line 0 means the span is unknown, e.g. for synthesized nodes
+    pub fn is_unknown(&self) -> bool {
+        self.line == 0
+    }
+
+    // from the start of self to the end of other
+    pub fn to(&self, other: Span) -> Span {
+        if self.is_unknown() {
+            return other
+        }
+        if other.is_unknown() {
+            return *self
+        }
+        Span { start: self.start, end: other.end, line: self.line, column: self.column }
+    }
+}
+
+impl Debug for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
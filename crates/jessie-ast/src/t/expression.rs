@@ -1,29 +1,29 @@
 use std::{rc::Rc, cell::OnceCell};
 
-use crate::{Expr, DataLiteral, Array, PropDef, Record, BinaryExpr, UnaryOp, UnaryExpr, CallPostOp, Assignment, LValue, Variable, Pattern, Block, Function};
+use crate::{Span, Expr, DataLiteral, Array, PropDef, Record, BinaryExpr, UnaryOp, UnaryExpr, CallPostOp, Assignment, LValue, Variable, Pattern, Block, Function};
 
 pub fn _null() -> Expr {
-    Expr::DataLiteral(Box::new(DataLiteral::Null))
+    DataLiteral::Null.into()
 }
 
 pub fn _undefined() -> Expr {
-    Expr::DataLiteral(Box::new(DataLiteral::Undefined))
+    DataLiteral::Undefined.into()
 }
 
 pub fn _number(n: i64) -> Expr {
-    Expr::DataLiteral(Box::new(DataLiteral::Integer(n)))
+    DataLiteral::Integer(n).into()
 }
 
 pub fn _string(s: &str) -> Expr {
-    Expr::DataLiteral(Box::new(DataLiteral::String(Rc::from(s))))
+    DataLiteral::String(Rc::from(s)).into()
 }
 
 pub fn _false() -> Expr {
-    Expr::DataLiteral(Box::new(DataLiteral::False))
+    DataLiteral::False.into()
 }
 
 pub fn _true() -> Expr {
-    Expr::DataLiteral(Box::new(DataLiteral::True))
+    DataLiteral::True.into()
 }
 
 pub fn _array(elements: &[Expr]) -> Expr {
@@ -31,7 +31,7 @@ pub fn _array(elements: &[Expr]) -> Expr {
 }
 
 pub fn _record(props: &[PropDef]) -> Expr {
-    Expr::Record(Box::new(Record(Box::from(props))))
+    Expr::Record(Box::new(Record(Box::from(props), Box::new([]), Span::default())))
 }
 
 fn _binary_expr(op: crate::BinaryOp, x: impl Into<Expr>, y: impl Into<Expr>) -> Expr {
@@ -104,12 +104,14 @@ fn _unary(op: UnaryOp, expr: impl Into<Expr>) -> Expr {
             Expr::UnaryExpr(Box::new(UnaryExpr{
                 op: ops.into_boxed_slice(),
                 expr: unary.expr,
+                span: unary.span,
             }))
         },
         _ => {
             Expr::UnaryExpr(Box::new(UnaryExpr{
                 op: Box::new([op]),
                 expr: expr,
+                span: Default::default(),
             }))
         },
     }
@@ -145,12 +147,14 @@ fn _callpost(op: CallPostOp, expr: impl Into<Expr>) -> Expr {
             Expr::CallExpr(Box::new(crate::CallExpr{
                 expr: call.expr,
                 post_ops: ops.into_boxed_slice(),
+                span: call.span,
             }))
         },
         _ => {
             Expr::CallExpr(Box::new(crate::CallExpr{
                 expr: expr,
                 post_ops: Box::new([op]),
+                span: Default::default(),
             }))
         },
     }
@@ -179,7 +183,8 @@ pub fn _add_assign(left: impl Into<LValue>, right: impl Into<Expr>) -> Expr {
 pub fn _var(name: &str) -> Expr {
     Expr::Variable(Box::new(Variable{
         name: Rc::from(name),
-        pointer: Rc::new(OnceCell::new())
+        pointer: Rc::new(OnceCell::new()),
+        span: Default::default(),
     }))
 }
 
@@ -187,8 +192,11 @@ pub fn _function_raw(name: &str,scope: Option<crate::FunctionScope>, params: &[P
     crate::Function{
         name: crate::FunctionName::Named(Rc::from(name)),
         parameters: Box::from(params),
+        signature: None,
         body: crate::ExprOrBlock::Block(body.into()),
         scope: scope.map(Into::into),
+        span: Default::default(),
+        comments: Box::new([]),
     }
 }
 
@@ -196,8 +204,11 @@ pub fn _function(name: &str,scope: Option<crate::FunctionScope>, params: &[Patte
     Expr::Function(Box::new(crate::Function{
         name: crate::FunctionName::Named(Rc::from(name)),
         parameters: Box::from(params),
+        signature: None,
         body: crate::ExprOrBlock::Block(body.into()),
         scope: scope.map(Into::into),
+        span: Default::default(),
+        comments: Box::new([]),
     }))
 }
 
@@ -205,8 +216,11 @@ pub fn _function_anonymous(params: &[Pattern], body: impl Into<Block>) -> Expr {
     Expr::Function(Box::new(crate::Function{
         name: crate::FunctionName::Anonymous,
         parameters: Box::from(params),
+        signature: None,
         body: crate::ExprOrBlock::Block(body.into()),
         scope: None,
+        span: Default::default(),
+        comments: Box::new([]),
     }))
 }
 
@@ -214,8 +228,11 @@ pub fn arrow_expr(params: &[Pattern], body: impl Into<Expr>) -> Expr {
     Expr::Function(Box::new(crate::Function{
         name: crate::FunctionName::Arrow,
         parameters: Box::from(params),
+        signature: None,
         body: crate::ExprOrBlock::Expr(body.into()),
         scope: None,
+        span: Default::default(),
+        comments: Box::new([]),
     }))
 }
 
@@ -223,8 +240,11 @@ pub fn arrow_block(params: &[Pattern], body: impl Into<Block>) -> Expr {
     Expr::Function(Box::new(crate::Function{
         name: crate::FunctionName::Arrow,
         parameters: Box::from(params),
+        signature: None,
         body: crate::ExprOrBlock::Block(body.into()),
         scope: None,
+        span: Default::default(),
+        comments: Box::new([]),
     }))
 }
 
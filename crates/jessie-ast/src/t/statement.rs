@@ -3,30 +3,33 @@ use std::{rc::Rc, cell::RefCell};
 use crate::{Expr, Statement, Pattern, Declaration, VariableDeclaration, IfStatement, Block, ElseArm, Function};
 
 pub fn _const(pattern: impl Into<Pattern>, value: impl Into<Expr>) -> Statement {
-    Statement::LocalDeclaration(Box::new(Declaration::Const(Box::new([VariableDeclaration{pattern: pattern.into(), value: Some(value.into())}]))))
+    Statement::LocalDeclaration(Box::new(Declaration::Const(Box::new([VariableDeclaration{pattern: pattern.into(), type_ann: None, value: Some(value.into())}]))))
 }
 
 pub fn _let(pattern: impl Into<Pattern>, value: Option<impl Into<Expr>>) -> Statement {
-    Statement::LocalDeclaration(Box::new(Declaration::Let(Box::new([VariableDeclaration{pattern: pattern.into(), value: value.map(Into::into)}]))))
+    Statement::LocalDeclaration(Box::new(Declaration::Let(Box::new([VariableDeclaration{pattern: pattern.into(), type_ann: None, value: value.map(Into::into)}]))))
 }
 
 pub fn _decl_function(name: &str, scope: Option<crate::FunctionScope>, params: &[Pattern], body: impl Into<Block>) -> Statement {
     Statement::LocalDeclaration(Box::new(Declaration::Function(Rc::new(RefCell::new(Function{
         name: crate::FunctionName::Named(Rc::from(name)),
         parameters: params.into(),
+        signature: None,
         body: crate::ExprOrBlock::Block(body.into()),
         scope: scope.map(Into::into),
+        span: Default::default(),
+        comments: Box::new([]),
     })))))
 }
 
 pub fn _block(statements: &[Statement]) -> Statement {
-    Statement::Block(Box::new(Block {
-        statements: Box::from(statements),
-        declarations: statements.iter().filter_map(|stmt| match stmt {
+    Statement::Block(Box::new(Block::new(
+        statements.iter().filter_map(|stmt| match stmt {
             Statement::LocalDeclaration(decl) => Some(*decl.clone()),
             _ => None,
         }).collect::<Vec<Declaration>>().into_boxed_slice(),
-    }))
+        Box::from(statements),
+    )))
 }
 
 
@@ -0,0 +1,46 @@
+use std::rc::Rc;
+
+use crate::Span;
+
+// Source comments, kept only when the lexer and the parser are asked to.
+// The lexer attaches a comment to the token after it, or to the token before it when it follows that token on the
+// same line. The parser then moves it to the innermost statement, function or property starting or ending with that
+// token, or to the enclosing block, record or module when it comes right before the closing brace.
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CommentKind {
+    Line, // `// text`
+    Block, // `/* text */`
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Comment {
+    pub kind: CommentKind,
+    // the text between the delimiters, as written
+    pub text: Rc<str>,
+    pub span: Span,
+    // on the same line after the node, rather than before it
+    pub trailing: bool,
+}
+
+// Comments of a node in source order
+pub type Comments = Box<[Comment]>;
+
+impl Comment {
+    // `/** text */`
+    pub fn is_doc(&self) -> bool {
+        self.kind == CommentKind::Block && self.text.starts_with('*')
+    }
+
+    // The @tags of a doc comment, e.g. export for `/** @export */`
+    pub fn pragmas(&self) -> impl Iterator<Item = &str> {
+        let text = if self.is_doc() { &self.text[..] } else { "" };
+        text.split(|c: char| c.is_whitespace() || c == '*')
+            .filter_map(|word| word.strip_prefix('@'))
+            .filter(|name| !name.is_empty())
+    }
+}
+
+pub fn has_pragma(comments: &[Comment], name: &str) -> bool {
+    comments.iter().any(|comment| comment.pragmas().any(|pragma| pragma == name))
+}
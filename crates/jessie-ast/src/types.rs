@@ -0,0 +1,97 @@
+use std::rc::Rc;
+
+use crate::DataLiteral;
+
+// Tessie type annotations, a TypeScript-style subset.
+// They are kept as written for the type checker to use as ground truth, and have no effect on evaluation.
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum TypeAnn {
+    Any,
+    Unknown,
+    Never,
+    Void,
+    Undefined,
+    Null,
+    Boolean,
+    Number,
+    String,
+    Bigint,
+    Literal(Box<DataLiteral>), // 'a', 1, true
+    Named(Box<NamedType>), // alias, interface or type parameter
+    Array(Box<TypeAnn>), // T[]
+    Tuple(Box<[TypeAnn]>), // [T, U]
+    Record(Box<[PropType]>), // { a: T; b?: U }
+    Function(Box<FunctionType>), // (a: T) => U
+    Union(Box<[TypeAnn]>),
+    Intersection(Box<[TypeAnn]>),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NamedType {
+    pub name: Rc<str>,
+    pub args: Box<[TypeAnn]>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct PropType {
+    pub name: Rc<str>,
+    pub optional: bool,
+    pub ty: TypeAnn,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct FunctionType {
+    pub parameters: Box<[ParamType]>,
+    pub return_type: TypeAnn,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParamType {
+    pub name: Rc<str>,
+    pub is_rest: bool,
+    pub ty: TypeAnn,
+}
+
+// Annotations of a function, one for each parameter.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Signature {
+    pub type_params: Box<[Rc<str>]>,
+    pub parameters: Box<[Option<TypeAnn>]>,
+    pub return_type: Option<TypeAnn>,
+}
+
+impl Signature {
+    // None when nothing is annotated, so that untyped functions stay the same as in Jessie
+    pub fn new(type_params: Box<[Rc<str>]>, parameters: Box<[Option<TypeAnn>]>, return_type: Option<TypeAnn>) -> Option<Box<Signature>> {
+        if type_params.is_empty() && parameters.iter().all(Option::is_none) && return_type.is_none() {
+            return None
+        }
+        Some(Box::new(Signature { type_params, parameters, return_type }))
+    }
+
+    pub fn parameter(&self, index: usize) -> Option<&TypeAnn> {
+        self.parameters.get(index).and_then(Option::as_ref)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum TypeDeclaration {
+    Alias(Box<TypeAlias>), // type Name<T> = T;
+    Interface(Box<Interface>), // interface Name<T> extends Base { a: T; }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TypeAlias {
+    pub name: Rc<str>,
+    pub type_params: Box<[Rc<str>]>,
+    pub ty: TypeAnn,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Interface {
+    pub name: Rc<str>,
+    pub type_params: Box<[Rc<str>]>,
+    pub extends: Box<[NamedType]>,
+    pub members: Box<[PropType]>,
+}
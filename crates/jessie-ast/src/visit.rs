@@ -0,0 +1,613 @@
+// Visitors over the AST.
+//
+// Every node type has a visit_* method, which is the one to override, and a walk_* method, which visits the children
+// of the node in source order. An overriding visit_* calls the matching walk_* to keep descending into the children.
+// Visit takes shared references and VisitMut mutable ones, both are generated from the same definition below.
+//
+// The declarations of a block are clones of its statements, and functions are shared with them, so walk_block only
+// walks the statements. Likewise walk_function_scope only visits the variables of the scope, the functions in it are
+// walked at their declaration.
+
+use crate::*;
+
+macro_rules! visitor {
+    ($Visit:ident, $iter:ident, $borrow:ident, $($mut:tt)?) => {
+        pub trait $Visit {
+            type Error;
+
+            fn visit_script(&mut self, script: &$($mut)? Script) -> Result<(), Self::Error> {
+                self.walk_script(script)
+            }
+
+            fn walk_script(&mut self, script: &$($mut)? Script) -> Result<(), Self::Error> {
+                self.visit_block(&$($mut)? script.statements)
+            }
+
+            fn visit_module(&mut self, module: &$($mut)? Module) -> Result<(), Self::Error> {
+                self.walk_module(module)
+            }
+
+            fn walk_module(&mut self, module: &$($mut)? Module) -> Result<(), Self::Error> {
+                for item in module.body.$iter() {
+                    self.visit_module_item(item)?;
+                }
+                if let Some(scope) = &$($mut)? module.scope {
+                    self.visit_function_scope(scope)?;
+                }
+                Ok(())
+            }
+
+            fn visit_module_item(&mut self, item: &$($mut)? ModuleItem) -> Result<(), Self::Error> {
+                self.walk_module_item(item)
+            }
+
+            fn walk_module_item(&mut self, item: &$($mut)? ModuleItem) -> Result<(), Self::Error> {
+                match item {
+                    ModuleItem::ImportDeclaration(import) => self.visit_import_declaration(import),
+                    ModuleItem::ModuleDeclaration(decl) => self.visit_declaration(&$($mut)? decl.declaration),
+                    ModuleItem::TypeDeclaration(_, decl) => self.visit_type_declaration(decl),
+                    ModuleItem::Error(_) => Ok(()),
+                }
+            }
+
+            fn visit_import_declaration(&mut self, import: &$($mut)? ImportDeclaration) -> Result<(), Self::Error> {
+                self.walk_import_declaration(import)
+            }
+
+            fn walk_import_declaration(&mut self, import: &$($mut)? ImportDeclaration) -> Result<(), Self::Error> {
+                match &$($mut)? import.import_clause {
+                    ImportClause::Namespace(var) | ImportClause::Default(var) => self.visit_variable(var),
+                    ImportClause::Named(names) => {
+                        for (_, var) in names.$iter() {
+                            self.visit_variable(var)?;
+                        }
+                        Ok(())
+                    },
+                }
+            }
+
+            // Statements
+
+            fn visit_statement(&mut self, statement: &$($mut)? Statement) -> Result<(), Self::Error> {
+                self.walk_statement(statement)
+            }
+
+            fn walk_statement(&mut self, statement: &$($mut)? Statement) -> Result<(), Self::Error> {
+                match statement {
+                    Statement::LocalDeclaration(decl) => self.visit_declaration(decl),
+                    Statement::Block(block) => self.visit_block(block),
+                    Statement::IfStatement(stmt) => self.visit_if_statement(stmt),
+                    Statement::ForOfStatement(stmt) => self.visit_for_of_statement(stmt),
+                    Statement::WhileStatement(stmt) => self.visit_while_statement(stmt),
+                    Statement::Return(expr) | Statement::Throw(expr) | Statement::ExprStatement(expr) => self.visit_expr(expr),
+                    Statement::TryStatement(stmt) => self.visit_try_statement(stmt),
+                    Statement::SwitchStatement(stmt) => self.visit_switch_statement(stmt),
+                    Statement::TypeDeclaration(decl) => self.visit_type_declaration(decl),
+                    Statement::Continue | Statement::Break | Statement::ReturnEmpty | Statement::Error => Ok(()),
+                }
+            }
+
+            fn visit_block(&mut self, block: &$($mut)? Block) -> Result<(), Self::Error> {
+                self.walk_block(block)
+            }
+
+            fn walk_block(&mut self, block: &$($mut)? Block) -> Result<(), Self::Error> {
+                for statement in block.statements.$iter() {
+                    self.visit_statement(statement)?;
+                }
+                Ok(())
+            }
+
+            fn visit_declaration(&mut self, decl: &$($mut)? Declaration) -> Result<(), Self::Error> {
+                self.walk_declaration(decl)
+            }
+
+            fn walk_declaration(&mut self, decl: &$($mut)? Declaration) -> Result<(), Self::Error> {
+                match decl {
+                    Declaration::Const(decls) | Declaration::Let(decls) => {
+                        for decl in decls.$iter() {
+                            self.visit_variable_declaration(decl)?;
+                        }
+                        Ok(())
+                    },
+                    Declaration::Function(func) => self.visit_function(&$($mut)? *func.$borrow()),
+                }
+            }
+
+            fn visit_variable_declaration(&mut self, decl: &$($mut)? VariableDeclaration) -> Result<(), Self::Error> {
+                self.walk_variable_declaration(decl)
+            }
+
+            fn walk_variable_declaration(&mut self, decl: &$($mut)? VariableDeclaration) -> Result<(), Self::Error> {
+                self.visit_pattern(&$($mut)? decl.pattern)?;
+                if let Some(ty) = &$($mut)? decl.type_ann {
+                    self.visit_type_ann(ty)?;
+                }
+                if let Some(value) = &$($mut)? decl.value {
+                    self.visit_expr(value)?;
+                }
+                Ok(())
+            }
+
+            fn visit_if_statement(&mut self, stmt: &$($mut)? IfStatement) -> Result<(), Self::Error> {
+                self.walk_if_statement(stmt)
+            }
+
+            fn walk_if_statement(&mut self, stmt: &$($mut)? IfStatement) -> Result<(), Self::Error> {
+                self.visit_expr(&$($mut)? stmt.condition)?;
+                self.visit_block(&$($mut)? stmt.consequent)?;
+                match &$($mut)? stmt.alternate {
+                    ElseArm::NoElse => Ok(()),
+                    ElseArm::Else(block) => self.visit_block(block),
+                    ElseArm::ElseIf(elseif) => self.visit_if_statement(elseif),
+                }
+            }
+
+            fn visit_while_statement(&mut self, stmt: &$($mut)? WhileStatement) -> Result<(), Self::Error> {
+                self.walk_while_statement(stmt)
+            }
+
+            fn walk_while_statement(&mut self, stmt: &$($mut)? WhileStatement) -> Result<(), Self::Error> {
+                self.visit_expr(&$($mut)? stmt.condition)?;
+                self.visit_block(&$($mut)? stmt.body)
+            }
+
+            fn visit_for_of_statement(&mut self, stmt: &$($mut)? ForOfStatement) -> Result<(), Self::Error> {
+                self.walk_for_of_statement(stmt)
+            }
+
+            fn walk_for_of_statement(&mut self, stmt: &$($mut)? ForOfStatement) -> Result<(), Self::Error> {
+                self.visit_pattern(&$($mut)? stmt.pattern)?;
+                self.visit_expr(&$($mut)? stmt.iterable)?;
+                self.visit_block(&$($mut)? stmt.body)
+            }
+
+            fn visit_switch_statement(&mut self, stmt: &$($mut)? SwitchStatement) -> Result<(), Self::Error> {
+                self.walk_switch_statement(stmt)
+            }
+
+            fn walk_switch_statement(&mut self, stmt: &$($mut)? SwitchStatement) -> Result<(), Self::Error> {
+                self.visit_expr(&$($mut)? stmt.discriminant)?;
+                for clause in stmt.clauses.$iter() {
+                    self.visit_switch_clause(clause)?;
+                }
+                Ok(())
+            }
+
+            fn visit_switch_clause(&mut self, clause: &$($mut)? SwitchClause) -> Result<(), Self::Error> {
+                self.walk_switch_clause(clause)
+            }
+
+            fn walk_switch_clause(&mut self, clause: &$($mut)? SwitchClause) -> Result<(), Self::Error> {
+                for label in clause.labels.$iter() {
+                    if let CaseLabel::Case(expr) = label {
+                        self.visit_expr(expr)?;
+                    }
+                }
+                self.visit_block(&$($mut)? clause.body)
+            }
+
+            fn visit_try_statement(&mut self, stmt: &$($mut)? TryStatement) -> Result<(), Self::Error> {
+                self.walk_try_statement(stmt)
+            }
+
+            fn walk_try_statement(&mut self, stmt: &$($mut)? TryStatement) -> Result<(), Self::Error> {
+                self.visit_block(&$($mut)? stmt.block)?;
+                if let Some(catch) = &$($mut)? stmt.catch {
+                    self.visit_catch_clause(catch)?;
+                }
+                if let Some(finally) = &$($mut)? stmt.finally {
+                    self.visit_block(finally)?;
+                }
+                Ok(())
+            }
+
+            fn visit_catch_clause(&mut self, catch: &$($mut)? CatchClause) -> Result<(), Self::Error> {
+                self.walk_catch_clause(catch)
+            }
+
+            fn walk_catch_clause(&mut self, catch: &$($mut)? CatchClause) -> Result<(), Self::Error> {
+                self.visit_pattern(&$($mut)? catch.param)?;
+                self.visit_block(&$($mut)? catch.body)
+            }
+
+            // Expressions
+
+            fn visit_expr(&mut self, expr: &$($mut)? Expr) -> Result<(), Self::Error> {
+                self.walk_expr(expr)
+            }
+
+            fn walk_expr(&mut self, expr: &$($mut)? Expr) -> Result<(), Self::Error> {
+                match expr {
+                    Expr::DataLiteral(literal) => self.visit_data_literal(&$($mut)? literal.data),
+                    Expr::Array(array) => self.visit_array(array),
+                    Expr::Record(record) => self.visit_record(record),
+                    Expr::Function(func) => self.visit_function(func),
+                    Expr::Assignment(assignment) => self.visit_assignment(assignment),
+                    Expr::CondExpr(cond) => self.visit_cond_expr(cond),
+                    Expr::BinaryExpr(binary) => self.visit_binary_expr(binary),
+                    Expr::UnaryExpr(unary) => self.visit_unary_expr(unary),
+                    Expr::CallExpr(call) => self.visit_call_expr(call),
+                    Expr::QuasiExpr(quasi) => self.visit_quasi_expr(quasi),
+                    Expr::ParenedExpr(expr) | Expr::Spread(expr) => self.visit_expr(expr),
+                    Expr::Variable(var) => self.visit_variable(var),
+                }
+            }
+
+            fn visit_data_literal(&mut self, _literal: &$($mut)? DataLiteral) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn visit_array(&mut self, array: &$($mut)? Array) -> Result<(), Self::Error> {
+                self.walk_array(array)
+            }
+
+            fn walk_array(&mut self, array: &$($mut)? Array) -> Result<(), Self::Error> {
+                for element in array.0.$iter() {
+                    self.visit_expr(element)?;
+                }
+                Ok(())
+            }
+
+            fn visit_record(&mut self, record: &$($mut)? Record) -> Result<(), Self::Error> {
+                self.walk_record(record)
+            }
+
+            fn walk_record(&mut self, record: &$($mut)? Record) -> Result<(), Self::Error> {
+                for prop in record.0.$iter() {
+                    self.visit_prop_def(prop)?;
+                }
+                Ok(())
+            }
+
+            fn visit_prop_def(&mut self, prop: &$($mut)? PropDef) -> Result<(), Self::Error> {
+                self.walk_prop_def(prop)
+            }
+
+            fn walk_prop_def(&mut self, prop: &$($mut)? PropDef) -> Result<(), Self::Error> {
+                match prop {
+                    PropDef::KeyValue(_, value) | PropDef::Spread(value) => self.visit_expr(value),
+                    PropDef::Shorthand(_, var) => self.visit_variable(var),
+                    PropDef::Getter(func) | PropDef::Setter(func) | PropDef::MethodDef(func) => self.visit_function(func),
+                    PropDef::Computed(key, value) => {
+                        self.visit_expr(key)?;
+                        self.visit_expr(value)
+                    },
+                }
+            }
+
+            fn visit_function(&mut self, func: &$($mut)? Function) -> Result<(), Self::Error> {
+                self.walk_function(func)
+            }
+
+            fn walk_function(&mut self, func: &$($mut)? Function) -> Result<(), Self::Error> {
+                for param in func.parameters.$iter() {
+                    self.visit_pattern(param)?;
+                }
+                if let Some(signature) = &$($mut)? func.signature {
+                    self.visit_signature(signature)?;
+                }
+                match &$($mut)? func.body {
+                    ExprOrBlock::Expr(expr) => self.visit_expr(expr)?,
+                    ExprOrBlock::Block(block) => self.visit_block(block)?,
+                }
+                if let Some(scope) = &$($mut)? func.scope {
+                    self.visit_function_scope(scope)?;
+                }
+                Ok(())
+            }
+
+            fn visit_function_scope(&mut self, scope: &$($mut)? FunctionScope) -> Result<(), Self::Error> {
+                self.walk_function_scope(scope)
+            }
+
+            fn walk_function_scope(&mut self, scope: &$($mut)? FunctionScope) -> Result<(), Self::Error> {
+                for param in scope.parameters.$iter() {
+                    self.visit_local_variable(param)?;
+                }
+                for capture in scope.captures.$iter() {
+                    self.visit_variable(capture)?;
+                }
+                for local in scope.locals.$iter() {
+                    self.visit_local_variable(local)?;
+                }
+                for (var, _) in scope.functions.$iter() {
+                    self.visit_variable(var)?;
+                }
+                Ok(())
+            }
+
+            fn visit_local_variable(&mut self, local: &$($mut)? LocalVariable) -> Result<(), Self::Error> {
+                self.walk_local_variable(local)
+            }
+
+            fn walk_local_variable(&mut self, local: &$($mut)? LocalVariable) -> Result<(), Self::Error> {
+                self.visit_variable(&$($mut)? local.var)
+            }
+
+            fn visit_assignment(&mut self, assignment: &$($mut)? Assignment) -> Result<(), Self::Error> {
+                self.walk_assignment(assignment)
+            }
+
+            fn walk_assignment(&mut self, assignment: &$($mut)? Assignment) -> Result<(), Self::Error> {
+                self.visit_lvalue(&$($mut)? assignment.1)?;
+                self.visit_expr(&$($mut)? assignment.2)
+            }
+
+            fn visit_lvalue(&mut self, lvalue: &$($mut)? LValue) -> Result<(), Self::Error> {
+                self.walk_lvalue(lvalue)
+            }
+
+            fn walk_lvalue(&mut self, lvalue: &$($mut)? LValue) -> Result<(), Self::Error> {
+                match lvalue {
+                    LValue::CallLValue(call) => self.visit_call_lvalue(call),
+                    LValue::Variable(var) => self.visit_variable(var),
+                }
+            }
+
+            fn visit_call_lvalue(&mut self, call: &$($mut)? CallLValue) -> Result<(), Self::Error> {
+                self.walk_call_lvalue(call)
+            }
+
+            fn walk_call_lvalue(&mut self, call: &$($mut)? CallLValue) -> Result<(), Self::Error> {
+                self.visit_expr(&$($mut)? call.expr)?;
+                for op in call.post_ops.$iter() {
+                    self.visit_lvalue_call_post_op(op)?;
+                }
+                Ok(())
+            }
+
+            fn visit_lvalue_call_post_op(&mut self, op: &$($mut)? LValueCallPostOp) -> Result<(), Self::Error> {
+                self.walk_lvalue_call_post_op(op)
+            }
+
+            fn walk_lvalue_call_post_op(&mut self, op: &$($mut)? LValueCallPostOp) -> Result<(), Self::Error> {
+                match op {
+                    LValueCallPostOp::Index(index) => self.visit_expr(index),
+                    LValueCallPostOp::Member(_) => Ok(()),
+                }
+            }
+
+            fn visit_cond_expr(&mut self, cond: &$($mut)? CondExpr) -> Result<(), Self::Error> {
+                self.walk_cond_expr(cond)
+            }
+
+            fn walk_cond_expr(&mut self, cond: &$($mut)? CondExpr) -> Result<(), Self::Error> {
+                self.visit_expr(&$($mut)? cond.0)?;
+                self.visit_expr(&$($mut)? cond.1)?;
+                self.visit_expr(&$($mut)? cond.2)
+            }
+
+            fn visit_binary_expr(&mut self, binary: &$($mut)? BinaryExpr) -> Result<(), Self::Error> {
+                self.walk_binary_expr(binary)
+            }
+
+            fn walk_binary_expr(&mut self, binary: &$($mut)? BinaryExpr) -> Result<(), Self::Error> {
+                self.visit_expr(&$($mut)? binary.1)?;
+                self.visit_expr(&$($mut)? binary.2)
+            }
+
+            fn visit_unary_expr(&mut self, unary: &$($mut)? UnaryExpr) -> Result<(), Self::Error> {
+                self.walk_unary_expr(unary)
+            }
+
+            fn walk_unary_expr(&mut self, unary: &$($mut)? UnaryExpr) -> Result<(), Self::Error> {
+                self.visit_expr(&$($mut)? unary.expr)
+            }
+
+            fn visit_call_expr(&mut self, call: &$($mut)? CallExpr) -> Result<(), Self::Error> {
+                self.walk_call_expr(call)
+            }
+
+            fn walk_call_expr(&mut self, call: &$($mut)? CallExpr) -> Result<(), Self::Error> {
+                self.visit_expr(&$($mut)? call.expr)?;
+                for op in call.post_ops.$iter() {
+                    self.visit_call_post_op(op)?;
+                }
+                Ok(())
+            }
+
+            fn visit_call_post_op(&mut self, op: &$($mut)? CallPostOp) -> Result<(), Self::Error> {
+                self.walk_call_post_op(op)
+            }
+
+            fn walk_call_post_op(&mut self, op: &$($mut)? CallPostOp) -> Result<(), Self::Error> {
+                match op {
+                    CallPostOp::Index(index) | CallPostOp::OptionalIndex(index) => self.visit_expr(index),
+                    CallPostOp::Member(_) | CallPostOp::OptionalMember(_) => Ok(()),
+                    CallPostOp::QuasiExpr(quasi) => self.visit_quasi_expr(quasi),
+                    CallPostOp::Call(args) | CallPostOp::OptionalCall(args) => {
+                        for arg in args.$iter() {
+                            self.visit_expr(arg)?;
+                        }
+                        Ok(())
+                    },
+                }
+            }
+
+            fn visit_quasi_expr(&mut self, quasi: &$($mut)? QuasiExpr) -> Result<(), Self::Error> {
+                self.walk_quasi_expr(quasi)
+            }
+
+            fn walk_quasi_expr(&mut self, quasi: &$($mut)? QuasiExpr) -> Result<(), Self::Error> {
+                for expr in quasi.exprs.$iter() {
+                    self.visit_expr(expr)?;
+                }
+                Ok(())
+            }
+
+            fn visit_variable(&mut self, _var: &$($mut)? Variable) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            // Patterns
+
+            fn visit_pattern(&mut self, pattern: &$($mut)? Pattern) -> Result<(), Self::Error> {
+                self.walk_pattern(pattern)
+            }
+
+            fn walk_pattern(&mut self, pattern: &$($mut)? Pattern) -> Result<(), Self::Error> {
+                match pattern {
+                    Pattern::Rest(pattern) => self.visit_pattern(pattern),
+                    Pattern::Optional(optional) => self.visit_optional_pattern(optional),
+                    Pattern::ArrayPattern(array) => self.visit_array_pattern(array),
+                    Pattern::RecordPattern(record) => self.visit_record_pattern(record),
+                    Pattern::Variable(var) => self.visit_variable(var),
+                }
+            }
+
+            fn visit_optional_pattern(&mut self, optional: &$($mut)? OptionalPattern) -> Result<(), Self::Error> {
+                self.walk_optional_pattern(optional)
+            }
+
+            fn walk_optional_pattern(&mut self, optional: &$($mut)? OptionalPattern) -> Result<(), Self::Error> {
+                self.visit_lvalue_optional(&$($mut)? optional.1)?;
+                self.visit_expr(&$($mut)? optional.2)
+            }
+
+            fn visit_lvalue_optional(&mut self, lvalue: &$($mut)? LValueOptional) -> Result<(), Self::Error> {
+                self.walk_lvalue_optional(lvalue)
+            }
+
+            fn walk_lvalue_optional(&mut self, lvalue: &$($mut)? LValueOptional) -> Result<(), Self::Error> {
+                match lvalue {
+                    LValueOptional::ArrayPattern(array) => self.visit_array_pattern(array),
+                    LValueOptional::RecordPattern(record) => self.visit_record_pattern(record),
+                    LValueOptional::Variable(var) => self.visit_variable(var),
+                }
+            }
+
+            fn visit_array_pattern(&mut self, array: &$($mut)? ArrayPattern) -> Result<(), Self::Error> {
+                self.walk_array_pattern(array)
+            }
+
+            fn walk_array_pattern(&mut self, array: &$($mut)? ArrayPattern) -> Result<(), Self::Error> {
+                for element in array.0.$iter() {
+                    self.visit_pattern(element)?;
+                }
+                Ok(())
+            }
+
+            fn visit_record_pattern(&mut self, record: &$($mut)? RecordPattern) -> Result<(), Self::Error> {
+                self.walk_record_pattern(record)
+            }
+
+            fn walk_record_pattern(&mut self, record: &$($mut)? RecordPattern) -> Result<(), Self::Error> {
+                for prop in record.0.$iter() {
+                    self.visit_prop_param(prop)?;
+                }
+                Ok(())
+            }
+
+            fn visit_prop_param(&mut self, prop: &$($mut)? PropParam) -> Result<(), Self::Error> {
+                self.walk_prop_param(prop)
+            }
+
+            fn walk_prop_param(&mut self, prop: &$($mut)? PropParam) -> Result<(), Self::Error> {
+                match prop {
+                    PropParam::KeyValue(_, pattern) => self.visit_pattern(pattern),
+                    PropParam::Shorthand(_, var) | PropParam::Rest(var) => self.visit_variable(var),
+                    PropParam::Computed(key, pattern) => {
+                        self.visit_expr(key)?;
+                        self.visit_pattern(pattern)
+                    },
+                }
+            }
+
+            // Tessie types
+
+            fn visit_signature(&mut self, signature: &$($mut)? Signature) -> Result<(), Self::Error> {
+                self.walk_signature(signature)
+            }
+
+            fn walk_signature(&mut self, signature: &$($mut)? Signature) -> Result<(), Self::Error> {
+                for ty in signature.parameters.$iter().flatten() {
+                    self.visit_type_ann(ty)?;
+                }
+                if let Some(ty) = &$($mut)? signature.return_type {
+                    self.visit_type_ann(ty)?;
+                }
+                Ok(())
+            }
+
+            fn visit_type_declaration(&mut self, decl: &$($mut)? TypeDeclaration) -> Result<(), Self::Error> {
+                self.walk_type_declaration(decl)
+            }
+
+            fn walk_type_declaration(&mut self, decl: &$($mut)? TypeDeclaration) -> Result<(), Self::Error> {
+                match decl {
+                    TypeDeclaration::Alias(alias) => self.visit_type_ann(&$($mut)? alias.ty),
+                    TypeDeclaration::Interface(interface) => {
+                        for base in interface.extends.$iter() {
+                            self.visit_named_type(base)?;
+                        }
+                        for member in interface.members.$iter() {
+                            self.visit_prop_type(member)?;
+                        }
+                        Ok(())
+                    },
+                }
+            }
+
+            fn visit_type_ann(&mut self, ty: &$($mut)? TypeAnn) -> Result<(), Self::Error> {
+                self.walk_type_ann(ty)
+            }
+
+            fn walk_type_ann(&mut self, ty: &$($mut)? TypeAnn) -> Result<(), Self::Error> {
+                match ty {
+                    TypeAnn::Literal(literal) => self.visit_data_literal(literal),
+                    TypeAnn::Named(named) => self.visit_named_type(named),
+                    TypeAnn::Array(element) => self.visit_type_ann(element),
+                    TypeAnn::Tuple(types) | TypeAnn::Union(types) | TypeAnn::Intersection(types) => {
+                        for ty in types.$iter() {
+                            self.visit_type_ann(ty)?;
+                        }
+                        Ok(())
+                    },
+                    TypeAnn::Record(props) => {
+                        for prop in props.$iter() {
+                            self.visit_prop_type(prop)?;
+                        }
+                        Ok(())
+                    },
+                    TypeAnn::Function(func) => self.visit_function_type(func),
+                    TypeAnn::Any | TypeAnn::Unknown | TypeAnn::Never | TypeAnn::Void | TypeAnn::Undefined | TypeAnn::Null |
+                    TypeAnn::Boolean | TypeAnn::Number | TypeAnn::String | TypeAnn::Bigint => Ok(()),
+                }
+            }
+
+            fn visit_named_type(&mut self, named: &$($mut)? NamedType) -> Result<(), Self::Error> {
+                self.walk_named_type(named)
+            }
+
+            fn walk_named_type(&mut self, named: &$($mut)? NamedType) -> Result<(), Self::Error> {
+                for arg in named.args.$iter() {
+                    self.visit_type_ann(arg)?;
+                }
+                Ok(())
+            }
+
+            fn visit_prop_type(&mut self, prop: &$($mut)? PropType) -> Result<(), Self::Error> {
+                self.walk_prop_type(prop)
+            }
+
+            fn walk_prop_type(&mut self, prop: &$($mut)? PropType) -> Result<(), Self::Error> {
+                self.visit_type_ann(&$($mut)? prop.ty)
+            }
+
+            fn visit_function_type(&mut self, func: &$($mut)? FunctionType) -> Result<(), Self::Error> {
+                self.walk_function_type(func)
+            }
+
+            fn walk_function_type(&mut self, func: &$($mut)? FunctionType) -> Result<(), Self::Error> {
+                for param in func.parameters.$iter() {
+                    self.visit_type_ann(&$($mut)? param.ty)?;
+                }
+                self.visit_type_ann(&$($mut)? func.return_type)
+            }
+        }
+    };
+}
+
+visitor!(Visit, iter, borrow,);
+visitor!(VisitMut, iter_mut, borrow_mut, mut);
@@ -0,0 +1,1144 @@
+// The char based lexer from before the lexer moved to byte offsets over &str, trimmed to the
+// entry point the benchmark uses and kept in step with the current tokens, so that the two
+// lexers can be compared token for token. Do not use it elsewhere.
+// The debug prints are stripped so that the benchmark measures the lexing itself.
+
+use jessie_ast::Span;
+
+use crate::lexer::Token;
+
+// Vec<char> with a cursor, what ParserState<char> used to be
+struct CharState {
+    input: Vec<char>,
+    pos: usize,
+}
+
+impl CharState {
+    fn new(input: Vec<char>) -> Self {
+        CharState { input, pos: 0 }
+    }
+
+    fn lookahead_1(&self) -> Option<char> {
+        self.input.get(self.pos).cloned()
+    }
+
+    fn lookahead_2(&self) -> Option<char> {
+        self.input.get(self.pos + 1).cloned()
+    }
+
+    fn lookahead_3(&self) -> Option<char> {
+        self.input.get(self.pos + 2).cloned()
+    }
+
+    fn lookahead_4(&self) -> Option<char> {
+        self.input.get(self.pos + 3).cloned()
+    }
+
+    fn lookahead_n(&self, n: usize) -> Option<char> {
+        self.input.get(self.pos + n - 1).cloned()
+    }
+
+    fn proceed(&mut self) -> Option<char> {
+        let result = self.input.get(self.pos).cloned();
+        if result.is_some() {
+            self.pos += 1;
+        }
+        result
+    }
+
+    fn proceed_with<R>(&mut self, r: R) -> R {
+        self.proceed();
+        r
+    }
+
+    fn consume(&mut self, s: Vec<char>) -> Result<(), String> {
+        let pos = self.pos;
+        for c in s {
+            if self.lookahead_1() == Some(c) {
+                self.proceed();
+            } else {
+                self.pos = pos;
+                return Err(format!("Expected {:?}, but got {:?}", c, self.lookahead_1()));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct Lexer {
+    state: CharState,
+    // (byte offset, line, column) of each character, plus the end of input
+    positions: Vec<(u32, u32, u32)>,
+    // span of each emitted token, parallel to the token stream
+    pub spans: Vec<Span>,
+    parenthesize_stack: Vec<ParenthesisIndex>,
+    // number of currently open braces
+    brace_depth: usize,
+    // brace depth at the start of each open template substitution
+    quasi_stack: Vec<usize>,
+}
+
+impl Lexer {
+    pub fn new(input: Vec<char>) -> Self {
+        let mut positions = Vec::with_capacity(input.len() + 1);
+        let (mut offset, mut line, mut column) = (0, 1, 1);
+        for c in input.iter() {
+            positions.push((offset, line, column));
+            offset += c.len_utf8() as u32;
+            if *c == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        positions.push((offset, line, column));
+
+        Lexer {
+            state: CharState::new(input),
+            positions,
+            spans: Vec::new(),
+            parenthesize_stack: Vec::new(),
+            brace_depth: 0,
+            quasi_stack: Vec::new(),
+        }
+    }
+
+    pub fn lookahead_1(&self) -> Option<char> {
+        self.state.lookahead_1()
+    }
+
+    pub fn lookahead_2(&self) -> Option<char> {
+        self.state.lookahead_2()
+    }
+
+    pub fn lookahead_3(&self) -> Option<char> {
+        self.state.lookahead_3()
+    }
+
+    pub fn lookahead_4(&self) -> Option<char> {
+        self.state.lookahead_4()
+    }
+
+    pub fn lookahead_n(&self, n: usize) -> Option<char> {
+        self.state.lookahead_n(n)
+    }
+
+    pub fn proceed_with(&mut self, token: Token) -> Token {
+        self.state.proceed_with(token)
+    }
+
+    pub fn proceed(&mut self) -> Option<char> {
+        self.state.proceed()
+    }
+
+    pub fn consume(&mut self, token: Vec<char>) -> Result<(), String> {
+        self.state.consume(token)
+    }
+    
+    pub fn position(&self) -> usize {
+        self.state.pos
+    }
+
+    // span from the character index start to the current position
+    pub fn span_from(&self, start: usize) -> Span {
+        let (start_offset, line, column) = self.positions[start.min(self.positions.len() - 1)];
+        let (end_offset, _, _) = self.positions[self.state.pos.min(self.positions.len() - 1)];
+        Span::new(start_offset, end_offset, line, column)
+    }
+
+    pub fn open_paren(&mut self, index: usize) {
+        self.parenthesize_stack.push(ParenthesisIndex(index));
+    }
+
+    pub fn close_paren(&mut self) -> Result<usize, String> {
+        match self.parenthesize_stack.pop() {
+            Some(ParenthesisIndex(index)) => Ok(index),
+            _ => Err("Parenthesis mismatch".to_string()),
+        }
+    }
+}
+
+pub struct ParenthesisIndex(usize);
+
+
+#[inline]
+fn given_keyword_or_ident(lexer: &mut Lexer, keyword: &'static str, token: Token) -> Result<Token, String> {
+    // if the following character of the (supposed) keyword can form an identifier, namely, an IdentifierPart or an escape, then it is not a keyword.
+    // proceed to identifier parsing
+    if lexer.lookahead_n(keyword.len()+1).map(|x| is_id_continue(x) || x == '\\').unwrap_or(false) {
+        return ident(lexer);   
+    }
+
+    if lexer.consume(keyword.chars().collect()).is_ok() {
+        Ok(token)
+    } else {
+        ident(lexer)
+    }
+}
+
+fn keyword_or_ident(lexer: &mut Lexer) -> Result<Token, String> {
+    // keywords in this list may or may not be used in the actual script,
+    // but need to be reserved for future use
+    /*
+    A: async, arguments, await
+    B: break, bigint, bool(ts)
+    C: case, catch, class, const, continue
+    D: delete, do, debugger, default
+    E: else, enum, extends, eval
+    F: finally, for, function
+    G: get
+    I: if, implements, import, in, instanceof, interface
+    L: let
+    N: new, null, number(ts)
+    P: package, private, protected, public
+    R: return, require
+    S: set, static, string(ts), super, switch, symbol(ts)
+    T: this, throw, try, typeof, true
+    U: undefined,
+    V: var, void
+    W: while, with, while
+    Y: yield
+    */
+
+
+    match lexer.lookahead_1() {
+        Some('a') => 
+            match lexer.lookahead_2() {
+                Some('s') => given_keyword_or_ident(lexer, &"async", Token::Async),
+                Some('r') => given_keyword_or_ident(lexer, &"arguments", Token::Arguments),
+                Some('w') => given_keyword_or_ident(lexer, &"await", Token::Await),
+                _ => ident(lexer),
+            },
+        Some('b') => {
+            match lexer.lookahead_2() {
+                Some('r') => given_keyword_or_ident(lexer, &"break", Token::Break),
+                // Some('i') => given_keyword_or_ident(lexer, &"bigint", Token::Bigint("".to_string())),
+                // Some('o') => given_keyword_or_ident(lexer, &"bool", Token::Identifier("bool".to_string())),
+                _ => ident(lexer),
+            }
+        },
+        Some('c') => {
+            match lexer.lookahead_2() {
+                Some('a') => match lexer.lookahead_3() {
+                    Some('s') => given_keyword_or_ident(lexer, &"case", Token::Case),
+                    Some('t') => given_keyword_or_ident(lexer, &"catch", Token::Catch),
+                    _ => ident(lexer),
+                },
+                Some('l') => given_keyword_or_ident(lexer, &"class", Token::Class),
+                Some('o') => match lexer.lookahead_3() {
+                    Some('n') => match lexer.lookahead_4() {
+                        Some('s') => given_keyword_or_ident(lexer, &"const", Token::Const),
+                        Some('t') => given_keyword_or_ident(lexer, &"continue", Token::Continue),
+                        _ => ident(lexer),
+                    },
+                    _ => ident(lexer),
+                },
+                _ => ident(lexer),
+            }
+        },
+        Some('d') => {
+            match lexer.lookahead_2() {
+                Some('e') => match lexer.lookahead_3() {
+                    Some('l') => given_keyword_or_ident(lexer, &"delete", Token::Delete),
+                    Some('b') => given_keyword_or_ident(lexer, &"debugger", Token::Debugger),
+                    Some('f') => given_keyword_or_ident(lexer, &"default", Token::Default),
+                    _ => ident(lexer),
+                },
+                Some('o') => given_keyword_or_ident(lexer, &"do", Token::Do),
+                _ => ident(lexer),
+            }
+        },
+        Some('e') => {
+            match lexer.lookahead_2() {
+                Some('l') => given_keyword_or_ident(lexer, &"else", Token::Else),
+                Some('n') => given_keyword_or_ident(lexer, &"enum", Token::Enum),
+                Some('x') => match lexer.lookahead_3() {
+                    Some('t') => given_keyword_or_ident(lexer, &"extends", Token::Extends),
+                    Some('p') => given_keyword_or_ident(lexer, &"export", Token::Export),
+                    _ => ident(lexer),
+                },
+                Some('v') => given_keyword_or_ident(lexer, &"eval", Token::Eval),
+                _ => ident(lexer),
+            }
+        },
+        Some('f') => {
+            match lexer.lookahead_2() {
+                Some('a') => given_keyword_or_ident(lexer, &"false", Token::False),
+                Some('i') => given_keyword_or_ident(lexer, &"finally", Token::Finally),
+                Some('o') => given_keyword_or_ident(lexer, &"for", Token::For),
+                Some('u') => given_keyword_or_ident(lexer, &"function", Token::Function),
+                _ => ident(lexer),
+            }
+        },
+        Some('g') => {
+            match lexer.lookahead_2() {
+                Some('e') => given_keyword_or_ident(lexer, &"get", Token::Get),
+                _ => ident(lexer),
+            }
+        },
+        Some('i') => {
+            match lexer.lookahead_2() {
+                Some('f') => given_keyword_or_ident(lexer, &"if", Token::If),
+                Some('m') => match lexer.lookahead_3() {
+                    Some('p') => match lexer.lookahead_4() {
+                        Some('l') => given_keyword_or_ident(lexer, &"implements", Token::Implements),
+                        Some('o') => given_keyword_or_ident(lexer, &"import", Token::Import),
+                        _ => ident(lexer),
+                    },
+                    _ => ident(lexer),
+                }
+                Some('n') => match lexer.lookahead_3() {
+                    Some('s') => given_keyword_or_ident(lexer, &"instanceof", Token::Instanceof),
+                    Some('t') => given_keyword_or_ident(lexer, &"interface", Token::Interface),
+                    _ => given_keyword_or_ident(lexer, &"in", Token::In),
+                },
+                _ => ident(lexer),
+            }
+        },
+        Some('l') => given_keyword_or_ident(lexer, &"let", Token::Let),
+        Some('n') => {
+            match lexer.lookahead_2() {
+                Some('e') => given_keyword_or_ident(lexer, &"new", Token::New),
+                Some('u') => match lexer.lookahead_3() {
+                    Some('l') => given_keyword_or_ident(lexer, &"null", Token::Null),
+                    // Some('m') => given_keyword_or_ident(lexer, &"number", Token::Identifier("number".to_string())),
+                    _ => ident(lexer),
+                },
+                _ => ident(lexer),
+            }
+        },
+        Some('p') => {
+            match lexer.lookahead_2() {
+                Some('a') => given_keyword_or_ident(lexer, &"package", Token::Package),
+                Some('r') => match lexer.lookahead_3() {
+                    Some('i') => given_keyword_or_ident(lexer, &"private", Token::Private),
+                    Some('o') => given_keyword_or_ident(lexer, &"protected", Token::Protected),
+                    _ => ident(lexer),
+                },
+                Some('u') => given_keyword_or_ident(lexer, &"public", Token::Public),
+                _ => ident(lexer),
+            }
+        },
+        Some('r') => {
+            match lexer.lookahead_2() {
+                Some('e') => match lexer.lookahead_3() {
+                    Some('t') => given_keyword_or_ident(lexer, &"return", Token::Return),
+                    Some('q') => given_keyword_or_ident(lexer, &"require", Token::Require),
+                    _ => ident(lexer),
+                },
+                _ => ident(lexer),
+            }
+        },
+        Some('s') => {
+            match lexer.lookahead_2() {
+                Some('e') => given_keyword_or_ident(lexer, &"set", Token::Set),
+                Some('t') => match lexer.lookahead_3() {
+                    Some('a') => given_keyword_or_ident(lexer, &"static", Token::Static),
+                    // Some('r') => given_keyword_or_ident(lexer, &"string", Token::String),
+                    _ => ident(lexer),
+                }
+                Some('u') => given_keyword_or_ident(lexer, &"super", Token::Super),
+                Some('w') => given_keyword_or_ident(lexer, &"switch", Token::Switch),
+                _ => ident(lexer),
+            }
+        },
+        Some('t') => {
+            match lexer.lookahead_2() {
+                Some('h') => match lexer.lookahead_3() {
+                    Some('i') => given_keyword_or_ident(lexer, &"this", Token::This),
+                    Some('r') => given_keyword_or_ident(lexer, &"throw", Token::Throw),
+                    _ => ident(lexer),
+                },
+                Some('r') => match lexer.lookahead_3() {
+                    Some('u') => given_keyword_or_ident(lexer, &"true", Token::True),
+                    Some('y') => given_keyword_or_ident(lexer, &"try", Token::Try),
+                    _ => ident(lexer),
+                },
+                Some('y') => given_keyword_or_ident(lexer, &"typeof", Token::TypeOf),
+                _ => ident(lexer),
+            }
+        },
+        Some('u') => given_keyword_or_ident(lexer, &"undefined", Token::Undefined),
+        Some('v') => {
+            match lexer.lookahead_2() {
+                Some('a') => given_keyword_or_ident(lexer, &"var", Token::Var),
+                Some('o') => given_keyword_or_ident(lexer, &"void", Token::Void),
+                _ => ident(lexer),
+            }
+        },
+        Some('w') => {
+            match lexer.lookahead_2() {
+                Some('h') => given_keyword_or_ident(lexer, &"while", Token::While),
+                Some('i') => given_keyword_or_ident(lexer, &"with", Token::With),
+                _ => ident(lexer),
+            }
+        },
+        Some('y') => {
+            match lexer.lookahead_2() {
+                Some('i') => given_keyword_or_ident(lexer, &"yield", Token::Yield),
+                _ => ident(lexer),
+            }
+        },
+        Some(_) => ident(lexer),
+        None => unreachable!("Lexer should not be empty"),
+    }
+}
+
+// Returns the tokens along with the span of each token.
+pub fn lex_jessie_with_spans(input: String) -> Result<(Vec<Token>, Vec<Span>), String> {
+    let mut result = Vec::new();
+    let mut lexer = Lexer::new(input.chars().collect());
+    if let Err(err) = lex(&mut lexer, &mut result) {
+        let span = lexer.span_from(lexer.position());
+        return Err(format!("{} at {}:{}", err, span.line, span.column))
+    }
+    Ok((result, lexer.spans))
+}
+
+fn table(lexer: &mut Lexer, result: &mut Vec<Token>, token: Token) -> Result<(), String> {
+    match token {
+        Token::LeftParen => {
+            lexer.open_paren(result.len()-1);
+        },
+        Token::RightParen => {
+            let open_index = lexer.close_paren()?;
+            let next_token = tokenize(lexer, result)?;
+            match next_token {
+                Token::FatArrow => {
+                    let close_index = result.len()-2; // one before the fat arrow 
+                    result[open_index] = Token::ArrowLeftParen;
+                    result[close_index] = Token::ArrowRightParen;
+                },
+                _ => table(lexer, result, next_token)?,
+            }
+        },
+        _ => {},
+    }
+
+    Ok(())
+}
+
+fn lex(lexer: &mut Lexer, result: &mut Vec<Token>) -> Result<(), String> {
+    loop {
+        let token = tokenize(lexer, result)?;
+        if token == Token::EOF {
+            return Ok(())
+        }
+        table(lexer, result, token)?;
+    }
+}
+
+// Function lex consumes the input string, returns a single Token, and modifies the lexer state if needed
+fn tokenize(lexer: &mut Lexer, result: &mut Vec<Token>) -> Result<Token, String> {
+    consume_whitespace(lexer);
+    let start = lexer.position();
+    let emitted = result.len();
+    let res = match lexer.lookahead_1() {
+        Some('a'..='z') => keyword_or_ident(lexer)?,
+        Some('A'..='Z'|'_'|'$'|'\\') => ident(lexer)?,
+        Some('0'..='9') => parse_number_or_bigint(lexer)?,
+        Some('"'|'\'') => parse_string(lexer)?,
+        // Punctuation
+        Some('(') => lexer.proceed_with(Token::LeftParen),
+        Some(')') => lexer.proceed_with(Token::RightParen),
+        Some('{') => {
+            lexer.brace_depth += 1;
+            lexer.proceed_with(Token::LeftBrace)
+        },
+        Some('}') => {
+            if lexer.quasi_stack.last() == Some(&lexer.brace_depth) {
+                // closing brace of a template substitution, continue the template
+                lexer.quasi_stack.pop();
+                lexer.proceed();
+                result.push(Token::RightBrace);
+                parse_quasi_chunk(lexer, result)?
+            } else {
+                lexer.brace_depth = lexer.brace_depth.saturating_sub(1);
+                lexer.proceed_with(Token::RightBrace)
+            }
+        },
+        Some('[') => lexer.proceed_with(Token::LeftBracket),
+        Some(']') => lexer.proceed_with(Token::RightBracket),
+        Some(',') => lexer.proceed_with(Token::Comma),
+        Some('.') => {
+            if lexer.lookahead_2() == Some('.') && lexer.lookahead_3() == Some('.') {
+                lexer.proceed();
+                lexer.proceed();
+                lexer.proceed();
+                Token::DotDotDot
+            } else if let Some('0'..='9') = lexer.lookahead_2() {
+                parse_number_or_bigint(lexer)?
+            } else {
+                lexer.proceed_with(Token::Dot)
+            }
+        },
+        Some(':') => lexer.proceed_with(Token::Colon),
+        Some(';') => lexer.proceed_with(Token::Semicolon),
+        Some('?') => {
+            if lexer.lookahead_2() == Some('?') {
+                lexer.proceed();
+                lexer.proceed();
+                Token::QuestionQuestion
+            } else if lexer.lookahead_2() == Some('.') && !matches!(lexer.lookahead_3(), Some('0'..='9')) {
+                // ?.5 is a conditional with a leading-dot decimal
+                lexer.proceed();
+                lexer.proceed();
+                Token::QuestionDot
+            } else {
+                lexer.proceed_with(Token::Question)
+            }
+        }
+        Some('`') => {
+            lexer.proceed();
+            result.push(Token::QuasiQuote);
+            parse_quasi_chunk(lexer, result)?
+        },
+        Some('/') => {
+            if lexer.lookahead_2() == Some('/') {
+                unreachable!("line comment should have been handled by consume_whitespace")
+            } else if lexer.lookahead_2() == Some('*') {
+                unreachable!("block comment should have been handled by consume_whitespace")
+            } else {
+                if lexer.lookahead_2() == Some('=') {
+                    lexer.proceed();
+                    lexer.proceed();
+                    Token::SlashEqual
+                } else {
+                    lexer.proceed_with(Token::Slash)
+                }
+            }
+        },
+        // Operators
+        Some('+') => {
+            if lexer.lookahead_2() == Some('+') {
+                return Err("Increment operator not supported yet".to_string());
+            } else if lexer.lookahead_2() == Some('=') {
+                lexer.proceed();
+                lexer.proceed();
+                Token::PlusEqual
+            } else {
+                lexer.proceed_with(Token::Plus)
+            }
+        },            
+        Some('-') => {
+            match lexer.lookahead_2() {
+                Some('-') => {
+                    return Err("Decrement operator not supported yet".to_string());
+                },
+                Some('=') => {
+                    lexer.proceed();
+                    lexer.proceed();
+                    Token::MinusEqual
+                },
+                _ => lexer.proceed_with(Token::Minus)
+            }
+        },
+        Some('*') => {
+            if lexer.lookahead_2() == Some('=') {
+                lexer.proceed();
+                lexer.proceed();
+                Token::AsteriskEqual
+            } else if lexer.lookahead_2() == Some('*') {
+                lexer.proceed();
+                if lexer.lookahead_3() == Some('=') {
+                    return Err("Exponentiation assignment operator not supported yet".to_string())
+                    // lexer.proceed();
+                    // result.push(lexer.proceed_with(Token::AsteriskAsteriskEqual);
+                } else {
+                    lexer.proceed_with(Token::AsteriskAsterisk)
+                }
+            } else if lexer.lookahead_2() == Some('/') {
+                lexer.proceed();
+                lexer.proceed();
+                Token::AsteriskSlash
+            } else {
+                lexer.proceed_with(Token::Asterisk)
+            }
+        },
+        Some('%') => {
+            if lexer.lookahead_2() == Some('=') {
+                lexer.proceed();
+                lexer.proceed();
+                Token::PercentEqual
+            } else {
+                lexer.proceed_with(Token::Percent)
+            }
+        },
+        Some('&') => {
+            if lexer.lookahead_2() == Some('&') {
+                lexer.proceed();
+                lexer.proceed();
+                Token::AmpAmp
+            } else if lexer.lookahead_2() == Some('=') {
+                lexer.proceed();
+                lexer.proceed();
+                Token::AmpersandEqual
+            } else {
+                lexer.proceed_with(Token::Ampersand)
+            }
+        },
+        Some('|') => {
+            if lexer.lookahead_2() == Some('|') {
+                lexer.proceed();
+                lexer.proceed();
+                Token::BarBar
+            } else if lexer.lookahead_2() == Some('=') {
+                lexer.proceed();
+                lexer.proceed();
+                Token::BarEqual
+            } else {
+                lexer.proceed_with(Token::Bar)
+            }
+        },
+        Some('^') => {
+            if lexer.lookahead_2() == Some('=') {
+                lexer.proceed();
+                lexer.proceed();
+                Token::CaretEqual
+            } else {
+                lexer.proceed_with(Token::Caret)
+            }
+        },
+        Some('~') => lexer.proceed_with(Token::Tilde),
+        Some('!') => {
+            if lexer.lookahead_2() == Some('=') {
+                if lexer.lookahead_3() == Some('=') {
+                    lexer.proceed();
+                    lexer.proceed();
+                    lexer.proceed();
+                    Token::BangEqualEqual
+                } else {
+                    return Err("!= operator not supported".to_string());
+                }
+            } else {
+                lexer.proceed_with(Token::Bang)
+            }
+        },
+        Some('=') => {
+            if lexer.lookahead_2() == Some('=') {
+                if lexer.lookahead_3() == Some('=') {
+                    lexer.proceed();
+                    lexer.proceed();
+                    lexer.proceed();
+                    Token::EqualEqualEqual
+                } else {
+                    return Err("== operator not supported".to_string());
+                }
+            } else if lexer.lookahead_2() == Some('>') {
+                lexer.proceed();
+                lexer.proceed();
+                Token::FatArrow
+            } else {
+                lexer.proceed_with(Token::Equal)
+            }
+        },
+        Some('<') => { // TODO: type annotations
+            if lexer.lookahead_2() == Some('<') {
+                if lexer.lookahead_3() == Some('=') {
+                    lexer.proceed();
+                    lexer.proceed();
+                    lexer.proceed();
+                    Token::LAngleLAngleEqual
+                } else {
+                    lexer.proceed();
+                    lexer.proceed();
+                    Token::LAngleLAngle
+                }
+            } else if lexer.lookahead_2() == Some('=') {
+                lexer.proceed();
+                lexer.proceed();
+                Token::LAngleEqual
+            } else {
+                lexer.proceed_with(Token::LAngle)
+            }
+        },
+        Some('>') => {
+            if lexer.lookahead_2() == Some('>') {
+                if lexer.lookahead_3() == Some('=') {
+                    lexer.proceed();
+                    lexer.proceed();
+                    lexer.proceed();
+                    Token::RAngleRAngleEqual
+                } else if lexer.lookahead_3() == Some('>') {
+                    lexer.proceed();
+                    lexer.proceed();
+                    lexer.proceed();
+                    Token::RAngleRAngleRAngle
+                } else {
+                    lexer.proceed();
+                    lexer.proceed();
+                    Token::RAngleRAngle
+                }
+            } else if lexer.lookahead_2() == Some('=') {
+                lexer.proceed();
+                lexer.proceed();
+                Token::RAngleEqual
+            } else {
+                lexer.proceed_with(Token::RAngle)
+            } 
+        } 
+        None => Token::EOF,
+        Some(c) if is_id_start(c) => ident(lexer)?,
+        Some(c) => return Err(format!("Unexpected character {}", c)),
+    };
+
+    result.push(res.clone());
+
+    // template literals emit multiple tokens at once, they all share the span of the whole chunk
+    let span = lexer.span_from(start);
+    for _ in emitted..result.len() {
+        lexer.spans.push(span);
+    }
+
+    Ok(res)
+}
+
+pub fn consume_whitespace(state: &mut Lexer) {
+    while let Some(c) = state.lookahead_1() {
+        match c {
+            ' ' | '\t' | '\r' | '\n' => { state.proceed(); },
+            '/' => {
+                match state.lookahead_2() {
+                    Some('/') => {
+                        state.proceed();
+                        state.proceed();
+                        while let Some(c) = state.lookahead_1() {
+                            if c == '\r' || c == '\n' {
+                                break;
+                            } else {
+                                state.proceed();
+                            }
+                        }
+                    }
+                    Some('*') => {
+                        state.proceed();
+                        state.proceed();
+                        while let Some(c) = state.lookahead_1() {
+                            if c == '*' {
+                                state.proceed();
+                                if let Some(c) = state.lookahead_1() {
+                                    if c == '/' {
+                                        state.proceed();
+                                        break;
+                                    }
+                                }
+                            } else {
+                                state.proceed();
+                            }
+                        }
+                    }
+                    _ => break,
+                }
+            },
+            _ => break,
+        }
+    }
+}
+
+// IdentifierStart ::= ID_Start | $ | _ | \\ UnicodeEscapeSequence
+fn is_id_start(c: char) -> bool {
+    c == '$' || c == '_' || unicode_ident::is_xid_start(c)
+}
+
+// IdentifierPart ::= ID_Continue | $ | <ZWNJ> | <ZWJ> | \\ UnicodeEscapeSequence
+fn is_id_continue(c: char) -> bool {
+    c == '$' || c == '\u{200C}' || c == '\u{200D}' || unicode_ident::is_xid_continue(c)
+}
+
+fn ident(state: &mut Lexer) -> Result<Token, String> {
+    // IdentifierStart IdentifierPart*
+    let mut ident = String::new();
+    match ident_char(state, is_id_start)? {
+        Some(c) => ident.push(c),
+        None => return Err(format!("Expected identifier, but got {:?}", state.lookahead_1())),
+    }
+    while let Some(c) = ident_char(state, is_id_continue)? {
+        ident.push(c);
+    }
+
+    Ok(Token::Identifier(ident.into()))
+}
+
+// Consumes the next identifier character if it satisfies the predicate, either literal or \\u escaped.
+fn ident_char(state: &mut Lexer, predicate: fn(char) -> bool) -> Result<Option<char>, String> {
+    match state.lookahead_1() {
+        Some('\\') => {
+            state.proceed();
+            if state.lookahead_1() != Some('u') {
+                return Err("Expected unicode escape sequence in identifier".to_string())
+            }
+            state.proceed();
+            let c = parse_unicode_escape(state)?;
+            if !predicate(c) {
+                return Err(format!("Escaped character {:?} is not allowed in identifier", c))
+            }
+            Ok(Some(c))
+        },
+        Some(c) if predicate(c) => {
+            state.proceed();
+            Ok(Some(c))
+        },
+        _ => Ok(None),
+    }
+}
+
+
+
+
+
+
+/*
+pub fn parse_number(state: &mut Lexer) -> Result<DataLiteral, String> {
+    // [1-9][0-9]*(\.[0-9]*|n)?
+    let mut number = String::new();
+    if state.lookahead_1().map(|x| x.is_ascii_digit()) != Some(true) {
+        return Err("not a number".to_string())
+    }
+    while let Some(c) = state.lookahead_1() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            state.proceed();
+        } else {
+            break;
+        }
+    }
+    if state.lookahead_1() == Some('.') {
+        state.proceed();
+        number.push('.');
+        while let Some(c) = state.lookahead_1() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                state.proceed();
+            } else {
+                break;
+            }
+        } 
+    } 
+ 
+    Ok(DataLiteral::Number(number)) 
+}
+ */
+// Token::Decimal(integer, fraction) holds the value integer + fraction / 10^19, with the fraction in [0, 10^19).
+pub const DECIMAL_FRACTION_DIGITS: usize = 19;
+
+// NumericLiteral ::= DecimalLiteral | DecimalBigIntegerLiteral | NonDecimalIntegerLiteral BigIntLiteralSuffix?
+// The immediate following symbol is guaranteed to be a digit, or a dot followed by a digit, by the caller.
+// The sign is not part of the literal, negative numbers are unary negations.
+// Values that are integers are lexed as Token::Integer(even if written as 1.0 or 1e3),
+// the others as Token::Decimal. Both are limited to the i64 range, bigints are not limited.
+pub fn parse_number_or_bigint(state: &mut Lexer) -> Result<Token, String> {
+    let radix = match (state.lookahead_1(), state.lookahead_2()) {
+        (Some('0'), Some('x' | 'X')) => 16,
+        (Some('0'), Some('o' | 'O')) => 8,
+        (Some('0'), Some('b' | 'B')) => 2,
+        (Some('0'), Some('0'..='9' | '_')) => return Err("Leading zeros and legacy octal literals are not allowed".to_string()),
+        _ => 10,
+    };
+
+    let token = if radix != 10 {
+        state.proceed();
+        state.proceed();
+        let digits = parse_digits(state, radix)?;
+        if digits.is_empty() {
+            return Err(format!("Expected digits after the radix prefix of base {}", radix))
+        }
+        let limbs = digits_to_limbs(&digits, radix);
+        if state.lookahead_1() == Some('n') {
+            state.proceed();
+            Token::Bigint(false, limbs)
+        } else {
+            Token::Integer(limbs_to_i64(&limbs).ok_or(format!("Integer literal is out of range, use a bigint literal instead"))?)
+        }
+    } else {
+        parse_decimal_literal(state)?
+    };
+
+    // The SourceCharacter immediately following a NumericLiteral must not be an IdentifierStart or DecimalDigit
+    match state.lookahead_1() {
+        Some(c) if is_id_start(c) || c.is_ascii_digit() || c == '\\' => Err(format!("Unexpected character {:?} right after a numeric literal", c)),
+        _ => Ok(token),
+    }
+}
+
+fn parse_decimal_literal(state: &mut Lexer) -> Result<Token, String> {
+    let integer = parse_digits(state, 10)?;
+
+    if state.lookahead_1() == Some('n') {
+        state.proceed();
+        return Ok(Token::Bigint(false, digits_to_limbs(&integer, 10)))
+    }
+
+    let mut fraction = String::new();
+    if state.lookahead_1() == Some('.') {
+        state.proceed();
+        if state.lookahead_1() == Some('_') {
+            return Err("Numeric separator is not allowed right after the decimal point".to_string())
+        }
+        fraction = parse_digits(state, 10)?;
+    }
+
+    let mut exponent: i64 = 0;
+    if let Some('e' | 'E') = state.lookahead_1() {
+        state.proceed();
+        let negative = match state.lookahead_1() {
+            Some('+') => { state.proceed(); false },
+            Some('-') => { state.proceed(); true },
+            _ => false,
+        };
+        let digits = parse_digits(state, 10)?;
+        if digits.is_empty() {
+            return Err("Expected digits in the exponent".to_string())
+        }
+        // saturating, anything this large is out of range anyway unless the mantissa is zero
+        exponent = digits.chars().fold(0i64, |acc, c| acc.saturating_mul(10).saturating_add(c as i64 - '0' as i64).min(1_000_000));
+        if negative {
+            exponent = -exponent;
+        }
+    }
+
+    // value = digits * 10^scale
+    let digits = format!("{}{}", integer, fraction);
+    let digits = digits.trim_start_matches('0');
+    let scale = exponent - fraction.len() as i64;
+    if digits.is_empty() {
+        return Ok(Token::Integer(0))
+    }
+
+    let out_of_range = || format!("Numeric literal {}{}{} is out of range", integer, if fraction.is_empty() { "" } else { "." }, fraction);
+
+    // number of digits in the integer part
+    let integer_len = digits.len() as i64 + scale;
+    if integer_len > 19 {
+        return Err(out_of_range())
+    }
+
+    let (integer_digits, fraction_digits) = if integer_len >= digits.len() as i64 {
+        (format!("{}{}", digits, "0".repeat((integer_len - digits.len() as i64) as usize)), String::new())
+    } else if integer_len > 0 {
+        (digits[..integer_len as usize].to_string(), digits[integer_len as usize..].to_string())
+    } else {
+        (String::new(), format!("{}{}", "0".repeat((-integer_len) as usize), digits))
+    };
+
+    let integer_part = if integer_digits.is_empty() { 0 } else { integer_digits.parse::<i64>().map_err(|_| out_of_range())? };
+
+    let fraction_digits = fraction_digits.trim_end_matches('0');
+    if fraction_digits.is_empty() {
+        return Ok(Token::Integer(integer_part))
+    }
+    if fraction_digits.len() > DECIMAL_FRACTION_DIGITS {
+        return Err(format!("Numeric literal has more than {} significant fractional digits and cannot be represented exactly", DECIMAL_FRACTION_DIGITS))
+    }
+    let fraction_part = format!("{:0<width$}", fraction_digits, width = DECIMAL_FRACTION_DIGITS).parse::<u64>().map_err(|_| out_of_range())?;
+
+    Ok(Token::Decimal(integer_part, fraction_part))
+}
+
+// Digits of the radix, with single numeric separators allowed between digits.
+// Returns the digits without separators, possibly empty.
+fn parse_digits(state: &mut Lexer, radix: u32) -> Result<String, String> {
+    let mut digits = String::new();
+    loop {
+        match state.lookahead_1() {
+            Some(c) if c.is_digit(radix) => {
+                digits.push(c);
+                state.proceed();
+            },
+            Some('_') => {
+                let followed_by_digit = state.lookahead_2().map(|c| c.is_digit(radix)).unwrap_or(false);
+                if digits.is_empty() || !followed_by_digit {
+                    return Err("Numeric separator must be placed between digits".to_string())
+                }
+                state.proceed();
+            },
+            _ => return Ok(digits),
+        }
+    }
+}
+
+// Converts digits of the radix into little endian u64 limbs, with at least one limb.
+fn digits_to_limbs(digits: &str, radix: u32) -> Box<[u64]> {
+    let mut limbs: Vec<u64> = vec![0];
+    for c in digits.chars() {
+        let mut carry = c.to_digit(radix).unwrap_or_default() as u128;
+        for limb in limbs.iter_mut() {
+            let value = (*limb as u128) * (radix as u128) + carry;
+            *limb = value as u64;
+            carry = value >> 64;
+        }
+        if carry > 0 {
+            limbs.push(carry as u64);
+        }
+    }
+    limbs.into_boxed_slice()
+}
+
+fn limbs_to_i64(limbs: &[u64]) -> Option<i64> {
+    if limbs.iter().skip(1).any(|limb| *limb != 0) {
+        return None
+    }
+    i64::try_from(limbs[0]).ok()
+}
+
+pub fn parse_string(state: &mut Lexer) -> Result<Token, String> {
+    let mut string = String::new();
+    let enclosing = state.lookahead_1().filter(|c| *c == '"' || *c == '\'').ok_or("Expected string".to_string())?;
+    state.proceed();
+    loop {
+        match state.lookahead_1() {
+            Some(c) if c == enclosing => {
+                state.proceed();
+                break;
+            },
+            Some('\\') => {
+                state.proceed();
+                parse_escape(state, &mut string)?;
+            },
+            // line terminators must be escaped, U+2028 and U+2029 are allowed since ES2019
+            Some('\n') | Some('\r') | None => return Err("Unterminated string literal".to_string()),
+            Some(c) => {
+                string.push(c); // TODO: optimize, i think we can just slice the string
+                state.proceed();
+            },
+        }
+    }
+    Ok(Token::String(string.into()))
+}
+
+// Parses an escape sequence right after the backslash, and pushes the escaped character.
+// Line continuations push nothing.
+fn parse_escape(state: &mut Lexer, string: &mut String) -> Result<(), String> {
+    let c = state.proceed().ok_or("Unterminated escape sequence".to_string())?;
+    match c {
+        'n' => string.push('\n'),
+        't' => string.push('\t'),
+        'r' => string.push('\r'),
+        'b' => string.push('\u{8}'),
+        'f' => string.push('\u{C}'),
+        'v' => string.push('\u{B}'),
+        '0' if !state.lookahead_1().map(|c| c.is_ascii_digit()).unwrap_or(false) => string.push('\0'),
+        '0'..='9' => return Err(format!("Octal escape sequence \\{} is not allowed in strict mode", c)),
+        'x' => {
+            let code = parse_hex_digits(state, 2)?;
+            // two hex digits are always a valid scalar value
+            string.push(char::from_u32(code).unwrap_or_default())
+        },
+        'u' => string.push(parse_unicode_escape(state)?),
+        // line continuations
+        '\r' => {
+            if state.lookahead_1() == Some('\n') {
+                state.proceed();
+            }
+        },
+        '\n' | '\u{2028}' | '\u{2029}' => {},
+        // NonEscapeCharacter, including the quotes and the backslash itself
+        c => string.push(c),
+    }
+    Ok(())
+}
+
+// Parses exactly n hex digits.
+fn parse_hex_digits(state: &mut Lexer, n: usize) -> Result<u32, String> {
+    let mut code = 0;
+    for _ in 0..n {
+        match state.lookahead_1().and_then(|c| c.to_digit(16)) {
+            Some(digit) => {
+                code = code * 16 + digit;
+                state.proceed();
+            },
+            None => return Err(format!("Invalid hexadecimal escape sequence, expected {} hex digits", n)),
+        }
+    }
+    Ok(code)
+}
+
+// Parses the code point of \\uXXXX or \\u{X...}, right after the 'u'.
+fn parse_unicode_escape_code(state: &mut Lexer) -> Result<u32, String> {
+    if state.lookahead_1() != Some('{') {
+        return parse_hex_digits(state, 4)
+    }
+
+    state.proceed();
+    let mut code: u32 = 0;
+    let mut digits = 0;
+    loop {
+        match state.lookahead_1() {
+            Some('}') if digits > 0 => {
+                state.proceed();
+                return Ok(code)
+            },
+            Some(c) if c.is_ascii_hexdigit() => {
+                code = code * 16 + c.to_digit(16).unwrap_or_default();
+                if code > 0x10FFFF {
+                    return Err("Unicode escape sequence is out of range, must be at most \\u{10FFFF}".to_string())
+                }
+                digits += 1;
+                state.proceed();
+            },
+            _ => return Err("Invalid unicode escape sequence, expected hex digits followed by }".to_string()),
+        }
+    }
+}
+
+// Parses a unicode escape right after the 'u', combining a surrogate pair \\uD83D\\uDE00 into a single character.
+// Lone surrogates cannot be represented in the string and are rejected.
+fn parse_unicode_escape(state: &mut Lexer) -> Result<char, String> {
+    let code = parse_unicode_escape_code(state)?;
+    match code {
+        0xD800..=0xDBFF => {
+            if state.lookahead_1() != Some('\\') || state.lookahead_2() != Some('u') {
+                return Err(format!("Lone surrogate \\u{:X} is not supported", code))
+            }
+            state.proceed();
+            state.proceed();
+            let low = parse_unicode_escape_code(state)?;
+            if !(0xDC00..=0xDFFF).contains(&low) {
+                return Err(format!("Lone surrogate \\u{:X} is not supported", code))
+            }
+            let combined = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+            char::from_u32(combined).ok_or(format!("Invalid surrogate pair \\u{:X}\\u{:X}", code, low))
+        },
+        0xDC00..=0xDFFF => Err(format!("Lone surrogate \\u{:X} is not supported", code)),
+        code => char::from_u32(code).ok_or(format!("Invalid code point \\u{{{:X}}}", code)),
+    }
+}
+
+// Template literals are lexed as
+// QuasiQuote String (Dollar LeftBrace ...tokens RightBrace String)* QuasiQuote
+// so that there is always one more string chunk than the substitutions.
+// Pushes the string chunk and returns the token that ends it, either the closing QuasiQuote or the LeftBrace of a substitution.
+fn parse_quasi_chunk(state: &mut Lexer, result: &mut Vec<Token>) -> Result<Token, String> {
+    let mut chunk = String::new();
+    loop {
+        match state.lookahead_1() {
+            Some('`') => {
+                state.proceed();
+                result.push(Token::String(chunk.into()));
+                return Ok(Token::QuasiQuote)
+            },
+            Some('$') if state.lookahead_2() == Some('{') => {
+                state.proceed();
+                state.proceed();
+                result.push(Token::String(chunk.into()));
+                result.push(Token::Dollar);
+                state.quasi_stack.push(state.brace_depth);
+                return Ok(Token::LeftBrace)
+            },
+            Some('\\') => {
+                state.proceed();
+                if state.lookahead_1().is_none() {
+                    return Err("Unterminated template literal".to_string())
+                }
+                parse_escape(state, &mut chunk)?;
+            },
+            Some(c) => {
+                chunk.push(c);
+                state.proceed();
+            },
+            None => return Err("Unterminated template literal".to_string()),
+        }
+    }
+}
+
+
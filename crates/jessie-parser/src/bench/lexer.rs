@@ -0,0 +1,57 @@
+// benchmark for comparing the &str lexer with the previous Vec<char> based lexer(legacy_lexer.rs)
+
+extern crate test;
+
+use test::{Bencher, black_box};
+
+use crate::lexer::lex_jessie_with_spans;
+use super::legacy_lexer;
+
+const SNIPPET: &str = r#"
+// counter with a few of everything the lexer handles
+const makeCounter = (init = 0, { step = 1, label: name = "counter" } = {}) => {
+    let count = init;
+    /* closures share the same binding */
+    const increment = () => { count += step; return count; };
+    const describe = () => `${name}: ${count} (step ${step})`;
+    return { increment, describe, [name]: count, limit: 0x7fff_ffff, ratio: 1.25e-3, big: 12345678901234567890n };
+};
+function fib(n) {
+    if (n <= 1) { return n; }
+    return fib(n - 1) + fib(n - 2);
+}
+const greeting = 'hello, \u{1F600} world\n';
+const 変数 = [1, 2, 3, ...[4, 5]].length ?? undefined;
+"#;
+
+fn source(repeat: usize) -> String {
+    SNIPPET.repeat(repeat)
+}
+
+#[test]
+fn test_legacy_lexer_equivalence() {
+    let source = source(4);
+    let (tokens, spans) = lex_jessie_with_spans(&source).unwrap();
+    let (legacy_tokens, legacy_spans) = legacy_lexer::lex_jessie_with_spans(source.clone()).unwrap();
+    assert_eq!(tokens, legacy_tokens);
+    for (span, legacy_span) in spans.iter().zip(legacy_spans.iter()) {
+        assert_eq!((span.start, span.end, span.line, span.column), (legacy_span.start, legacy_span.end, legacy_span.line, legacy_span.column));
+    }
+    assert_eq!(spans.len(), legacy_spans.len());
+}
+
+#[bench]
+fn bench_lexer(b: &mut Bencher) {
+    let source = source(200);
+    b.bytes = source.len() as u64;
+    b.iter(|| black_box(lex_jessie_with_spans(black_box(&source)).unwrap()));
+}
+
+#[bench]
+fn bench_legacy_lexer(b: &mut Bencher) {
+    let source = source(200);
+    b.bytes = source.len() as u64;
+    // the legacy lexer takes ownership of the input, the copy is part of its cost
+    b.iter(|| black_box(legacy_lexer::lex_jessie_with_spans(black_box(source.clone())).unwrap()));
+}
+
@@ -1 +1,2 @@
-mod map;
\ No newline at end of file
+mod legacy_lexer;
+mod lexer;
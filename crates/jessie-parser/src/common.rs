@@ -1,7 +1,8 @@
 use std::rc::Rc;
 
 use jessie_ast::*;
-use crate::jessie_parser::JessieParserState;
+use crate::jessie_parser::{JessieParserState, GrammarLevel};
+use crate::types::type_ann;
 use crate::parser;
 use crate::{
     Token,
@@ -17,6 +18,7 @@ type ParserError = parser::ParserError<Option<Token>>;
 pub fn identifier(state: &mut ParserState) -> Result<Rc<str>, ParserError> {
     match state.lookahead_1() {
         Some(Token::Identifier(s)) => {
+            let s = s.clone();
             state.proceed();
             Ok(s)
         },
@@ -33,22 +35,33 @@ pub fn identifier(state: &mut ParserState) -> Result<Rc<str>, ParserError> {
 }
 
 pub fn use_variable(state: &mut ParserState) -> Result<Variable, ParserError> {
+    let span = state.span();
     match state.lookahead_1() {
         Some(Token::Identifier(s)) => {
+            let s = s.clone();
             state.proceed();
-            Ok(Variable::new(s))
+            Ok(Variable::new(s).with_span(span))
         },
         Some(Token::Get) => {
             state.proceed();
-            Ok(Variable::new("get".into()))
+            Ok(Variable::new("get".into()).with_span(span))
         },
         Some(Token::Set) => {
             state.proceed();
-            Ok(Variable::new("set".into()))
+            Ok(Variable::new("set".into()).with_span(span))
         },
-        found => panic!("asdf"), // state.err_expected("variable identifier", found),
+        found => state.err_expected("variable identifier", found),
     }
 }
+
+// ":" type, only at the Tessie level
+pub fn optional_type_ann(state: &mut ParserState) -> Result<Option<TypeAnn>, ParserError> {
+    if state.level < GrammarLevel::Tessie || !state.try_proceed(Token::Colon) {
+        return Ok(None)
+    }
+    type_ann(state).map(Some)
+}
+
 /* 
 pub fn use_variable_with_parsed(state: &mut ParserState, ident: String) -> UseVariable {
     let var = state.scope.use_variable(&ident);
@@ -56,8 +69,4 @@ pub fn use_variable_with_parsed(state: &mut ParserState, ident: String) -> UseVa
     var 
 }
 
-pub fn optional_type_ann(state: &mut ParserState) -> Result<Option<TypeAnn>, ParserError> {
-    Ok(None)
-}
-
 }*/
\ No newline at end of file
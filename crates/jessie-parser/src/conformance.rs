@@ -0,0 +1,207 @@
+// Conformance checking for developers coming from JavaScript.
+// Jessie is a subset of JavaScript, and the parser rejects the rest with a generic error at the first one, or not at
+// all for the operators the lexer refuses to tokenize. check_conformance scans the whole source instead, and reports
+// every construct outside of Jessie with the reason it was left out and the way to write it in Jessie.
+
+use std::fmt;
+
+use jessie_ast::Span;
+
+use crate::lexer::{consume_whitespace, tokenize, Lexer, Token};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    // the construct as written in the source
+    pub construct: &'static str,
+    pub span: Span,
+    pub rationale: &'static str,
+    pub suggestion: &'static str,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is not Jessie at {}:{}: {}. Instead, {}.", self.construct, self.span.line, self.span.column, self.rationale, self.suggestion)
+    }
+}
+
+// Reports the non-Jessie constructs in source order. Scanning stops at the first lexical error that is not one of
+// them, which is left for the parser to report.
+pub fn check_conformance(source: &str) -> Vec<Violation> {
+    let mut lexer = Lexer::new(source);
+    let mut tokens = Vec::new();
+    let mut violations = Vec::new();
+
+    loop {
+        consume_whitespace(&mut lexer);
+        let start = lexer.position();
+        // the lexer refuses these operators, skip over them to keep scanning
+        if let Some((construct, len)) = loose_operator(&lexer) {
+            for _ in 0..len {
+                lexer.proceed();
+            }
+            let (rationale, suggestion) = explain(construct);
+            violations.push(Violation { construct, span: lexer.span_from(start), rationale, suggestion });
+            continue;
+        }
+        match tokenize(&mut lexer, &mut tokens) {
+            Ok(Token::EOF) | Err(_) => break,
+            Ok(_) => {},
+        }
+    }
+    // a template literal cut short by an error has tokens without spans
+    tokens.truncate(lexer.spans.len());
+
+    for (i, token) in tokens.iter().enumerate() {
+        let Some(construct) = reserved_word(token) else {
+            continue
+        };
+        // reserved words are still allowed as property names
+        let previous = i.checked_sub(1).and_then(|i| tokens.get(i));
+        let next = tokens.get(i + 1);
+        if matches!(previous, Some(Token::Dot | Token::QuestionDot)) || (matches!(previous, Some(Token::LeftBrace | Token::Comma)) && next == Some(&Token::Colon)) {
+            continue
+        }
+        let (rationale, suggestion) = explain(construct);
+        violations.push(Violation { construct, span: lexer.spans[i], rationale, suggestion });
+    }
+
+    violations.sort_by_key(|violation| violation.span.start);
+    violations
+}
+
+fn loose_operator(lexer: &Lexer<'_>) -> Option<(&'static str, usize)> {
+    match (lexer.lookahead_1(), lexer.lookahead_2(), lexer.lookahead_3()) {
+        (Some('='), Some('='), Some('=')) | (Some('!'), Some('='), Some('=')) => None,
+        (Some('='), Some('='), _) => Some(("==", 2)),
+        (Some('!'), Some('='), _) => Some(("!=", 2)),
+        (Some('+'), Some('+'), _) => Some(("++", 2)),
+        (Some('-'), Some('-'), _) => Some(("--", 2)),
+        _ => None,
+    }
+}
+
+fn reserved_word(token: &Token) -> Option<&'static str> {
+    Some(match token {
+        Token::This => "this",
+        Token::New => "new",
+        Token::Class => "class",
+        Token::Extends => "extends",
+        Token::Super => "super",
+        Token::Var => "var",
+        Token::In => "in",
+        Token::Instanceof => "instanceof",
+        Token::Delete => "delete",
+        Token::With => "with",
+        Token::Yield => "yield",
+        Token::Do => "do",
+        Token::Arguments => "arguments",
+        Token::Eval => "eval",
+        _ => return None,
+    })
+}
+
+// for a construct reported without an explanation of its own, rather than a panic
+const GENERIC_EXPLANATION: (&str, &str) = (
+    "it is outside of the subset of JavaScript that Jessie keeps",
+    "rewrite it with the constructs of Jessie",
+);
+
+// (rationale, suggestion)
+fn explain(construct: &str) -> (&'static str, &'static str) {
+    match construct {
+        "this" => (
+            "Jessie has no `this`, a method would depend on how it is called rather than on where it is defined",
+            "close over the state in the maker function, and refer to it by its variable name",
+        ),
+        "new" => (
+            "Jessie has no constructors or prototypes",
+            "call a maker function that returns a record, e.g. `makeCounter()` instead of `new Counter()`",
+        ),
+        "class" => (
+            "Jessie has no classes, since they need `this`, `new` and mutable prototypes",
+            "use a maker function instead of class, returning a hardened record of methods that close over its state",
+        ),
+        "extends" | "super" => (
+            "Jessie has no class inheritance",
+            "compose maker functions, spreading the methods of one record into another",
+        ),
+        "var" => (
+            "`var` is hoisted to the function scope, which makes the visible bindings hard to follow",
+            "use `const`, or `let` if the variable is reassigned",
+        ),
+        "==" => (
+            "loose equality coerces its operands to the same type before comparing",
+            "use `===`",
+        ),
+        "!=" => (
+            "loose inequality coerces its operands to the same type before comparing",
+            "use `!==`",
+        ),
+        "++" => (
+            "Jessie leaves out the increment operator, an assignment nested in an expression is easy to overlook",
+            "write the assignment as its own statement, `x += 1;`",
+        ),
+        "--" => (
+            "Jessie leaves out the decrement operator, an assignment nested in an expression is easy to overlook",
+            "write the assignment as its own statement, `x -= 1;`",
+        ),
+        "in" => (
+            "`in` and `for...in` look up inherited properties through the prototype chain",
+            "use `Object.hasOwn(record, key)`, or `for (const key of Object.keys(record))`",
+        ),
+        "instanceof" => (
+            "Jessie has no prototypes to test against",
+            "check the shape of the value, with `typeof`, `Array.isArray` or a brand held by the maker function",
+        ),
+        "delete" => (
+            "records are hardened, their properties cannot be removed",
+            "build a new record without the property, e.g. `const { key, ...rest } = record;`",
+        ),
+        "with" => (
+            "`with` makes the variables in scope depend on the properties of an object at run time",
+            "refer to the properties through the object, or destructure them into constants",
+        ),
+        "yield" => (
+            "Jessie has no generators",
+            "return an array of the values, or a record with a `next` method",
+        ),
+        "do" => (
+            "Jessie has no do-while loop",
+            "use a `while` loop, with the body before the loop if it must run at least once",
+        ),
+        "arguments" => (
+            "Jessie has no `arguments` object",
+            "declare a rest parameter, `(...args) => ...`",
+        ),
+        "eval" => (
+            "direct eval can reach the local variables of the calling code",
+            "evaluate the code in a separate compartment, with only the endowments it needs",
+        ),
+        _ => GENERIC_EXPLANATION,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conformance() {
+        let code = "class Counter {\n  constructor() { this.count = 0; }\n}\nvar c = new Counter();\nif (c == null || 'x' in c) { c.count++; }\nconst o = { delete: 1, new: o.new, s: '==' }; // this\n";
+        let found: Vec<_> = check_conformance(code).into_iter().map(|violation| (violation.construct, violation.span.line, violation.span.column)).collect();
+        assert_eq!(found, [("class", 1, 1), ("this", 2, 19), ("var", 4, 1), ("new", 4, 9), ("==", 5, 7), ("in", 5, 22), ("++", 5, 37)]);
+
+        let violation = &check_conformance("class A {}")[0];
+        assert!(violation.suggestion.contains("maker function instead of class"));
+        assert_eq!(violation.to_string(), format!("`class` is not Jessie at 1:1: {}. Instead, {}.", violation.rationale, violation.suggestion));
+        assert_eq!(check_conformance("x !== y && x === y"), []);
+
+        // every reported construct has an explanation of its own
+        let code = "this new class extends super var in instanceof delete with yield do arguments eval == != ++ --";
+        let violations = check_conformance(code);
+        assert_eq!(violations.len(), 18);
+        for violation in violations {
+            assert_ne!((violation.rationale, violation.suggestion), GENERIC_EXPLANATION, "{}", violation.construct);
+        }
+    }
+}
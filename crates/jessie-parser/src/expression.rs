@@ -1,7 +1,7 @@
 use std::rc::Rc;
 
 use jessie_ast::*;
-use crate::{jessie_parser::{JessieParserState, repeated_elements}, function::{function_expr, arrow_expr}, Token, operation::{cond_expr_with_leftmost, cond_expr_with_leftmost_no_power, unary_op, call_expr_internal}, common::use_variable, parser, statement::{block_raw, self}, pattern::param};
+use crate::{jessie_parser::{JessieParserState, GrammarLevel, repeated_elements, enclosed_element}, function::{function_expr, function_internal, arrow_expr, typed_arrow_expr}, Token, operation::{cond_expr_with_leftmost, cond_expr_with_leftmost_no_power, unary_op, call_expr_internal}, common::{use_variable, optional_type_ann}, parser, statement::block_raw, pattern::typed_param};
 
 type ParserState = JessieParserState; 
 type ParserError = parser::ParserError<Option<Token>>;
@@ -29,23 +29,42 @@ type ParserError = parser::ParserError<Option<Token>>;
 // ArrowFunction argument or Parenthesized Expression, for the valid lefthand for fat arrow. 
 pub fn expression(state: &mut ParserState) -> Result<Expr, ParserError> {
     println!("expression {:?}", state);
+    if state.level == GrammarLevel::Json {
+        return json_value(state)
+    }
+
     match state.lookahead_1() {
         // Function expression
         // function f(x) { return x + 1; }
         
-        Some(Token::Function) => function_expr(state).map(|x| Expr::Function(Box::new(x))),
+        Some(Token::Function) => {
+            state.require_level(GrammarLevel::Jessie, "function expression")?;
+            function_expr(state).map(|x| Expr::Function(Box::new(x)))
+        },
 
         // Parenthesized expression or arrow function
         // (x + y)
         // (x, y) => x + y
-        Some(Token::ArrowLeftParen) => arrow_expr(state),
+        Some(Token::ArrowLeftParen) => {
+            state.require_level(GrammarLevel::Jessie, "arrow function")?;
+            arrow_expr(state)
+        },
+        // x => x + 1
+        Some(Token::Identifier(_)) if state.lookahead_2() == Some(&Token::FatArrow) => {
+            state.require_level(GrammarLevel::Jessie, "arrow function")?;
+            arrow_expr(state)
+        },
         Some(Token::LeftParen) => {
+            if let Some(arrow) = typed_arrow_expr(state)? {
+                return Ok(arrow)
+            }
+            let start = state.position();
             state.proceed();
             let expr = expression(state)?;
             state.consume_1(Token::RightParen)?;
             // if the expression is parenthesized expression(a primary expression), 
             // it can be a leftmost expression for a CondExpr.
-            let (post_op_expr, _) = call_and_unary_op_internal(state, Expr::ParenedExpr(Box::new(expr)), vec![])?;
+            let (post_op_expr, _) = call_and_unary_op_internal(state, Expr::ParenedExpr(Box::new(expr)), vec![], start)?;
             cond_expr_with_leftmost(state, post_op_expr)
         },
 
@@ -61,6 +80,8 @@ pub fn expression(state: &mut ParserState) -> Result<Expr, ParserError> {
 // - unary_expr wrapping them(if exists)
 // any other cases are unreachable!.
 pub fn call_and_unary_op(state: &mut ParserState) -> Result<(Expr, bool), ParserError> {
+    let start = state.position();
+
     // 1. UnaryOp fast path
     let mut preops = vec![];
     while let Ok(preop) = unary_op(state) {
@@ -70,10 +91,10 @@ pub fn call_and_unary_op(state: &mut ParserState) -> Result<(Expr, bool), Parser
     // 2. Leftmost PrimaryExpression
     let expr = primary_expr(state)?;
 
-    call_and_unary_op_internal(state, expr, preops)
+    call_and_unary_op_internal(state, expr, preops, start)
 }
 
-fn call_and_unary_op_internal(state: &mut ParserState, expr: Expr, preops: Vec<UnaryOp>) -> Result<(Expr, bool), ParserError> {
+fn call_and_unary_op_internal(state: &mut ParserState, expr: Expr, preops: Vec<UnaryOp>, start: usize) -> Result<(Expr, bool), ParserError> {
 
     // 2-1. MemberPostOp fast path
     // If the leftmost node has CallPostOps(but without Call), it could be either an AssignExpr or a CondExpr(CallExpr).
@@ -81,7 +102,7 @@ fn call_and_unary_op_internal(state: &mut ParserState, expr: Expr, preops: Vec<U
 
     // apply unaryops(lower precedence than call ops)
     if !preops.is_empty() {
-        let unary_expr = Expr::UnaryExpr(Box::new(UnaryExpr { op: preops.into_boxed_slice(), expr: call_expr }));
+        let unary_expr = Expr::UnaryExpr(Box::new(UnaryExpr { op: preops.into_boxed_slice(), expr: call_expr, span: state.span_from(start) }));
         Ok((unary_expr, only_member_post_op))
     } else {
         Ok((call_expr, only_member_post_op))
@@ -103,12 +124,11 @@ pub fn assign_or_cond_or_primary_expression(state: &mut ParserState) -> Result<E
     // 3. Try parsing an AssignOp. If it succeeds, we know that we are parsing an AssignExpr. Coerce the primary expression to a LValue and return the AssignExpr parsing result. If it fails, we are parsing a CondExpr.
     // 4. Try parsing an ternary/binary operator. If it succeeds, we know that we are parsing a CondExpr. Return CondExpr parsing result.
     // 5. If we reach here, we are parsing a PrimaryExpression. Return the parsing result.
-    // TODO: support quasi expression
 
     let (expr, only_member_post_op) = call_and_unary_op(state)?;
 
     match expr {
-        Expr::DataLiteral(_) | Expr::Function(_) => {
+        Expr::DataLiteral(_) | Expr::Function(_) | Expr::QuasiExpr(_) => {
             // not a lvalue, cannot be an assignment.
             // jump to cond expression parsing.
             return cond_expr_with_leftmost(state, expr)
@@ -120,9 +140,9 @@ pub fn assign_or_cond_or_primary_expression(state: &mut ParserState) -> Result<E
             return cond_expr_with_leftmost_no_power(state, expr);
         },
         Expr::CallExpr(_) => {
-            // if function call exists in the postops, cannot be an assignment.
-            // jump to cond expression parsing.
-            if !only_member_post_op {
+            // if function call exists in the postops, only the members of its result can be assigned(f().x = 1).
+            // otherwise jump to cond expression parsing.
+            if !only_member_post_op && !is_call_member(&expr) {
                 return cond_expr_with_leftmost(state, expr)
             }
             // otherwise, continue
@@ -142,17 +162,18 @@ pub fn assign_or_cond_or_primary_expression(state: &mut ParserState) -> Result<E
             return cond_expr_with_leftmost(state, expr)
         }
         Expr::ParenedExpr(_) => {
-            unreachable!("parenthesized expression should not appear at the left side of an assignment. arrow_or_paren_expr takes priority. not a Jessie spec")
+            // parenthesized expression should not appear at the left side of an assignment. arrow_or_paren_expr takes priority. not a Jessie spec
+            return state.err_invalid("parenthesized expression is not a valid assignment target")
         },
-        _ => unreachable!("call_and_unary_op should not return other types of expression"),
+        _ => return state.err_invalid("call_and_unary_op should not return other types of expression"),
     }
 
     // At this point, expression is either a variable or a call expression(with only member post ops) with primary expression as its leftmost.
 
     // 3. AssignExpr parsing
-    if let Some(op) = assign_op(state) {
+    if let Some(op) = assign_op(state)? {
         // Assignment operator exists and the expression is coercible into LValue. 
-        let lvalue = expr.into(); // must work
+        let lvalue = call_member_lvalue(expr).into(); // must work
         let right = expression(state)?;
         return Ok(Expr::Assignment(Box::new(Assignment ( op, lvalue, right ))));
     }
@@ -166,60 +187,58 @@ pub fn assign_or_cond_or_primary_expression(state: &mut ParserState) -> Result<E
     Ok(expr)
 }
 
-fn assign_op(state: &mut ParserState) -> Option<AssignOp> {
-    match state.lookahead_1() {
-        Some(Token::Equal) => {
-            state.proceed();
-            Some(AssignOp::Assign)
-        },
-        Some(Token::PlusEqual) => {
-            state.proceed();
-            Some(AssignOp::AssignAdd)
-        },
-        Some(Token::MinusEqual) => {
-            state.proceed();
-            Some(AssignOp::AssignSub)
-        },
-        Some(Token::AsteriskEqual) => {
-            state.proceed();
-            Some(AssignOp::AssignMul)
-        },
-        Some(Token::SlashEqual) => {
-            state.proceed();
-            Some(AssignOp::AssignDiv)
-        },
-        Some(Token::PercentEqual) => {
-            state.proceed();
-            Some(AssignOp::AssignMod)
-        },
-        /*
-        Some(Token::CaretEqual) => {
-            state.proceed();
-            Some(AssignOp::AssignExp)
-        },
-        Some(Token::AmpersandEqual) => {
-            state.proceed();
-            Some(AssignOp::AssignAnd)
-        },
-        Some(Token::PipeEqual) => {
-            state.proceed();
-            Some(AssignOp::AssignOr)
-        },
-        Some(Token::LeftShiftEqual) => {
-            state.proceed();
-            Some(AssignOp::)
-        },
-        Some(Token::RightShiftEqual) => {
-            state.proceed();
-            Some(AssignOp::RightShiftEqual)
-        },
-        Some(Token::UnsignedRightShiftEqual) => {
-            state.proceed();
-            Some(AssignOp::UnsignedRightShiftEqual)
-        },
-        */
-        _ => None,
-    }
+// a call expression ending with member accesses, and without optional chaining
+fn is_call_member(expr: &Expr) -> bool {
+    let Expr::CallExpr(call) = expr else { return false };
+    matches!(call.post_ops.last(), Some(CallPostOp::Member(_) | CallPostOp::Index(_))) &&
+        !call.post_ops.iter().any(|op| op.is_optional())
+}
+
+// splits the trailing member accesses from f().x, so that the remaining post ops are coercible into LValue.
+// the call expression is evaluated once as the object of the lvalue.
+fn call_member_lvalue(expr: Expr) -> Expr {
+    let Expr::CallExpr(call) = expr else { return expr };
+    let CallExpr { expr, post_ops, span } = *call;
+    let Some(split) = post_ops.iter().rposition(|op| !matches!(op, CallPostOp::Member(_) | CallPostOp::Index(_))) else {
+        return Expr::CallExpr(Box::new(CallExpr { expr, post_ops, span }))
+    };
+    let mut post_ops = post_ops.into_vec();
+    let members = post_ops.split_off(split + 1);
+    // the inner call shares the span of the whole expression, the post ops do not keep their own spans
+    let object = Expr::CallExpr(Box::new(CallExpr { expr, post_ops: post_ops.into_boxed_slice(), span }));
+    Expr::CallExpr(Box::new(CallExpr { expr: object, post_ops: members.into_boxed_slice(), span }))
+}
+
+fn assign_op(state: &mut ParserState) -> Result<Option<AssignOp>, ParserError> {
+    let op = match state.lookahead_1() {
+        Some(Token::Equal) => AssignOp::Assign,
+        Some(Token::PlusEqual) => AssignOp::AssignAdd,
+        Some(Token::MinusEqual) => AssignOp::AssignSub,
+        Some(Token::AsteriskEqual) => AssignOp::AssignMul,
+        Some(Token::SlashEqual) => AssignOp::AssignDiv,
+        Some(Token::PercentEqual) => AssignOp::AssignMod,
+        Some(Token::AsteriskAsteriskEqual) => AssignOp::AssignExp,
+        Some(Token::LAngleLAngleEqual) => AssignOp::AssignLShift,
+        Some(Token::RAngleRAngleEqual) => AssignOp::AssignRShift,
+        Some(Token::RAngleRAngleRAngleEqual) => AssignOp::AssignURShift,
+        Some(Token::AmpersandEqual) => AssignOp::AssignBitAnd,
+        Some(Token::CaretEqual) => AssignOp::AssignBitXor,
+        Some(Token::BarEqual) => AssignOp::AssignBitOr,
+        Some(Token::BarBarEqual) => AssignOp::AssignOr,
+        Some(Token::AmpAmpEqual) => AssignOp::AssignAnd,
+        Some(Token::QuestionQuestionEqual) => AssignOp::AssignCoalesce,
+        _ => return Ok(None),
+    };
+
+    state.require_level(GrammarLevel::Jessie, "assignment")?;
+    state.proceed();
+    Ok(Some(op))
+}
+
+// a literal spans the single token it is lexed from
+fn literal(data: DataLiteral, state: &mut ParserState) -> Result<Expr, ParserError> {
+    let span = state.span();
+    state.proceed_then(Expr::DataLiteral(Box::new(LiteralExpr { data, span })))
 }
 
 pub fn primary_expr(state: &mut ParserState) -> Result<Expr, ParserError> {
@@ -231,22 +250,105 @@ pub fn primary_expr(state: &mut ParserState) -> Result<Expr, ParserError> {
             state.consume_1(Token::RightParen)?;
             Ok(Expr::ParenedExpr(Box::new(e)))
         },
-        Some(Token::QuasiQuote) => unimplemented!("QuasiExpr not implemented"),
+        Some(Token::QuasiQuote) => quasi_expr(state).map(|x| Expr::QuasiExpr(Box::new(x))),
         Some(Token::LeftBracket) => array(state).map(|x| Expr::Array(Box::new(x))),
         Some(Token::LeftBrace) => record(state).map(|x| Expr::Record(Box::new(x))),
-        Some(Token::String(s)) => state.proceed_then(Expr::DataLiteral(Box::new(DataLiteral::String(s.into())))),
-        Some(Token::Integer(n)) => state.proceed_then(Expr::DataLiteral(Box::new(DataLiteral::Integer(n)))),
-        Some(Token::Decimal(i, f)) => state.proceed_then(Expr::DataLiteral(Box::new(DataLiteral::Decimal(i, f)))),
-        Some(Token::Null) => state.proceed_then(Expr::DataLiteral(Box::new(DataLiteral::Null))),
-        Some(Token::True) => state.proceed_then(Expr::DataLiteral(Box::new(DataLiteral::True))),
-        Some(Token::False) => state.proceed_then(Expr::DataLiteral(Box::new(DataLiteral::False))),
-        Some(Token::Undefined) => state.proceed_then(Expr::DataLiteral(Box::new(DataLiteral::Undefined))),
-        Some(Token::Bigint(sign, abs)) => state.proceed_then(Expr::DataLiteral(Box::new(DataLiteral::Bigint(sign, abs)))),
+        Some(Token::String(s)) => literal(DataLiteral::String(s.clone()), state),
+        Some(Token::Integer(n)) => literal(DataLiteral::Integer(*n), state),
+        Some(Token::Decimal(i, f)) => literal(DataLiteral::Decimal(*i, *f), state),
+        Some(Token::Float(f)) => literal(DataLiteral::Float(*f), state),
+        Some(Token::Null) => literal(DataLiteral::Null, state),
+        Some(Token::True) => literal(DataLiteral::True, state),
+        Some(Token::False) => literal(DataLiteral::False, state),
+        Some(Token::Undefined) => literal(DataLiteral::Undefined, state),
+        Some(Token::Bigint(sign, abs)) => literal(DataLiteral::Bigint(*sign, abs.clone()), state),
         Some(Token::Function) => function_expr(state).map(|x| Expr::Function(Box::new(x))),
         _ => use_variable(state).map(|x| Expr::Variable(Box::new(x))),
     }
 }
 
+// JSON value, parsed into the same AST as the equivalent Jessie expression.
+// value ::= null | true | false | string | "-"? number | "[" (value ("," value)*)? "]" | "{" (string ":" value ("," string ":" value)*)? "}"
+// The lexical restrictions(double quoted strings, plain decimal numbers, no comments) are checked by the lexer.
+pub fn json_value(state: &mut ParserState) -> Result<Expr, ParserError> {
+    let start = state.position();
+    match state.lookahead_1() {
+        Some(Token::Null | Token::True | Token::False | Token::String(_) | Token::Integer(_) | Token::Decimal(..) | Token::Float(_)) => primary_expr(state),
+        Some(Token::Minus) => {
+            state.proceed();
+            if !matches!(state.lookahead_1(), Some(Token::Integer(_) | Token::Decimal(..) | Token::Float(_))) {
+                return state.err_invalid("unary operators other than the sign of a number are not allowed in JSON")
+            }
+            // the sign is a part of the number literal in JSON
+            let adjacent = match (state.state.spans.get(start), state.state.spans.get(start + 1)) {
+                (Some(sign), Some(number)) => sign.end == number.start,
+                _ => true,
+            };
+            if !adjacent {
+                return state.err_invalid("whitespace between the sign and the number is not allowed in JSON")
+            }
+            let number = primary_expr(state)?;
+            Ok(Expr::UnaryExpr(Box::new(UnaryExpr { op: Box::new([UnaryOp::Neg]), expr: number, span: state.span_from(start) })))
+        },
+        Some(Token::LeftBracket) => {
+            let elements = repeated_elements(state, Some(Token::LeftBracket), Token::RightBracket, &json_value, false)?;
+            Ok(Expr::Array(Box::new(Array(elements.into_boxed_slice()))))
+        },
+        Some(Token::LeftBrace) => {
+            let props = repeated_elements(state, Some(Token::LeftBrace), Token::RightBrace, &json_member, false)?;
+            Ok(Expr::Record(Box::new(Record(props.into_boxed_slice(), Box::new([]), state.span_from(start)))))
+        },
+        Some(Token::Undefined) => state.err_invalid("undefined is not allowed in JSON"),
+        Some(Token::Bigint(..)) => state.err_invalid("bigint is not allowed in JSON"),
+        Some(Token::Identifier(_)) => state.err_invalid("variable is not allowed in JSON"),
+        Some(Token::EOF) | None => state.err_expected("JSON value", state.lookahead_1()),
+        Some(_) => state.err_invalid("only data is allowed in JSON"),
+    }
+}
+
+fn json_member(state: &mut ParserState) -> Result<PropDef, ParserError> {
+    let name = match state.lookahead_1() {
+        Some(Token::String(s)) => s.clone(),
+        la => return state.err_expected("string key of JSON object", la),
+    };
+    state.proceed();
+    state.consume_1(Token::Colon)?;
+    let value = json_value(state)?;
+    Ok(PropDef::KeyValue(Box::new(Field{name}), value))
+}
+
+// The lexer emits QuasiQuote String (Dollar LeftBrace expr RightBrace String)* QuasiQuote
+pub fn quasi_expr(state: &mut ParserState) -> Result<QuasiExpr, ParserError> {
+    let start = state.position();
+    state.consume_1(Token::QuasiQuote)?;
+
+    let mut strings = vec![];
+    let mut exprs = vec![];
+    loop {
+        match state.lookahead_1() {
+            Some(Token::String(s)) => {
+                let s = s.clone();
+                strings.push(state.proceed_then(s)?)
+            },
+            la => return state.err_expected("template string", la),
+        }
+
+        match state.lookahead_1() {
+            Some(Token::QuasiQuote) => {
+                state.proceed();
+                break
+            },
+            Some(Token::Dollar) => {
+                state.proceed();
+                exprs.push(enclosed_element(state, Token::LeftBrace, Token::RightBrace, &expression)?);
+            },
+            la => return state.err_expected("template substitution or end of template", la),
+        }
+    }
+
+    Ok(QuasiExpr { strings: strings.into_boxed_slice(), exprs: exprs.into_boxed_slice(), span: state.span_from(start) })
+}
+
 pub fn array(state: &mut ParserState) -> Result<Array, ParserError> {
     let elements = repeated_elements(state, Some(Token::LeftBracket), Token::RightBracket, &mut element, true)?.into_boxed_slice();
     Ok(Array(elements))
@@ -270,48 +372,85 @@ pub fn prop_def(state: &mut ParserState) -> Result<PropDef, ParserError> {
         return Ok(PropDef::Spread(expr))
     }
     
-    if state.try_proceed(Token::Get) {
+    let start = state.position();
+    // get and set are plain property names when followed by the value, the parameters of a method named get or
+    // set, or the end of the property
+    let is_accessor = !matches!(state.lookahead_2(), Some(Token::Colon | Token::LeftParen | Token::Comma | Token::RightBrace));
+    if state.lookahead_1() == Some(&Token::Get) && is_accessor {
         // Getter
+        state.require_level(GrammarLevel::Jessie, "getter")?;
+        state.proceed();
         let prop_name = prop_name(state)?;
         state.consume_1(Token::LeftParen)?;
         state.consume_1(Token::RightParen)?;
+        let return_type = optional_type_ann(state)?;
         state.enter_block();
-        let statements = block_raw(state)?;
+        let (statements, spans, comments) = block_raw(state)?;
         let declarations = state.exit_block();
-        let body = Block { statements, declarations};
+        let body = Block::with_spans(declarations, statements, spans).with_comments(comments);
         let getter = Function {
             name: FunctionName::Named(prop_name.name),
             parameters: Box::new([]),
+            signature: Signature::new(Box::new([]), Box::new([]), return_type),
             body: ExprOrBlock::Block(body),
             scope: None,
+            span: state.span_from(start),
+            comments: state.take_comments(start),
         };
         return Ok(PropDef::Getter(Box::new(getter)))
     }
 
-    if state.try_proceed(Token::Set) {
+    if state.lookahead_1() == Some(&Token::Set) && is_accessor {
         // Setter
+        state.require_level(GrammarLevel::Jessie, "setter")?;
+        state.proceed();
         let prop_name = prop_name(state)?;
         state.consume_1(Token::LeftParen)?;
-        let param = param(state)?;
+        let (param, param_type) = typed_param(state)?;
         state.consume_1(Token::RightParen)?;
         state.enter_block();
-        let statements = block_raw(state)?;
+        let (statements, spans, comments) = block_raw(state)?;
         let declarations = state.exit_block();
-        let body = Block { statements, declarations };
+        let body = Block::with_spans(declarations, statements, spans).with_comments(comments);
         let setter = Function {
             name: FunctionName::Named(prop_name.name),
             parameters: Box::new([param]),
+            signature: Signature::new(Box::new([]), Box::new([param_type]), None),
             body: ExprOrBlock::Block(body),
             scope: None,
+            span: state.span_from(start),
+            comments: state.take_comments(start),
         };
         return Ok(PropDef::Setter(Box::new(setter)))
     }
 
+    if state.lookahead_1() == Some(&Token::LeftBracket) {
+        // Computed key
+        let key = enclosed_element(state, Token::LeftBracket, Token::RightBracket, &expression)?;
+        return match state.lookahead_1() {
+            Some(Token::LeftParen) => {
+                state.require_level(GrammarLevel::Jessie, "method")?;
+                let method = function_internal(state, FunctionName::Anonymous, start)?;
+                Ok(PropDef::Computed(key, Expr::Function(Box::new(method))))
+            },
+            Some(Token::Colon) => {
+                state.proceed();
+                let expr = expression(state)?;
+                Ok(PropDef::Computed(key, expr))
+            },
+            la => state.err_expected(": for computed property", la),
+        }
+    }
+
+    // string keys are only allowed with an explicit value
+    let is_string_key = matches!(state.lookahead_1(), Some(Token::String(_)));
     let prop_name = prop_name(state)?;
     match state.lookahead_1() {
         // Method
         Some(Token::LeftParen) => {
-            unimplemented!("method def")
+            state.require_level(GrammarLevel::Jessie, "method")?;
+            let method = function_internal(state, FunctionName::Named(prop_name.name), start)?;
+            Ok(PropDef::MethodDef(Box::new(method)))
         },
         // KeyValue
         Some(Token::Colon) => {
@@ -320,7 +459,7 @@ pub fn prop_def(state: &mut ParserState) -> Result<PropDef, ParserError> {
             Ok(PropDef::KeyValue(prop_name, expr))
         },
         // Shorthand
-        Some(Token::Comma) | Some(Token::RightBrace) => {
+        Some(Token::Comma) | Some(Token::RightBrace) if !is_string_key => {
             //let var = state.scope.use_variable(prop_name.clone().name);
             Ok(PropDef::Shorthand(prop_name.clone(), Box::new(Variable::new(prop_name.name))))
         },
@@ -331,8 +470,26 @@ pub fn prop_def(state: &mut ParserState) -> Result<PropDef, ParserError> {
 }
 
 pub fn record(state: &mut ParserState) -> Result<Record, ParserError> {
-    let props = repeated_elements(state, Some(Token::LeftBrace), Token::RightBrace, &mut prop_def, true)?.into_boxed_slice();
-    Ok(Record(props))
+    let start = state.position();
+    let (props, comments): (Vec<_>, Vec<_>) = repeated_elements(state, Some(Token::LeftBrace), Token::RightBrace, &prop_def_with_comments, true)?.into_iter().unzip();
+    // the closing brace is consumed already
+    let comments = if state.keeps_comments() {
+        let mut comments = comments;
+        let closing = state.position() - 1;
+        comments.push(state.take_comments_in(closing, closing + 1));
+        comments.into_boxed_slice()
+    } else {
+        Box::new([])
+    };
+    Ok(Record(props.into_boxed_slice(), comments, state.span_from(start)))
+}
+
+// a comment after the comma following the property, on the same line, is the property's
+fn prop_def_with_comments(state: &mut ParserState) -> Result<(PropDef, Comments), ParserError> {
+    let start = state.position();
+    let prop = prop_def(state)?;
+    let end = if state.lookahead_1() == Some(&Token::Comma) { state.position() + 1 } else { state.position() };
+    Ok((prop, state.take_comments_in(start, end)))
 }
 /* 
 pub fn pure_prop_def(state: &mut ParserState) -> Result<PropDef, ParserError> {
@@ -341,7 +498,7 @@ pub fn pure_prop_def(state: &mut ParserState) -> Result<PropDef, ParserError> {
         Ok(PropDef::Spread(expr))
     } else {
         let prop_name = prop_name(state)?;
-        if state.lookahead_1() == Some(Token::LeftParen) {
+        if state.lookahead_1() == Some(&Token::LeftParen) {
             unimplemented!()
             /* 
             let method_def = method_def(state)?;
@@ -383,14 +540,24 @@ pub fn prop_name(state: &mut ParserState) -> Result<PropName, ParserError> {
 pub fn prop_name(state: &mut ParserState) -> Result<Box<Field>, ParserError> {
     match state.lookahead_1() {
         Some(Token::Identifier(s)) => {
+            let name = s.clone();
             state.proceed();
-            Ok(Box::new(Field{name: s}))
+            Ok(Box::new(Field{name}))
+        },
+        Some(Token::Get) => {
+            state.proceed();
+            Ok(Box::new(Field{name: "get".into()}))
+        },
+        Some(Token::Set) => {
+            state.proceed();
+            Ok(Box::new(Field{name: "set".into()}))
         },
-        /* 
         Some(Token::String(s)) => {
+            let name = s.clone();
             state.proceed();
-            Ok(Box::new(Field::String(s)))
+            Ok(Box::new(Field{name}))
         },
+        /* 
         Some(Token::Integer(s)) => {
             state.proceed();
             Ok(Box::new(Field::Number(s)))
@@ -1,7 +1,7 @@
 use std::rc::Rc;
 
 use jessie_ast::*;
-use crate::{jessie_parser::{JessieParserState, repeated_elements}, Token, statement::{block_raw, self}, expression::{prop_name, expression}, common::use_variable, parser, pattern::{pattern, param}};
+use crate::{jessie_parser::{JessieParserState, GrammarLevel, repeated_elements}, Token, statement::block_raw, expression::{prop_name, expression}, common::{use_variable, optional_type_ann}, parser, pattern::{pattern, typed_param}, types::{type_params, type_ann}};
 
 type ParserState = JessieParserState;
 type ParserError = parser::ParserError<Option<Token>>;
@@ -9,15 +9,17 @@ type ParserError = parser::ParserError<Option<Token>>;
 
 
 pub fn function_expr(state: &mut ParserState) -> Result<Function, ParserError> {
+    let start = state.position();
     state.consume_1(Token::Function)?;
     let name = if let Some(Token::Identifier(name)) = state.lookahead_1() {
+        let name = name.clone();
         state.proceed();
         FunctionName::Named(name)
     } else {
         FunctionName::Anonymous
     };
 
-    let function = function_internal(state, name)?;
+    let function = function_internal(state, name, start)?;
 
     // Named function expr should be only locally bound. TODO.
     // For now recursive call is not supported for function expressions
@@ -26,26 +28,34 @@ pub fn function_expr(state: &mut ParserState) -> Result<Function, ParserError> {
     Ok(function)
 }
 
-pub fn function_internal(state: &mut ParserState, name: FunctionName) -> Result<Function, ParserError> {
+// start is the token position where the function begins, for the span
+pub fn function_internal(state: &mut ParserState, name: FunctionName, start: usize) -> Result<Function, ParserError> {
 
     println!("function_internal");
     state.enter_block();
 
-    let parameters = repeated_elements
-    (state, Some(Token::LeftParen), Token::RightParen, &param, true/*Check it*/)?.into_boxed_slice();
+    let type_params = if state.level >= GrammarLevel::Tessie { type_params(state)? } else { Box::new([]) };
+    let (parameters, parameter_types): (Vec<_>, Vec<_>) = repeated_elements
+    (state, Some(Token::LeftParen), Token::RightParen, &typed_param, true/*Check it*/)?.into_iter().unzip();
+    let parameters = parameters.into_boxed_slice();
 
     println!("parameters {:?}", parameters);
 
     // TODO: spread parameter can only come at the end
 
-    let statements = block_raw(state)?;
+    let return_type = optional_type_ann(state)?;
+
+    let (statements, spans, comments) = block_raw(state)?;
     let declarations = state.exit_block();
-    let statements = Block { declarations, statements };
+    let statements = Block::with_spans(declarations, statements, spans).with_comments(comments);
     let func = Function {
         name: name,
         parameters,
+        signature: Signature::new(type_params, parameter_types.into_boxed_slice(), return_type),
         body: ExprOrBlock::Block(statements),
         scope: None,
+        span: state.span_from(start),
+        comments: state.take_comments(start),
     };
     Ok(func)
 }
@@ -64,17 +74,14 @@ pub fn prop_param(state: &mut ParserState) -> Result<PropParam, ParserError> {
             Ok(PropParam::KeyValue(prop_name, pattern(state)?))
         },
         Some(Token::LeftParen) => {
-            unimplemented!("method def")
-            /* 
-            let method_def = method_def(state)?;
-            Ok(PropDef::MethodDef(method_def))
-            */
+            // methods are only allowed in record literals
+            state.err_expected(": for property pair", Some(&Token::LeftParen))
         },
         Some(Token::Comma) | Some(Token::RightBrace) => {
             Ok(PropParam::Shorthand(prop_name.clone(), Box::new(Variable::new(prop_name.name))))
         },
         Some(Token::QuasiQuote) => {
-            unimplemented!("quasiquote")
+            state.err_unimplemented("quasiquote")
         },
         la => {
             state.err_expected(": for property pair", la)
@@ -82,36 +89,104 @@ pub fn prop_param(state: &mut ParserState) -> Result<PropParam, ParserError> {
     }
 }
 
-pub fn arrow_function_body(state: &mut ParserState) -> Result<Box<[Statement]>, ParserError> {
+pub fn arrow_function_body(state: &mut ParserState) -> Result<(Box<[Statement]>, Box<[Span]>, Box<[Comments]>), ParserError> {
     match state.lookahead_1() {
         Some(Token::LeftBrace) => {
             block_raw(state)
         },
         _ => {
+            let start = state.position();
             let expr = expression(state)?;
-            Ok(Box::new([Statement::Return(Box::new(expr))]))
+            // the comments in a concise body are left for the function
+            Ok((Box::new([Statement::Return(Box::new(expr))]), Box::new([state.span_from(start)]), Box::new([])))
         }
     }
 }
 
 pub fn arrow_expr(state: &mut ParserState) -> Result<Expr, ParserError> { 
-    let parameters = repeated_elements(state, Some(Token::ArrowLeftParen), Token::ArrowRightParen, &param, true)?.into_boxed_slice();
+    let start = state.position();
+    let (parameters, parameter_types): (Vec<_>, Vec<_>) = match state.lookahead_1() {
+        // a single parameter without parentheses
+        Some(Token::Identifier(_)) => (vec![pattern(state)?], vec![None]),
+        _ => repeated_elements(state, Some(Token::ArrowLeftParen), Token::ArrowRightParen, &typed_param, true)?.into_iter().unzip(),
+    };
     if !state.try_proceed(Token::FatArrow) {
         let la = state.lookahead_1();
         return state.err_expected("=>", la)
     }
 
+    let signature = Signature::new(Box::new([]), parameter_types.into_boxed_slice(), None);
+    arrow_body(state, parameters.into_boxed_slice(), signature, start)
+}
+
+// An arrow function with a return type, (x: T): U => ..., at the Tessie level.
+// The lexer marks the parentheses of an arrow function only when => follows right after them, so these come as plain
+// parentheses, and the parser backtracks when they turn out to be a parenthesized expression followed by the : of a
+// conditional expression. Once the => is found, errors in the body are reported as they are.
+pub fn typed_arrow_expr(state: &mut ParserState) -> Result<Option<Expr>, ParserError> {
+    if state.level < GrammarLevel::Tessie || !is_followed_by_colon(state) {
+        return Ok(None)
+    }
+
+    let start = state.position();
+    let depth = state.scope.len();
+    let splits = state.splits.len();
+    let head = (|| {
+        let (parameters, parameter_types): (Vec<_>, Vec<_>) = repeated_elements(state, Some(Token::LeftParen), Token::RightParen, &typed_param, true)?.into_iter().unzip();
+        state.consume_1(Token::Colon)?;
+        let return_type = type_ann(state)?;
+        state.consume_1(Token::FatArrow)?;
+        Ok::<_, ParserError>((parameters, parameter_types, return_type))
+    })();
+
+    match head {
+        Ok((parameters, parameter_types, return_type)) => {
+            let signature = Signature::new(Box::new([]), parameter_types.into_boxed_slice(), Some(return_type));
+            arrow_body(state, parameters.into_boxed_slice(), signature, start).map(Some)
+        },
+        Err(_) => {
+            state.state.pos = start;
+            state.scope.truncate(depth);
+            state.undo_splits(splits);
+            Ok(None)
+        },
+    }
+}
+
+// whether the parenthesis at the next token is closed by one followed by :
+fn is_followed_by_colon(state: &ParserState) -> bool {
+    let mut depth = 0;
+    for (i, token) in state.state.input.iter().enumerate().skip(state.position()) {
+        match token {
+            Token::LeftParen | Token::ArrowLeftParen => depth += 1,
+            Token::RightParen | Token::ArrowRightParen => {
+                depth -= 1;
+                if depth == 0 {
+                    return state.state.input.get(i + 1) == Some(&Token::Colon)
+                }
+            },
+            Token::EOF => return false,
+            _ => {},
+        }
+    }
+    false
+}
+
+fn arrow_body(state: &mut ParserState, parameters: Box<[Pattern]>, signature: Option<Box<Signature>>, start: usize) -> Result<Expr, ParserError> {
     state.enter_block();
-    let statements = arrow_function_body(state)?;
+    let (statements, spans, comments) = arrow_function_body(state)?;
     let declarations = state.exit_block();
 
-    let body = Block { statements, declarations };
+    let body = Block::with_spans(declarations, statements, spans).with_comments(comments);
 
     let function = Function {
         name: FunctionName::Arrow,
         parameters,
+        signature,
         body: ExprOrBlock::Block(body),
         scope: None,
+        span: state.span_from(start),
+        comments: state.take_comments(start),
     };
 
     Ok(Expr::Function(Box::new(function)))
@@ -0,0 +1,309 @@
+// Incremental reparsing for editors.
+// An edit relexes the text from the start of the top-level statement before the edit, until the lexer
+// reaches the start of an old top-level statement after the edit in a clean state. The statements are
+// reparsed over the same range, and the old tokens and statements after it are reused with their spans shifted.
+// The result is always the same as lexing and parsing the new source from scratch.
+
+use std::{convert::Infallible, ops::Range};
+
+use jessie_ast::*;
+
+use crate::lexer::{lex_jessie_with_spans, lex_until, Lexer, Position, Token};
+use crate::statement::statement_item;
+use crate::{parser, JessieParserState};
+
+type ParserError = parser::ParserError<Option<Token>>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReparseError {
+    Lexer(String),
+    Parser(ParserError),
+}
+
+// Replaces the byte range of the source with the replacement.
+// The range must lie on character boundaries of the source.
+#[derive(Debug, Clone)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub replacement: String,
+}
+
+pub struct IncrementalScript {
+    source: String,
+    // Err if the source does not lex or parse, the next edit then parses from scratch
+    parsed: Result<Parsed, ReparseError>,
+}
+
+struct Parsed {
+    tokens: Vec<Token>,
+    spans: Vec<Span>,
+    script: Script,
+    // index of the first token of each top-level statement
+    statement_tokens: Vec<usize>,
+}
+
+impl IncrementalScript {
+    pub fn new(source: String) -> Self {
+        let parsed = parse_full(&source);
+        IncrementalScript { source, parsed }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn script(&self) -> Result<&Script, &ReparseError> {
+        self.parsed.as_ref().map(|parsed| &parsed.script)
+    }
+
+    // tokens along with their spans, as returned by lex_jessie_with_spans
+    pub fn tokens(&self) -> Result<(&[Token], &[Span]), &ReparseError> {
+        self.parsed.as_ref().map(|parsed| (&parsed.tokens[..], &parsed.spans[..]))
+    }
+
+    pub fn edit(&mut self, edit: TextEdit) -> Result<&Script, &ReparseError> {
+        let previous = std::mem::replace(&mut self.parsed, Err(ReparseError::Lexer(String::new())));
+        self.parsed = match previous {
+            Ok(parsed) => reparse(&mut self.source, parsed, &edit),
+            Err(_) => {
+                self.source.replace_range(edit.range.clone(), &edit.replacement);
+                parse_full(&self.source)
+            }
+        };
+        self.script()
+    }
+}
+
+fn parse_full(source: &str) -> Result<Parsed, ReparseError> {
+    let (tokens, spans) = lex_jessie_with_spans(source).map_err(ReparseError::Lexer)?;
+    let mut state = JessieParserState::with_spans(tokens, spans);
+    let (statements, statement_spans, statement_tokens) = parse_statements(&mut state, |_| false)?;
+    let script = Script { statements: top_level_block(statements, statement_spans) };
+    let JessieParserState { state: parser::ParserState { input: tokens, spans, .. }, .. } = state;
+    Ok(Parsed { tokens, spans, script, statement_tokens })
+}
+
+// Parses top-level statements until the end of input, or until stop returns true for the next token index.
+fn parse_statements(state: &mut JessieParserState, mut stop: impl FnMut(usize) -> bool) -> Result<(Vec<Statement>, Vec<Span>, Vec<usize>), ReparseError> {
+    state.enter_block();
+    let mut statements = vec![];
+    let mut spans = vec![];
+    let mut starts = vec![];
+    while !matches!(state.lookahead_1(), Some(Token::EOF) | None) && !stop(state.position()) {
+        let start = state.position();
+        statements.push(statement_item(state).map_err(ReparseError::Parser)?);
+        spans.push(state.span_from(start));
+        starts.push(start);
+    }
+    state.exit_block();
+    Ok((statements, spans, starts))
+}
+
+// Same as the block built by jessie_parser::script, each top-level declaration statement declares in the block.
+fn top_level_block(statements: Vec<Statement>, spans: Vec<Span>) -> Block {
+    let declarations = statements.iter().filter_map(|statement| match statement {
+        Statement::LocalDeclaration(decl) => Some((**decl).clone()),
+        _ => None,
+    }).collect();
+    Block::with_spans(declarations, statements.into_boxed_slice(), spans.into_boxed_slice())
+}
+
+fn reparse(source: &mut String, parsed: Parsed, edit: &TextEdit) -> Result<Parsed, ReparseError> {
+    let Parsed { mut tokens, mut spans, script, mut statement_tokens } = parsed;
+    // the tokens end with EOF, without any there is nothing to resynchronize with
+    if tokens.is_empty() {
+        source.replace_range(edit.range.clone(), &edit.replacement);
+        return parse_full(source)
+    }
+    let Block { statements, spans: statement_spans, .. } = script.statements;
+    let mut statements = statements.into_vec();
+    let mut statement_spans = statement_spans.into_vec();
+
+    // The statement right before the edit is reparsed as well, its end may depend on the following tokens(e.g. else).
+    let touched = statement_spans.iter().position(|span| span.end as usize >= edit.range.start).unwrap_or(statements.len());
+    let mut first = touched.saturating_sub(1);
+    let mut start = statement_tokens.get(first).copied().unwrap_or(tokens.len() - 1);
+    if spans[start].start as usize > edit.range.start {
+        // the edit is in the leading whitespace or comments of the script
+        first = 0;
+        start = 0;
+    }
+    let start_position = if start == 0 {
+        Position { offset: 0, line: 1, column: 1 }
+    } else {
+        Position { offset: spans[start].start, line: spans[start].line, column: spans[start].column }
+    };
+
+    let old_end = advance(start_position, &source[start_position.offset as usize..edit.range.end]);
+    source.replace_range(edit.range.clone(), &edit.replacement);
+    let new_end = advance(start_position, &source[start_position.offset as usize..edit.range.start + edit.replacement.len()]);
+    let mut shift = Shift { old_end, new_end };
+
+    // Relex until the start of an old statement after the edit, the old tokens from there on stay the same.
+    let mut lexer = Lexer::starting_at(source, start_position);
+    let mut relexed = vec![];
+    let mut resync = statements.len();
+    let stopped = lex_until(&mut lexer, &mut relexed, |offset| {
+        if offset < new_end.offset as usize {
+            return false
+        }
+        let old_offset = offset - new_end.offset as usize + old_end.offset as usize;
+        match statement_tokens[first..].binary_search_by_key(&old_offset, |index| spans[*index].start as usize) {
+            Ok(found) => {
+                resync = first + found;
+                true
+            },
+            Err(_) => false,
+        }
+    }).map_err(|err| {
+        let span = lexer.span_from(lexer.position());
+        ReparseError::Lexer(format!("{} at {}:{}", err, span.line, span.column))
+    })?;
+    let mut relexed_spans = lexer.spans;
+    let end = if stopped { statement_tokens[resync] } else { tokens.len() };
+
+    for span in spans[end..].iter_mut() {
+        shift.span(span);
+    }
+    let relexed_len = relexed.len();
+    tokens.splice(start..end, relexed);
+    spans.splice(start..end, relexed_spans.drain(..));
+
+    // token index of the reused old statements in the new token stream
+    let offset_index = |index: usize| index - end + start + relexed_len;
+
+    // Reparse from the same statement, until landing on the start of a reused statement.
+    let mut state = JessieParserState::with_spans(tokens, spans);
+    state.state.pos = start;
+    let mut reuse = resync;
+    let parsed = parse_statements(&mut state, |position| {
+        while reuse < statements.len() && offset_index(statement_tokens[reuse]) < position {
+            reuse += 1;
+        }
+        (reuse < statements.len()) && offset_index(statement_tokens[reuse]) == position
+    });
+    let (new_statements, new_spans, new_starts) = parsed?;
+    if matches!(state.lookahead_1(), Some(Token::EOF) | None) {
+        reuse = statements.len();
+    }
+
+    let mut tail = statements.split_off(reuse);
+    for statement in tail.iter_mut() {
+        let Ok(()) = shift.visit_statement(statement);
+    }
+    statements.truncate(first);
+    statements.extend(new_statements);
+    statements.extend(tail);
+
+    let mut tail_spans = statement_spans.split_off(reuse);
+    for span in tail_spans.iter_mut() {
+        shift.span(span);
+    }
+    statement_spans.truncate(first);
+    statement_spans.extend(new_spans);
+    statement_spans.extend(tail_spans);
+
+    let tail_tokens: Vec<usize> = statement_tokens.split_off(reuse).into_iter().map(offset_index).collect();
+    statement_tokens.truncate(first);
+    statement_tokens.extend(new_starts);
+    statement_tokens.extend(tail_tokens);
+
+    let JessieParserState { state: parser::ParserState { input: tokens, spans, .. }, .. } = state;
+    Ok(Parsed { tokens, spans, script: Script { statements: top_level_block(statements, statement_spans) }, statement_tokens })
+}
+
+// Position after the text, counted the same way as the lexer does.
+fn advance(mut position: Position, text: &str) -> Position {
+    for c in text.chars() {
+        position.offset += c.len_utf8() as u32;
+        if c == '\n' {
+            position.line += 1;
+            position.column = 1;
+        } else {
+            position.column += 1;
+        }
+    }
+    position
+}
+
+// Moves the spans after the edit, from where the edit ended in the old source to where it ends in the new one.
+struct Shift {
+    old_end: Position,
+    new_end: Position,
+}
+
+impl Shift {
+    fn span(&self, span: &mut Span) {
+        if span.is_unknown() {
+            return
+        }
+        let moved = |value: u32, from: u32, to: u32| (value as i64 + to as i64 - from as i64) as u32;
+        span.start = moved(span.start, self.old_end.offset, self.new_end.offset);
+        span.end = moved(span.end, self.old_end.offset, self.new_end.offset);
+        if span.line == self.old_end.line {
+            span.column = moved(span.column, self.old_end.column, self.new_end.column);
+        }
+        span.line = moved(span.line, self.old_end.line, self.new_end.line);
+    }
+}
+
+// Every span in the reused statements is shifted, block declarations included.
+impl VisitMut for Shift {
+    type Error = Infallible;
+
+    fn visit_block(&mut self, block: &mut Block) -> Result<(), Infallible> {
+        for span in block.spans.iter_mut() {
+            self.span(span);
+        }
+        self.walk_block(block)?;
+        // the declarations are clones of the statements, except for the functions which are shared with them
+        for decl in block.declarations.iter_mut() {
+            if !matches!(decl, Declaration::Function(_)) {
+                self.visit_declaration(decl)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn visit_expr(&mut self, expr: &mut Expr) -> Result<(), Infallible> {
+        if let Expr::DataLiteral(literal) = expr {
+            self.span(&mut literal.span);
+        }
+        self.walk_expr(expr)
+    }
+
+    fn visit_record(&mut self, record: &mut Record) -> Result<(), Infallible> {
+        self.span(&mut record.2);
+        self.walk_record(record)
+    }
+
+    fn visit_function(&mut self, function: &mut Function) -> Result<(), Infallible> {
+        self.span(&mut function.span);
+        self.walk_function(function)
+    }
+
+    fn visit_unary_expr(&mut self, unary: &mut UnaryExpr) -> Result<(), Infallible> {
+        self.span(&mut unary.span);
+        self.walk_unary_expr(unary)
+    }
+
+    fn visit_call_expr(&mut self, call: &mut CallExpr) -> Result<(), Infallible> {
+        self.span(&mut call.span);
+        self.walk_call_expr(call)
+    }
+
+    fn visit_call_lvalue(&mut self, call: &mut CallLValue) -> Result<(), Infallible> {
+        self.span(&mut call.span);
+        self.walk_call_lvalue(call)
+    }
+
+    fn visit_quasi_expr(&mut self, quasi: &mut QuasiExpr) -> Result<(), Infallible> {
+        self.span(&mut quasi.span);
+        self.walk_quasi_expr(quasi)
+    }
+
+    fn visit_variable(&mut self, variable: &mut Variable) -> Result<(), Infallible> {
+        self.span(&mut variable.span);
+        Ok(())
+    }
+}
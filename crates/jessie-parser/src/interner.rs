@@ -0,0 +1,20 @@
+use std::rc::Rc;
+
+use fxhash::FxHashSet;
+
+// Deduplicates strings, so that every occurrence of the same identifier shares one Rc<str>.
+#[derive(Debug, Default)]
+pub struct Interner {
+    strings: FxHashSet<Rc<str>>,
+}
+
+impl Interner {
+    pub fn intern(&mut self, s: &str) -> Rc<str> {
+        if let Some(interned) = self.strings.get(s) {
+            return interned.clone()
+        }
+        let interned: Rc<str> = s.into();
+        self.strings.insert(interned.clone());
+        interned
+    }
+}
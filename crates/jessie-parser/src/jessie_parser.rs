@@ -1,19 +1,59 @@
 use std::fmt::Debug;
 use std::mem::replace;
 use std::rc::Rc;
+use std::collections::HashMap;
 
 use crate::parser::{self, ParserState}; 
-use crate::lexer::{Token};
+use crate::lexer::{Token, lex_with_level};
 use jessie_ast::*;
 use utils::{MapPool,  Map};
 
 type ParserError = parser::ParserError<Option<Token>>;
 
+// Subsets of the language accepted by the parser, each level includes the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GrammarLevel {
+    // strict JSON, data only
+    Json,
+    // JSON with comments, undefined, template literals, operators, variables and calls,
+    // a single expression without functions or assignments
+    Justin,
+    // full Jessie
+    Jessie,
+    // Jessie with TypeScript-style type annotations, type aliases and interfaces
+    Tessie,
+}
+
+impl std::fmt::Display for GrammarLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GrammarLevel::Json => write!(f, "JSON"),
+            GrammarLevel::Justin => write!(f, "Justin"),
+            GrammarLevel::Jessie => write!(f, "Jessie"),
+            GrammarLevel::Tessie => write!(f, "Tessie"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct JessieParserState {
     pub state: ParserState<Token>,
 
+    // constructs above this level are rejected
+    pub level: GrammarLevel,
+
     pub scope: Vec<Vec<Declaration>>,
+
+    // In recovery mode, failed statements are replaced with Statement::Error
+    // and the errors are collected here instead of aborting the parse.
+    pub recovering: bool,
+    pub diagnostics: Vec<ParserError>,
+
+    // (position, original token) of the tokens split by split_angle, to be merged back on backtracking
+    pub splits: Vec<(usize, Token)>,
+
+    // comments by the byte offset of the token they are attached to, None if comments are not kept
+    pub comments: Option<HashMap<u32, Vec<Comment>>>,
 }
 
 impl JessieParserState {
@@ -28,19 +68,109 @@ impl JessieParserState {
     pub fn new(tokens: Vec<Token>) -> JessieParserState {
         JessieParserState {
             state: ParserState::new(tokens),
+            level: GrammarLevel::Jessie,
             scope: Vec::new(),
+            recovering: false,
+            diagnostics: Vec::new(),
+            splits: Vec::new(),
+            comments: None,
         }
     }
 
+    // spans are as returned by lexer::lex_jessie_with_spans
+    pub fn with_spans(tokens: Vec<Token>, spans: Vec<Span>) -> JessieParserState {
+        JessieParserState {
+            state: ParserState::with_spans(tokens, spans),
+            level: GrammarLevel::Jessie,
+            scope: Vec::new(),
+            recovering: false,
+            diagnostics: Vec::new(),
+            splits: Vec::new(),
+            comments: None,
+        }
+    }
+
+    // Lexes and parses at the same level. This is the only way to set the level, so the parser never gets the
+    // tokens lexed at a wider level(comments or single quoted strings for JSON).
+    pub fn from_source(input: impl AsRef<str>, level: GrammarLevel) -> Result<JessieParserState, String> {
+        let (tokens, spans) = lex_with_level(input, level)?;
+        Ok(JessieParserState { level, ..JessieParserState::with_spans(tokens, spans) })
+    }
+
+    // comments are as returned by lexer::lex_with_comments, along with the spans
+    pub fn with_comments(self, comments: Vec<(u32, Comment)>) -> JessieParserState {
+        let mut attached: HashMap<u32, Vec<Comment>> = HashMap::new();
+        for (offset, comment) in comments {
+            attached.entry(offset).or_default().push(comment);
+        }
+        JessieParserState { comments: Some(attached), ..self }
+    }
+
+    pub fn keeps_comments(&self) -> bool {
+        self.comments.is_some()
+    }
+
+    // Takes the comments attached to the tokens of the node started at token position start and ending at the last
+    // consumed token. The nodes take them once they are parsed, so the innermost node gets a comment first.
+    pub fn take_comments(&mut self, start: usize) -> Comments {
+        self.take_comments_in(start, self.position())
+    }
+
+    // The comments taken by each element of a block, record or module, followed by the ones attached to the closing
+    // token at the current position. Empty if comments are not kept.
+    pub fn closing_comments(&mut self, mut comments: Vec<Comments>) -> Box<[Comments]> {
+        if !self.keeps_comments() {
+            return Box::new([])
+        }
+        let pos = self.position();
+        comments.push(self.take_comments_in(pos, pos + 1));
+        comments.into_boxed_slice()
+    }
+
+    // Takes the comments attached to the tokens from start to end, exclusive.
+    pub fn take_comments_in(&mut self, start: usize, end: usize) -> Comments {
+        let Some(attached) = &mut self.comments else {
+            return Box::new([])
+        };
+        let mut comments = Vec::new();
+        for span in self.state.spans.get(start..end).unwrap_or_default() {
+            if let Some(taken) = attached.remove(&span.start) {
+                comments.extend(taken);
+            }
+        }
+        comments.into_boxed_slice()
+    }
+
+    // Rejects the construct starting at the next token if it is above the current level.
+    pub fn require_level(&self, level: GrammarLevel, construct: &'static str) -> Result<(), ParserError> {
+        if self.level < level {
+            return self.err_invalid(format!("{} is not allowed in {}", construct, self.level))
+        }
+        Ok(())
+    }
+
+    pub fn position(&self) -> usize {
+        self.state.pos
+    }
+
+    pub fn span(&self) -> Span {
+        self.state.span()
+    }
+
+    pub fn span_from(&self, start: usize) -> Span {
+        self.state.span_from(start)
+    }
+
     pub fn consume_1(&mut self, token: Token) -> Result<(), ParserError> {
         self.state.consume_1(token)
     }
 
-    pub fn lookahead_1(&mut self) -> Option<Token> {
+    // tokens are borrowed from the token stream, clone only what is kept in the AST
+    pub fn lookahead_1(&self) -> Option<&Token> {
         self.state.lookahead_1()
     }
 
-    pub fn lookahead_2(&mut self) -> Option<Token> {
+    pub fn lookahead_2(&self) -> Option<&Token> {
         self.state.lookahead_2()
     }
 
@@ -56,8 +186,93 @@ impl JessieParserState {
         self.state.proceed_then(r)
     }
 
-    pub fn err_expected<T>(&mut self, expected: &'static str, found: Option<Token>) -> Result<T, ParserError> {
-        self.state.err_expected(expected, found)
+    pub fn err_expected<T>(&self, expected: &'static str, found: Option<&Token>) -> Result<T, ParserError> {
+        self.state.err_expected(expected, found.cloned())
+    }
+
+    pub fn err_invalid<T>(&self, message: impl Into<String>) -> Result<T, ParserError> {
+        self.state.err_invalid(message)
+    }
+
+    pub fn err_unimplemented<T>(&mut self, message: &'static str) -> Result<T, ParserError> {
+        self.state.err_unimplemented(message)
+    }
+
+    // Splits the leading > off a >>, >>> or >= token, so that the > can close type arguments as in Array<Array<T>>.
+    pub fn split_angle(&mut self) {
+        let pos = self.state.pos;
+        let rest = match self.state.input.get(pos) {
+            Some(Token::RAngleRAngle) => Token::RAngle,
+            Some(Token::RAngleRAngleRAngle) => Token::RAngleRAngle,
+            Some(Token::RAngleEqual) => Token::Equal,
+            _ => return,
+        };
+        let original = replace(&mut self.state.input[pos], Token::RAngle);
+        self.state.input.insert(pos + 1, rest);
+        if let Some(span) = self.state.spans.get(pos).cloned() {
+            self.state.spans[pos] = Span::new(span.start, span.start + 1, span.line, span.column);
+            self.state.spans.insert(pos + 1, Span::new(span.start + 1, span.end, span.line, span.column + 1));
+        }
+        self.splits.push((pos, original));
+    }
+
+    // Merges back the tokens split after the splits log had the given length.
+    pub fn undo_splits(&mut self, len: usize) {
+        while self.splits.len() > len {
+            let (pos, original) = self.splits.pop().unwrap();
+            self.state.input[pos] = original;
+            self.state.input.remove(pos + 1);
+            if self.state.spans.len() > pos + 1 {
+                let rest = self.state.spans.remove(pos + 1);
+                self.state.spans[pos].end = rest.end;
+            }
+        }
+    }
+
+    // Handles an error of the statement started at token position start, with the block scope depth.
+    // In recovery mode the error is recorded, the parser skips to the next statement boundary and Ok is returned.
+    pub fn recover(&mut self, err: ParserError, start: usize, depth: usize) -> Result<(), ParserError> {
+        if !self.recovering {
+            return Err(err)
+        }
+
+        // blocks entered by the failed statement are never exited
+        self.scope.truncate(depth);
+        self.diagnostics.push(err);
+        self.synchronize(start);
+        Ok(())
+    }
+
+    // Skips tokens until a statement boundary: after a ';' or a balanced '}' outside of braces,
+    // or before a '}' closing the enclosing block. Always makes progress unless at the end of input.
+    fn synchronize(&mut self, start: usize) {
+        let mut depth = 0;
+        loop {
+            match self.lookahead_1() {
+                None | Some(Token::EOF) => return,
+                Some(Token::Semicolon) if depth == 0 => {
+                    self.proceed();
+                    return
+                },
+                Some(Token::LeftBrace) => depth += 1,
+                Some(Token::RightBrace) if depth == 0 => {
+                    // a stray '}' at the start of the statement is skipped, otherwise it belongs to the enclosing block
+                    if self.position() == start {
+                        self.proceed();
+                    }
+                    return
+                },
+                Some(Token::RightBrace) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.proceed();
+                        return
+                    }
+                },
+                _ => {},
+            }
+            self.proceed();
+        }
     }
 }
 
@@ -90,7 +305,7 @@ pub fn repeated_elements<Data: Debug>(
     loop { // I don't like having loop here
         println!("loop {:?}", elements);
         // consume_whitespace(state);
-        if state.lookahead_1() == Some(close.clone()) {
+        if state.lookahead_1() == Some(&close) {
             state.proceed();
             break;
         }
@@ -100,12 +315,12 @@ pub fn repeated_elements<Data: Debug>(
         println!("element end");
         // consume_whitespace(state);
         if state.try_proceed(Token::Comma) {
-            if state.lookahead_1() == Some(close.clone()) {
+            if state.lookahead_1() == Some(&close) {
                 if trailing {
                     state.proceed();
                     break;
                 } else {
-                    return state.err_expected("no trailing comma", Some(Token::Comma))
+                    return state.err_expected("no trailing comma", Some(&Token::Comma))
                 }
             } 
         } else if state.try_proceed(close.clone()) {
@@ -143,3 +358,71 @@ pub fn module_binding(state: &mut ParserState, proxy: MutableDeclarationPointer)
 }
 */
 
+#[cfg(test)]
+mod tests {
+    use crate::{lexer::{lex_jessie, lex_with_comments}, printer};
+
+    use super::*;
+
+    #[test]
+    fn test_comments() {
+        fn parse(code: &str) -> Script {
+            let (tokenstream, spans, comments) = lex_with_comments(code, GrammarLevel::Jessie).unwrap_or_else(|err| panic!("{}\n{}", err, code));
+            let mut state = JessieParserState::with_spans(tokenstream, spans).with_comments(comments);
+            crate::script(&mut state).unwrap_or_else(|err| panic!("{:?}\n{}", err, code))
+        }
+        fn texts(comments: &[Comment]) -> Vec<(&str, bool)> {
+            comments.iter().map(|comment| (&*comment.text, comment.trailing)).collect()
+        }
+
+        let code = "// Copyright\n\n/**\n * Adds.\n * @param a first\n * @export\n */\nfunction add(a, b) {\n    return a + b; // sum\n    // end of add\n}\nconst o = {\n    // the answer\n    x: 42, // trailing\n    /* m */ f() {\n        return 1;\n    }\n};\nconst g = /* inline */ (x) => x;\n// end\n";
+        let script = parse(code);
+        let block = &script.statements;
+
+        // the innermost node starting with the commented token gets the comments
+        let Statement::LocalDeclaration(decl) = &block.statements[0] else { unreachable!() };
+        let Declaration::Function(add) = &**decl else { unreachable!() };
+        let add = add.borrow();
+        assert_eq!(block.comments_of(0), []);
+        assert_eq!(texts(&add.comments)[0], (" Copyright", false));
+        assert!(add.comments[1].is_doc());
+        assert_eq!(add.comments[1].pragmas().collect::<Vec<_>>(), ["param", "export"]);
+        assert!(has_pragma(&add.comments, "export"));
+        let ExprOrBlock::Block(body) = &add.body else { unreachable!() };
+        assert_eq!(texts(body.comments_of(0)), [(" sum", true)]);
+        assert_eq!(texts(body.comments_of(1)), [(" end of add", false)]);
+
+        let Statement::LocalDeclaration(decl) = &block.statements[1] else { unreachable!() };
+        let Declaration::Const(decls) = &**decl else { unreachable!() };
+        let Some(Expr::Record(record)) = &decls[0].value else { unreachable!() };
+        assert_eq!(texts(&record.1[0]), [(" the answer", false), (" trailing", true)]);
+        assert_eq!(texts(&record.1[1]), []);
+        let PropDef::MethodDef(method) = &record.0[1] else { unreachable!() };
+        assert_eq!(texts(&method.comments), [(" m ", false)]);
+
+        let Statement::LocalDeclaration(decl) = &block.statements[2] else { unreachable!() };
+        let Declaration::Const(decls) = &**decl else { unreachable!() };
+        let Some(Expr::Function(arrow)) = &decls[0].value else { unreachable!() };
+        assert_eq!(texts(&arrow.comments), [(" inline ", false)]);
+        assert_eq!(texts(block.comments_of(3)), [(" end", false)]);
+
+        // a block comment is leading the token on its line, else trailing the one before
+        let inline = parse("f(/* a */ x); /* b */ // c\n/* d */ g();");
+        assert_eq!(texts(inline.statements.comments_of(0)), [(" a ", false), (" b ", true), (" c", true)]);
+        assert_eq!(texts(inline.statements.comments_of(1)), [(" d ", false)]);
+
+        // the printer keeps them, and printing is stable
+        let printed = printer::print_script(&script);
+        assert_eq!(parse(&printed).content_hash(), script.content_hash(), "{}", printed);
+        assert_eq!(printer::print_script(&parse(&printed)), printed);
+        assert!(printed.starts_with("// Copyright\n/**\n * Adds."), "{}", printed);
+        assert!(printed.contains("return a + b; // sum\n    // end of add\n}"), "{}", printed);
+        assert!(printed.contains("const g = /* inline */ (x) => x;"), "{}", printed);
+
+        // comments are dropped unless asked for
+        let mut state = JessieParserState::new(lex_jessie(code).unwrap());
+        let plain = crate::script(&mut state).unwrap();
+        assert!(plain.statements.comments.is_empty());
+        assert_eq!(printer::print_script(&plain), printer::print_script(&parse("function add(a, b) { return a + b; } const o = { x: 42, f() { return 1; } }; const g = (x) => x;")));
+    }
+}
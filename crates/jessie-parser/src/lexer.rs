@@ -1,58 +1,230 @@
-// just wrap the parser state to accumulate the tokens
+// just wrap the input string to accumulate the tokens
 
 use std::{fmt::{Debug, Display}, rc::Rc};
 
-use crate::parser::ParserState;
+use jessie_ast::{Span, Comment, CommentKind};
 
-pub struct Lexer {
-    state: ParserState<char>,
+use crate::{interner::Interner, jessie_parser::GrammarLevel};
+
+// Lexes directly over the source string, positions are byte offsets into it.
+pub struct Lexer<'a> {
+    input: &'a str,
+    // byte offset of the next character
+    pos: usize,
+    // line and column of the next character, both 1-based, columns counted in characters
+    line: u32,
+    column: u32,
+    // identifiers and string literals share a single allocation per distinct value
+    interner: Interner,
+    // span of each emitted token, parallel to the token stream
+    pub spans: Vec<Span>,
     parenthesize_stack: Vec<ParenthesisIndex>,
+    // number of currently open braces
+    brace_depth: usize,
+    // brace depth at the start of each open template substitution
+    quasi_stack: Vec<usize>,
+    // at the JSON level, comments, single quoted strings, templates and the non-JSON number and escape forms are rejected
+    level: GrammarLevel,
+    // comments are dropped unless keep_comments is set
+    keep_comments: bool,
+    // (byte offset of the attached token, comment) in source order
+    pub comments: Vec<(u32, Comment)>,
+    // comments waiting for the next token, with the line where they end
+    pending_comments: Vec<(Comment, u32)>,
+    // byte offset of the last emitted token and the line where it ends, for the comments following it on the same line
+    last_token: Option<(u32, u32)>,
+}
+
+// Location of a character in the input, as returned by Lexer::position
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub offset: u32,
+    pub line: u32,
+    pub column: u32,
 }
 
-impl Lexer {
-    pub fn new(input: Vec<char>) -> Self {
+impl<'a> Lexer<'a> {
+    pub fn new(input: &'a str) -> Self {
         Lexer {
-            state: ParserState::new(input),
+            input,
+            pos: 0,
+            line: 1,
+            column: 1,
+            interner: Interner::default(),
+            spans: Vec::new(),
             parenthesize_stack: Vec::new(),
+            brace_depth: 0,
+            quasi_stack: Vec::new(),
+            level: GrammarLevel::Jessie,
+            keep_comments: false,
+            comments: Vec::new(),
+            pending_comments: Vec::new(),
+            last_token: None,
+        }
+    }
+
+    pub fn with_level(self, level: GrammarLevel) -> Self {
+        Lexer { level, ..self }
+    }
+
+    pub fn with_comments(self) -> Self {
+        Lexer { keep_comments: true, ..self }
+    }
+
+    // A comment is trailing the last token if it starts on the line where the token ends, and is leading the next
+    // token otherwise. A block comment is leading the next token if that starts on the line where the comment ends,
+    // as in `f(/* a */ x)`.
+    fn keep_comment(&mut self, kind: CommentKind, start: Position) {
+        if !self.keep_comments {
+            return
+        }
+        let span = self.span_from(start);
+        let end = match kind {
+            CommentKind::Block if self.input[..self.pos].ends_with("*/") && self.pos >= start.offset as usize + 4 => self.pos - 2,
+            _ => self.pos,
+        };
+        let text = self.intern(&self.input[start.offset as usize + 2..end]);
+        let trailing = matches!(self.last_token, Some((_, line)) if line == start.line);
+        let comment = Comment { kind, text, span, trailing };
+        match self.last_token {
+            Some((token, _)) if trailing && kind == CommentKind::Line => {
+                // the block comments before it on the same line are trailing too
+                for (comment, _) in self.pending_comments.drain(..) {
+                    self.comments.push((token, comment));
+                }
+                self.comments.push((token, comment))
+            },
+            _ => self.pending_comments.push((comment, self.line)),
+        }
+    }
+
+    // attaches the pending comments around the token just emitted
+    fn token_emitted(&mut self, span: Span) {
+        for (mut comment, end_line) in std::mem::take(&mut self.pending_comments) {
+            match self.last_token {
+                Some((token, _)) if comment.trailing && end_line != span.line => self.comments.push((token, comment)),
+                _ => {
+                    comment.trailing = false;
+                    self.comments.push((span.start, comment));
+                },
+            }
+        }
+        self.last_token = Some((span.start, self.line));
+    }
+
+    fn is_json(&self) -> bool {
+        self.level == GrammarLevel::Json
+    }
+
+    // Starts lexing in the middle of the input, at a position where no parenthesis, brace or template literal is open.
+    pub fn starting_at(input: &'a str, start: Position) -> Self {
+        Lexer {
+            pos: start.offset as usize,
+            line: start.line,
+            column: start.column,
+            ..Lexer::new(input)
         }
     }
 
     pub fn is_empty(&self) -> bool {
-        self.state.is_empty()
+        self.pos >= self.input.len()
+    }
+
+    // no parenthesis, brace or template literal is open, the following tokens do not depend on the preceding ones
+    pub fn is_clean(&self) -> bool {
+        self.parenthesize_stack.is_empty() && self.brace_depth == 0 && self.quasi_stack.is_empty()
+    }
+
+    // rest of the input, starting from the next character
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    // n-th character from the current position, 0-based
+    #[inline]
+    fn nth_char(&self, n: usize) -> Option<char> {
+        let bytes = &self.input.as_bytes()[self.pos..];
+        // fast path, no need to decode if everything up to the n-th byte is ascii
+        if bytes.len() > n && bytes[..=n].is_ascii() {
+            return Some(bytes[n] as char)
+        }
+        self.rest().chars().nth(n)
     }
 
     pub fn lookahead_1(&self) -> Option<char> {
-        self.state.lookahead_1()
+        self.nth_char(0)
     }
 
     pub fn lookahead_2(&self) -> Option<char> {
-        self.state.lookahead_2()
+        self.nth_char(1)
     }
 
     pub fn lookahead_3(&self) -> Option<char> {
-        self.state.lookahead_3()
+        self.nth_char(2)
     }
 
     pub fn lookahead_4(&self) -> Option<char> {
-        self.state.lookahead_4()
+        self.nth_char(3)
     }
 
     pub fn lookahead_n(&self, n: usize) -> Option<char> {
-        self.state.lookahead_n(n)
+        if n == 0 {
+            panic!("lookahead_n(0) is not allowed");
+        }
+        self.nth_char(n - 1)
     }
 
     pub fn proceed_with(&mut self, token: Token) -> Token {
-        self.state.proceed_with(token)
+        self.proceed();
+        token
     }
 
     pub fn proceed(&mut self) -> Option<char> {
-        self.state.proceed()
+        let c = self.lookahead_1()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    // Skips n ascii characters that are not line terminators, already known by the caller.
+    fn proceed_ascii(&mut self, n: usize) {
+        self.pos += n;
+        self.column += n as u32;
     }
 
-    pub fn consume(&mut self, token: Vec<char>) -> Result<(), String> {
-        self.state.consume(token)
+    pub fn consume(&mut self, token: &str) -> Result<(), String> {
+        if self.rest().starts_with(token) {
+            for _ in token.chars() {
+                self.proceed();
+            }
+            Ok(())
+        } else {
+            Err(format!("Expected {:?}, but got {:?}", token, self.lookahead_1()))
+        }
     }
     
+    pub fn position(&self) -> Position {
+        Position {
+            offset: self.pos as u32,
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    // span from the position start to the current position
+    pub fn span_from(&self, start: Position) -> Span {
+        Span::new(start.offset, self.pos as u32, start.line, start.column)
+    }
+
+    pub fn intern(&mut self, s: &str) -> Rc<str> {
+        self.interner.intern(s)
+    }
+
     pub fn open_paren(&mut self, index: usize) {
         self.parenthesize_stack.push(ParenthesisIndex(index));
     }
@@ -166,7 +338,10 @@ pub enum Token {
     LAngleLAngleEqual, // <<=
     RAngleRAngleEqual, // >>=
     RAngleRAngleRAngleEqual, // >>>=
-    // TODO: Add more assignment operators
+    AsteriskAsteriskEqual, // **=
+    BarBarEqual, // ||=
+    AmpAmpEqual, // &&=
+    QuestionQuestionEqual, // ??=
     // Unary
     TypeOf, // typeof
     Tilde, // ~
@@ -187,6 +362,7 @@ pub enum Token {
     DotDotDot, // ...
     FatArrow, // =>
     Question, // ?
+    QuestionDot, // ?.
     QuasiQuote, // `
     Dollar, // $
     DoubleSlash, // //
@@ -198,6 +374,8 @@ pub enum Token {
     String(Rc<str>),
     Integer(i64),
     Decimal(i64, u64),
+    // numbers out of the range of Integer and Decimal, not exact
+    Float(f64),
     Bigint(bool, Box<[u64]>),
 
     // ????
@@ -294,6 +472,10 @@ impl ToString for Token {
             Token::LAngleLAngleEqual => "<<=".to_string(),
             Token::RAngleRAngleEqual => ">>=".to_string(),
             Token::RAngleRAngleRAngleEqual => ">>>=".to_string(),
+            Token::AsteriskAsteriskEqual => "**=".to_string(),
+            Token::BarBarEqual => "||=".to_string(),
+            Token::AmpAmpEqual => "&&=".to_string(),
+            Token::QuestionQuestionEqual => "??=".to_string(),
             Token::TypeOf => "typeof".to_string(),
             Token::Tilde => "~".to_string(),
             Token::Bang => "!".to_string(),
@@ -312,6 +494,7 @@ impl ToString for Token {
             Token::DotDotDot => "...".to_string(),
             Token::FatArrow => "=>".to_string(),
             Token::Question => "?".to_string(),
+            Token::QuestionDot => "?.".to_string(),
             Token::QuasiQuote => "`".to_string(),
             Token::Dollar => "$".to_string(),
             Token::DoubleSlash => "//".to_string(),
@@ -322,12 +505,18 @@ impl ToString for Token {
             Token::Identifier(s) => s.to_string(),
             Token::String(s) => s.to_string(),
             Token::Integer(i) => i.to_string(),
-            Token::Decimal(i, f) => format!("{}.{}", i, f), // TODO
+            Token::Decimal(i, f) => format!("{}.{}", i, format!("{:0>19}", f).trim_end_matches('0')),
+            Token::Float(f) => format!("{:e}", f),
             Token::Bigint(s, v) => {
-                let mut s = if *s { "-".to_string() } else { "".to_string() };
-                for i in v.iter() {
-                    s.push_str(&i.to_string()); // TODO
+                let mut s = if *s { "-0x".to_string() } else { "0x".to_string() };
+                for (index, limb) in v.iter().rev().enumerate() {
+                    if index == 0 {
+                        s.push_str(&format!("{:x}", limb));
+                    } else {
+                        s.push_str(&format!("{:016x}", limb));
+                    }
                 }
+                s.push('n');
                 s
             },
         }
@@ -335,24 +524,23 @@ impl ToString for Token {
 }
 
 #[inline]
-fn given_keyword_or_ident(lexer: &mut Lexer, keyword: &'static str, token: Token) -> Result<Token, String> {
-    // if the following character of the (supposed) keyword can form an identifier, namely, one of a-z, A-Z, $, _, or 0-9, then it is not a keyword.
+fn given_keyword_or_ident(lexer: &mut Lexer<'_>, keyword: &'static str, token: Token) -> Result<Token, String> {
+    // if the following character of the (supposed) keyword can form an identifier, namely, an IdentifierPart or an escape, then it is not a keyword.
     // proceed to identifier parsing
-    if lexer.lookahead_n(keyword.len()+1).map(|x| x.is_alphabetic() || x == '_' || x.is_digit(10)).unwrap_or(false) {
-        println!("fastpath ident given_keyword_or_ident");
+    if lexer.lookahead_n(keyword.len()+1).map(|x| is_id_continue(x) || x == '\\').unwrap_or(false) {
         return ident(lexer);   
     }
 
-    if lexer.consume(keyword.chars().collect()).is_ok() {
-        println!("keyword given_keyword_or_ident: {:?}", token);
+    if lexer.rest().starts_with(keyword) {
+        // keywords are ascii without line terminators
+        lexer.proceed_ascii(keyword.len());
         Ok(token)
     } else {
-        println!("ident given_keyword_or_ident: {:?}", Token::Identifier(keyword.into()));
         ident(lexer)
     }
 }
 
-fn keyword_or_ident(lexer: &mut Lexer) -> Result<Token, String> {
+fn keyword_or_ident(lexer: &mut Lexer<'_>) -> Result<Token, String> {
     // keywords in this list may or may not be used in the actual script,
     // but need to be reserved for future use
     /*
@@ -376,8 +564,6 @@ fn keyword_or_ident(lexer: &mut Lexer) -> Result<Token, String> {
     Y: yield
     */
 
-    println!("keyword_or_ident: {:?}", lexer.lookahead_1());
-
     match lexer.lookahead_1() {
         Some('a') => 
             match lexer.lookahead_2() {
@@ -429,7 +615,11 @@ fn keyword_or_ident(lexer: &mut Lexer) -> Result<Token, String> {
             match lexer.lookahead_2() {
                 Some('l') => given_keyword_or_ident(lexer, &"else", Token::Else),
                 Some('n') => given_keyword_or_ident(lexer, &"enum", Token::Enum),
-                Some('x') => given_keyword_or_ident(lexer, &"extends", Token::Extends),
+                Some('x') => match lexer.lookahead_3() {
+                    Some('t') => given_keyword_or_ident(lexer, &"extends", Token::Extends),
+                    Some('p') => given_keyword_or_ident(lexer, &"export", Token::Export),
+                    _ => ident(lexer),
+                },
                 Some('v') => given_keyword_or_ident(lexer, &"eval", Token::Eval),
                 _ => ident(lexer),
             }
@@ -517,10 +707,13 @@ fn keyword_or_ident(lexer: &mut Lexer) -> Result<Token, String> {
         },
         Some('t') => {
             match lexer.lookahead_2() {
-                Some('h') => given_keyword_or_ident(lexer, &"this", Token::This),
+                Some('h') => match lexer.lookahead_3() {
+                    Some('i') => given_keyword_or_ident(lexer, &"this", Token::This),
+                    Some('r') => given_keyword_or_ident(lexer, &"throw", Token::Throw),
+                    _ => ident(lexer),
+                },
                 Some('r') => match lexer.lookahead_3() {
                     Some('u') => given_keyword_or_ident(lexer, &"true", Token::True),
-                    Some('h') => given_keyword_or_ident(lexer, &"throw", Token::Throw),
                     Some('y') => given_keyword_or_ident(lexer, &"try", Token::Try),
                     _ => ident(lexer),
                 },
@@ -554,14 +747,41 @@ fn keyword_or_ident(lexer: &mut Lexer) -> Result<Token, String> {
     }
 }
 
-pub fn lex_jessie(input: String) -> Result<Vec<Token>, String> {
+pub fn lex_jessie(input: impl AsRef<str>) -> Result<Vec<Token>, String> {
+    lex_jessie_with_spans(input).map(|(tokens, _)| tokens)
+}
+
+// Returns the tokens along with the span of each token.
+pub fn lex_jessie_with_spans(input: impl AsRef<str>) -> Result<(Vec<Token>, Vec<Span>), String> {
+    lex_with_level(input, GrammarLevel::Jessie)
+}
+
+// Justin and Tessie share the lexical grammar with Jessie, JSON restricts it.
+pub fn lex_with_level(input: impl AsRef<str>, level: GrammarLevel) -> Result<(Vec<Token>, Vec<Span>), String> {
+    let mut result = Vec::new();
+    let mut lexer = Lexer::new(input.as_ref()).with_level(level);
+    lex_reporting(&mut lexer, &mut result)?;
+    Ok((result, lexer.spans))
+}
+
+// Also returns the comments, each with the byte offset of the token it is attached to,
+// for JessieParserState::with_comments.
+pub fn lex_with_comments(input: impl AsRef<str>, level: GrammarLevel) -> Result<(Vec<Token>, Vec<Span>, Vec<(u32, Comment)>), String> {
     let mut result = Vec::new();
-    let mut lexer = Lexer::new(input.chars().collect());
-    lex(&mut lexer, &mut result)?;
-    Ok(result)
+    let mut lexer = Lexer::new(input.as_ref()).with_level(level).with_comments();
+    lex_reporting(&mut lexer, &mut result)?;
+    Ok((result, lexer.spans, lexer.comments))
 }
 
-fn table(lexer: &mut Lexer, result: &mut Vec<Token>, token: Token) -> Result<(), String> {
+// lex with the location in the error message
+fn lex_reporting(lexer: &mut Lexer<'_>, result: &mut Vec<Token>) -> Result<(), String> {
+    lex(lexer, result).map_err(|err| {
+        let span = lexer.span_from(lexer.position());
+        format!("{} at {}:{}", err, span.line, span.column)
+    })
+}
+
+fn table(lexer: &mut Lexer<'_>, result: &mut Vec<Token>, token: Token) -> Result<(), String> {
     match token {
         Token::LeftParen => {
             lexer.open_paren(result.len()-1);
@@ -584,29 +804,59 @@ fn table(lexer: &mut Lexer, result: &mut Vec<Token>, token: Token) -> Result<(),
     Ok(())
 }
 
-fn lex(lexer: &mut Lexer, result: &mut Vec<Token>) -> Result<(), String> {
+fn lex(lexer: &mut Lexer<'_>, result: &mut Vec<Token>) -> Result<(), String> {
+    lex_until(lexer, result, |_| false).map(|_| ())
+}
+
+// Lexes until the end of input, or until stop returns true for the byte offset of the next token.
+// stop is only asked at the token boundaries where the lexer is clean.
+// Returns true if stopped before the end of input.
+pub fn lex_until(lexer: &mut Lexer<'_>, result: &mut Vec<Token>, mut stop: impl FnMut(usize) -> bool) -> Result<bool, String> {
     loop {
+        consume_whitespace(lexer);
+        if lexer.is_clean() && !lexer.is_empty() && stop(lexer.pos) {
+            return Ok(true)
+        }
         let token = tokenize(lexer, result)?;
         if token == Token::EOF {
-            return Ok(())
+            return Ok(false)
         }
         table(lexer, result, token)?;
     }
 }
 
 // Function lex consumes the input string, returns a single Token, and modifies the lexer state if needed
-fn tokenize(lexer: &mut Lexer, result: &mut Vec<Token>) -> Result<Token, String> {
+pub(crate) fn tokenize(lexer: &mut Lexer<'_>, result: &mut Vec<Token>) -> Result<Token, String> {
     consume_whitespace(lexer);
+    let start = lexer.position();
+    let emitted = result.len();
+    if lexer.is_json() {
+        check_json_token(lexer)?;
+    }
     let res = match lexer.lookahead_1() {
         Some('a'..='z') => keyword_or_ident(lexer)?,
-        Some('A'..='Z'|'_') => ident(lexer)?,
-        Some('0'..='9') => parse_number_or_bigint(lexer, false)?,
+        Some('A'..='Z'|'_'|'$'|'\\') => ident(lexer)?,
+        Some('0'..='9') => parse_number_or_bigint(lexer)?,
         Some('"'|'\'') => parse_string(lexer)?,
         // Punctuation
         Some('(') => lexer.proceed_with(Token::LeftParen),
         Some(')') => lexer.proceed_with(Token::RightParen),
-        Some('{') => lexer.proceed_with(Token::LeftBrace),
-        Some('}') => lexer.proceed_with(Token::RightBrace),
+        Some('{') => {
+            lexer.brace_depth += 1;
+            lexer.proceed_with(Token::LeftBrace)
+        },
+        Some('}') => {
+            if lexer.quasi_stack.last() == Some(&lexer.brace_depth) {
+                // closing brace of a template substitution, continue the template
+                lexer.quasi_stack.pop();
+                lexer.proceed();
+                result.push(Token::RightBrace);
+                parse_quasi_chunk(lexer, result)?
+            } else {
+                lexer.brace_depth = lexer.brace_depth.saturating_sub(1);
+                lexer.proceed_with(Token::RightBrace)
+            }
+        },
         Some('[') => lexer.proceed_with(Token::LeftBracket),
         Some(']') => lexer.proceed_with(Token::RightBracket),
         Some(',') => lexer.proceed_with(Token::Comma),
@@ -616,6 +866,8 @@ fn tokenize(lexer: &mut Lexer, result: &mut Vec<Token>) -> Result<Token, String>
                 lexer.proceed();
                 lexer.proceed();
                 Token::DotDotDot
+            } else if let Some('0'..='9') = lexer.lookahead_2() {
+                parse_number_or_bigint(lexer)?
             } else {
                 lexer.proceed_with(Token::Dot)
             }
@@ -624,15 +876,30 @@ fn tokenize(lexer: &mut Lexer, result: &mut Vec<Token>) -> Result<Token, String>
         Some(';') => lexer.proceed_with(Token::Semicolon),
         Some('?') => {
             if lexer.lookahead_2() == Some('?') {
+                if lexer.lookahead_3() == Some('=') {
+                    lexer.proceed();
+                    lexer.proceed();
+                    lexer.proceed();
+                    Token::QuestionQuestionEqual
+                } else {
+                    lexer.proceed();
+                    lexer.proceed();
+                    Token::QuestionQuestion
+                }
+            } else if lexer.lookahead_2() == Some('.') && !matches!(lexer.lookahead_3(), Some('0'..='9')) {
+                // ?.5 is a conditional with a leading-dot decimal
                 lexer.proceed();
                 lexer.proceed();
-                Token::QuestionQuestion
+                Token::QuestionDot
             } else {
                 lexer.proceed_with(Token::Question)
             }
         }
-        Some('`') => lexer.proceed_with(Token::QuasiQuote),
-        Some('$') => lexer.proceed_with(Token::Dollar),
+        Some('`') => {
+            lexer.proceed();
+            result.push(Token::QuasiQuote);
+            parse_quasi_chunk(lexer, result)?
+        },
         Some('/') => {
             if lexer.lookahead_2() == Some('/') {
                 unreachable!("line comment should have been handled by consume_whitespace")
@@ -670,7 +937,6 @@ fn tokenize(lexer: &mut Lexer, result: &mut Vec<Token>) -> Result<Token, String>
                     lexer.proceed();
                     Token::MinusEqual
                 },
-                Some('0'..='9') => parse_number_or_bigint(lexer, true)?,
                 _ => lexer.proceed_with(Token::Minus)
             }
         },
@@ -680,12 +946,13 @@ fn tokenize(lexer: &mut Lexer, result: &mut Vec<Token>) -> Result<Token, String>
                 lexer.proceed();
                 Token::AsteriskEqual
             } else if lexer.lookahead_2() == Some('*') {
-                lexer.proceed();
                 if lexer.lookahead_3() == Some('=') {
-                    return Err("Exponentiation assignment operator not supported yet".to_string())
-                    // lexer.proceed();
-                    // result.push(lexer.proceed_with(Token::AsteriskAsteriskEqual);
+                    lexer.proceed();
+                    lexer.proceed();
+                    lexer.proceed();
+                    Token::AsteriskAsteriskEqual
                 } else {
+                    lexer.proceed();
                     lexer.proceed_with(Token::AsteriskAsterisk)
                 }
             } else if lexer.lookahead_2() == Some('/') {
@@ -707,9 +974,16 @@ fn tokenize(lexer: &mut Lexer, result: &mut Vec<Token>) -> Result<Token, String>
         },
         Some('&') => {
             if lexer.lookahead_2() == Some('&') {
-                lexer.proceed();
-                lexer.proceed();
-                Token::AmpAmp
+                if lexer.lookahead_3() == Some('=') {
+                    lexer.proceed();
+                    lexer.proceed();
+                    lexer.proceed();
+                    Token::AmpAmpEqual
+                } else {
+                    lexer.proceed();
+                    lexer.proceed();
+                    Token::AmpAmp
+                }
             } else if lexer.lookahead_2() == Some('=') {
                 lexer.proceed();
                 lexer.proceed();
@@ -720,9 +994,16 @@ fn tokenize(lexer: &mut Lexer, result: &mut Vec<Token>) -> Result<Token, String>
         },
         Some('|') => {
             if lexer.lookahead_2() == Some('|') {
-                lexer.proceed();
-                lexer.proceed();
-                Token::BarBar
+                if lexer.lookahead_3() == Some('=') {
+                    lexer.proceed();
+                    lexer.proceed();
+                    lexer.proceed();
+                    Token::BarBarEqual
+                } else {
+                    lexer.proceed();
+                    lexer.proceed();
+                    Token::BarBar
+                }
             } else if lexer.lookahead_2() == Some('=') {
                 lexer.proceed();
                 lexer.proceed();
@@ -801,10 +1082,18 @@ fn tokenize(lexer: &mut Lexer, result: &mut Vec<Token>) -> Result<Token, String>
                     lexer.proceed();
                     Token::RAngleRAngleEqual
                 } else if lexer.lookahead_3() == Some('>') {
-                    lexer.proceed();
-                    lexer.proceed();
-                    lexer.proceed();
-                    Token::RAngleRAngleRAngle
+                    if lexer.lookahead_4() == Some('=') {
+                        lexer.proceed();
+                        lexer.proceed();
+                        lexer.proceed();
+                        lexer.proceed();
+                        Token::RAngleRAngleRAngleEqual
+                    } else {
+                        lexer.proceed();
+                        lexer.proceed();
+                        lexer.proceed();
+                        Token::RAngleRAngleRAngle
+                    }
                 } else {
                     lexer.proceed();
                     lexer.proceed();
@@ -819,16 +1108,34 @@ fn tokenize(lexer: &mut Lexer, result: &mut Vec<Token>) -> Result<Token, String>
             } 
         } 
         None => Token::EOF,
+        Some(c) if is_id_start(c) => ident(lexer)?,
         Some(c) => return Err(format!("Unexpected character {}", c)),
     };
 
     result.push(res.clone());
 
+    // template literals emit multiple tokens at once, they all share the span of the whole chunk
+    let span = lexer.span_from(start);
+    for _ in emitted..result.len() {
+        lexer.spans.push(span);
+    }
+    lexer.token_emitted(span);
+
     Ok(res)
 }
 
+// Rejects the tokens that start differently in JSON, the rest is checked while lexing the token.
+fn check_json_token(lexer: &Lexer<'_>) -> Result<(), String> {
+    match (lexer.lookahead_1(), lexer.lookahead_2()) {
+        (Some('/'), Some('/' | '*')) => Err("Comments are not allowed in JSON".to_string()),
+        (Some('\''), _) => Err("Single quoted strings are not allowed in JSON".to_string()),
+        (Some('`'), _) => Err("Template literals are not allowed in JSON".to_string()),
+        (Some('.'), Some('0'..='9')) => Err("Numbers must have digits before the decimal point in JSON".to_string()),
+        _ => Ok(()),
+    }
+}
+
 pub fn check_whitespace_nonident(c1: Option<char>, c2: Option<char>) -> Result<(), String> {
-    println!("c1: {:?}, c2: {:?}", c1, c2);
     if let Some(c) = c1 {
         match c {
             ' ' | '\t' | '\r' | '\n' => {
@@ -849,17 +1156,20 @@ pub fn check_whitespace_nonident(c1: Option<char>, c2: Option<char>) -> Result<(
     }
 }
 
-pub fn lookahead_whitespace_nonident(state: &mut Lexer) -> Result<(), String> {
+pub fn lookahead_whitespace_nonident(state: &mut Lexer<'_>) -> Result<(), String> {
     check_whitespace_nonident(state.lookahead_1(), state.lookahead_2())
 }
 
-pub fn consume_whitespace(state: &mut Lexer) {
+pub fn consume_whitespace(state: &mut Lexer<'_>) {
     while let Some(c) = state.lookahead_1() {
         match c {
             ' ' | '\t' | '\r' | '\n' => { state.proceed(); },
+            // left for the tokenizer to reject
+            '/' if state.is_json() => break,
             '/' => {
                 match state.lookahead_2() {
                     Some('/') => {
+                        let start = state.position();
                         state.proceed();
                         state.proceed();
                         while let Some(c) = state.lookahead_1() {
@@ -869,8 +1179,10 @@ pub fn consume_whitespace(state: &mut Lexer) {
                                 state.proceed();
                             }
                         }
+                        state.keep_comment(CommentKind::Line, start);
                     }
                     Some('*') => {
+                        let start = state.position();
                         state.proceed();
                         state.proceed();
                         while let Some(c) = state.lookahead_1() {
@@ -886,6 +1198,7 @@ pub fn consume_whitespace(state: &mut Lexer) {
                                 state.proceed();
                             }
                         }
+                        state.keep_comment(CommentKind::Block, start);
                     }
                     _ => break,
                 }
@@ -895,20 +1208,61 @@ pub fn consume_whitespace(state: &mut Lexer) {
     }
 }
 
-fn ident(state: &mut Lexer) -> Result<Token, String> {
-    // [a-zA-Z_][a-zA-Z0-9_]*
+// IdentifierStart ::= ID_Start | $ | _ | \\ UnicodeEscapeSequence
+pub(crate) fn is_id_start(c: char) -> bool {
+    c == '$' || c == '_' || unicode_ident::is_xid_start(c)
+}
+
+// IdentifierPart ::= ID_Continue | $ | <ZWNJ> | <ZWJ> | \\ UnicodeEscapeSequence
+pub(crate) fn is_id_continue(c: char) -> bool {
+    c == '$' || c == '\u{200C}' || c == '\u{200D}' || unicode_ident::is_xid_continue(c)
+}
+
+fn ident(state: &mut Lexer<'_>) -> Result<Token, String> {
+    // fast path, plain ascii identifiers are sliced out of the input
+    let rest = state.rest();
+    let bytes = rest.as_bytes();
+    let len = bytes.iter().take_while(|b| b.is_ascii_alphanumeric() || **b == b'_' || **b == b'$').count();
+    if len > 0 && !bytes[0].is_ascii_digit() && bytes.get(len).map(|b| b.is_ascii() && *b != b'\\').unwrap_or(true) {
+        let ident = state.intern(&rest[..len]);
+        state.proceed_ascii(len);
+        return Ok(Token::Identifier(ident))
+    }
+
+    // IdentifierStart IdentifierPart*
     let mut ident = String::new();
+    match ident_char(state, is_id_start)? {
+        Some(c) => ident.push(c),
+        None => return Err(format!("Expected identifier, but got {:?}", state.lookahead_1())),
+    }
+    while let Some(c) = ident_char(state, is_id_continue)? {
+        ident.push(c);
+    }
+
+    Ok(Token::Identifier(state.intern(&ident)))
+}
+
+// Consumes the next identifier character if it satisfies the predicate, either literal or \\u escaped.
+fn ident_char(state: &mut Lexer<'_>, predicate: fn(char) -> bool) -> Result<Option<char>, String> {
     match state.lookahead_1() {
-        Some('a'..='z') | Some('A'..='Z') | Some('_') => {
-            while let Some('a'..='z') | Some('A'..='Z') | Some('_') | Some('0'..='9') = state.lookahead_1() {
-                ident.push(state.lookahead_1().unwrap());
-                state.proceed();
+        Some('\\') => {
+            state.proceed();
+            if state.lookahead_1() != Some('u') {
+                return Err("Expected unicode escape sequence in identifier".to_string())
             }
-        }
-        _ => return Err(format!("Expected identifier, but got {:?}", state.lookahead_1())),
+            state.proceed();
+            let c = parse_unicode_escape(state)?;
+            if !predicate(c) {
+                return Err(format!("Escaped character {:?} is not allowed in identifier", c))
+            }
+            Ok(Some(c))
+        },
+        Some(c) if predicate(c) => {
+            state.proceed();
+            Ok(Some(c))
+        },
+        _ => Ok(None),
     }
-
-    Ok(Token::Identifier(ident.into()))
 }
 
 
@@ -917,7 +1271,7 @@ fn ident(state: &mut Lexer) -> Result<Token, String> {
 
 
 /*
-pub fn parse_number(state: &mut Lexer) -> Result<DataLiteral, String> {
+pub fn parse_number(state: &mut Lexer<'_>) -> Result<DataLiteral, String> {
     // [1-9][0-9]*(\.[0-9]*|n)?
     let mut number = String::new();
     if state.lookahead_1().map(|x| x.is_ascii_digit()) != Some(true) {
@@ -947,95 +1301,450 @@ pub fn parse_number(state: &mut Lexer) -> Result<DataLiteral, String> {
     Ok(DataLiteral::Number(number)) 
 }
  */
-pub fn parse_number_or_bigint(state: &mut Lexer, sign: bool) -> Result<Token, String> {
-    // integer: -?[1-9][0-9]* within i64 range
-    // decimal: -?[1-9][0-9]*\.[0-9]* within i128 range
-    // bigint: -?[1-9][0-9]*n without limit
+// Token::Decimal(integer, fraction) holds the value integer + fraction / 10^19, with the fraction in [0, 10^19).
+pub const DECIMAL_FRACTION_DIGITS: usize = 19;
 
-    // the sign is pre-parsed by the caller and passed via sign parameter
+// NumericLiteral ::= DecimalLiteral | DecimalBigIntegerLiteral | NonDecimalIntegerLiteral BigIntLiteralSuffix?
+// The immediate following symbol is guaranteed to be a digit, or a dot followed by a digit, by the caller.
+// The sign is not part of the literal, negative numbers are unary negations.
+// Values that are integers are lexed as Token::Integer(even if written as 1.0 or 1e3),
+// the others as Token::Decimal. Both are limited to the i64 range and Token::Decimal to 19 significant fractional digits,
+// the numbers that cannot be represented exactly are rejected. Bigints are not limited.
+// JSON numbers are doubles, so at the JSON level the inexact numbers are lexed as Token::Float(rounded to the nearest f64)
+// instead, and only the ones out of the range of f64 are rejected.
+pub fn parse_number_or_bigint(state: &mut Lexer<'_>) -> Result<Token, String> {
+    let radix = match (state.lookahead_1(), state.lookahead_2()) {
+        (Some('0'), Some('x' | 'X')) => 16,
+        (Some('0'), Some('o' | 'O')) => 8,
+        (Some('0'), Some('b' | 'B')) => 2,
+        (Some('0'), Some('0'..='9' | '_')) => return Err("Leading zeros and legacy octal literals are not allowed".to_string()),
+        _ => 10,
+    };
 
-    // the immediate following symbol is guaranteed to be a digit 1..=9 by the caller
+    if radix != 10 && state.is_json() {
+        return Err("Hexadecimal, octal and binary literals are not allowed in JSON".to_string())
+    }
 
-    // integer part, [1-9][0-9]*
-    let mut slice = String::new();
-    while let Some(c) = state.lookahead_1() {
-        if let Some(digit) = c.to_digit(10) {
-            slice.push(c);
+    let token = if radix != 10 {
+        state.proceed();
+        state.proceed();
+        let digits = parse_digits(state, radix)?;
+        if digits.is_empty() {
+            return Err(format!("Expected digits after the radix prefix of base {}", radix))
+        }
+        let limbs = digits_to_limbs(&digits, radix);
+        if state.lookahead_1() == Some('n') {
             state.proceed();
+            Token::Bigint(false, limbs)
         } else {
-            break;
+            match limbs_to_i64(&limbs) {
+                Some(integer) => Token::Integer(integer),
+                None => return Err(format!("Numeric literal 0{}{} is out of range", if radix == 16 { 'x' } else if radix == 8 { 'o' } else { 'b' }, digits)),
+            }
         }
-    }
+    } else {
+        parse_decimal_literal(state)?
+    };
 
+    // The SourceCharacter immediately following a NumericLiteral must not be an IdentifierStart or DecimalDigit
     match state.lookahead_1() {
-        Some('n') => {
-            // bigint
-            state.proceed();
-            if slice.len() >= 20 {
-                unimplemented!("bigint too large")
-            }
-            Ok(Token::Bigint(sign, Box::new([slice.parse::<u64>().unwrap()])))
-        },
-        Some('.') => {
-            // decimal
-            state.proceed();
-            let mut decimal = String::new();
-            while let Some(c) = state.lookahead_1() {
-                if let Some(digit) = c.to_digit(10) {
-                    decimal.push(c);
-                    state.proceed();
-                } else {
-                    break;
+        Some(c) if is_id_start(c) || c.is_ascii_digit() || c == '\\' => Err(format!("Unexpected character {:?} right after a numeric literal", c)),
+        _ => Ok(token),
+    }
+}
+
+fn parse_decimal_literal(state: &mut Lexer<'_>) -> Result<Token, String> {
+    let integer = parse_digits(state, 10)?;
+
+    if state.lookahead_1() == Some('n') {
+        if state.is_json() {
+            return Err("Bigint literals are not allowed in JSON".to_string())
+        }
+        state.proceed();
+        return Ok(Token::Bigint(false, digits_to_limbs(&integer, 10)))
+    }
+
+    let mut fraction = String::new();
+    if state.lookahead_1() == Some('.') {
+        state.proceed();
+        if state.lookahead_1() == Some('_') {
+            return Err("Numeric separator is not allowed right after the decimal point".to_string())
+        }
+        fraction = parse_digits(state, 10)?;
+        if fraction.is_empty() && state.is_json() {
+            return Err("Numbers must have digits after the decimal point in JSON".to_string())
+        }
+    }
+
+    let mut exponent: i64 = 0;
+    if let Some('e' | 'E') = state.lookahead_1() {
+        state.proceed();
+        let negative = match state.lookahead_1() {
+            Some('+') => { state.proceed(); false },
+            Some('-') => { state.proceed(); true },
+            _ => false,
+        };
+        let digits = parse_digits(state, 10)?;
+        if digits.is_empty() {
+            return Err("Expected digits in the exponent".to_string())
+        }
+        // saturating, anything this large is out of range anyway unless the mantissa is zero
+        exponent = digits.chars().fold(0i64, |acc, c| acc.saturating_mul(10).saturating_add(c as i64 - '0' as i64).min(1_000_000));
+        if negative {
+            exponent = -exponent;
+        }
+    }
+
+    // value = digits * 10^scale
+    let digits = format!("{}{}", integer, fraction);
+    let digits = digits.trim_start_matches('0');
+    let scale = exponent - fraction.len() as i64;
+    if digits.is_empty() {
+        return Ok(Token::Integer(0))
+    }
+
+    let literal = format!("{}{}{}{}", integer, if fraction.is_empty() { "" } else { "." }, fraction, if exponent == 0 { String::new() } else { format!("e{}", exponent) });
+    let json = state.is_json();
+    // the exponent is clamped to a million, far beyond the range of f64
+    let inexact = |error: String| -> Result<Token, String> {
+        if !json {
+            return Err(error)
+        }
+        match format!("{}e{}", digits, scale).parse::<f64>() {
+            Ok(float) if float.is_finite() => Ok(Token::Float(float)),
+            _ => Err(format!("Number {} is out of the range of a double", literal)),
+        }
+    };
+    let out_of_range = || format!("Numeric literal {} is out of range", literal);
+
+    // number of digits in the integer part
+    let integer_len = digits.len() as i64 + scale;
+    if integer_len > 19 {
+        return inexact(out_of_range())
+    }
+
+    let (integer_digits, fraction_digits) = if integer_len >= digits.len() as i64 {
+        (format!("{}{}", digits, "0".repeat((integer_len - digits.len() as i64) as usize)), String::new())
+    } else if integer_len > 0 {
+        (digits[..integer_len as usize].to_string(), digits[integer_len as usize..].to_string())
+    } else {
+        (String::new(), format!("{}{}", "0".repeat((-integer_len) as usize), digits))
+    };
+
+    let integer_part = if integer_digits.is_empty() { 0 } else {
+        match integer_digits.parse::<i64>() {
+            Ok(integer_part) => integer_part,
+            Err(_) => return inexact(out_of_range()),
+        }
+    };
+
+    let fraction_digits = fraction_digits.trim_end_matches('0');
+    if fraction_digits.is_empty() {
+        return Ok(Token::Integer(integer_part))
+    }
+    if fraction_digits.len() > DECIMAL_FRACTION_DIGITS {
+        return inexact(format!("Numeric literal {} has more than {} significant fractional digits and cannot be represented exactly", literal, DECIMAL_FRACTION_DIGITS))
+    }
+    let fraction_part = format!("{:0<width$}", fraction_digits, width = DECIMAL_FRACTION_DIGITS).parse::<u64>().map_err(|_| out_of_range())?;
+
+    Ok(Token::Decimal(integer_part, fraction_part))
+}
+
+// Digits of the radix, with single numeric separators allowed between digits.
+// Returns the digits without separators, possibly empty.
+fn parse_digits(state: &mut Lexer<'_>, radix: u32) -> Result<String, String> {
+    let mut digits = String::new();
+    loop {
+        match state.lookahead_1() {
+            Some(c) if c.is_digit(radix) => {
+                digits.push(c);
+                state.proceed();
+            },
+            Some('_') if state.is_json() => return Err("Numeric separators are not allowed in JSON".to_string()),
+            Some('_') => {
+                let followed_by_digit = state.lookahead_2().map(|c| c.is_digit(radix)).unwrap_or(false);
+                if digits.is_empty() || !followed_by_digit {
+                    return Err("Numeric separator must be placed between digits".to_string())
                 }
+                state.proceed();
+            },
+            _ => return Ok(digits),
+        }
+    }
+}
+
+// Converts digits of the radix into little endian u64 limbs, with at least one limb.
+fn digits_to_limbs(digits: &str, radix: u32) -> Box<[u64]> {
+    let mut limbs: Vec<u64> = vec![0];
+    for c in digits.chars() {
+        let mut carry = c.to_digit(radix).unwrap_or_default() as u128;
+        for limb in limbs.iter_mut() {
+            let value = (*limb as u128) * (radix as u128) + carry;
+            *limb = value as u64;
+            carry = value >> 64;
+        }
+        if carry > 0 {
+            limbs.push(carry as u64);
+        }
+    }
+    limbs.into_boxed_slice()
+}
+
+fn limbs_to_i64(limbs: &[u64]) -> Option<i64> {
+    if limbs.iter().skip(1).any(|limb| *limb != 0) {
+        return None
+    }
+    i64::try_from(limbs[0]).ok()
+}
+
+pub fn parse_string(state: &mut Lexer<'_>) -> Result<Token, String> {
+    let enclosing = state.lookahead_1().filter(|c| *c == '"' || *c == '\'').ok_or("Expected string".to_string())?;
+    state.proceed();
+    let mut chunk = Chunk::new(state);
+    loop {
+        match state.lookahead_1() {
+            Some(c) if c == enclosing => {
+                let string = chunk.finish(state);
+                state.proceed();
+                return Ok(Token::String(string))
+            },
+            Some('\\') => {
+                if state.is_json() && !matches!(state.lookahead_2(), Some('"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't' | 'u')) {
+                    return Err(format!("Escape sequence \\{} is not allowed in JSON", state.lookahead_2().map(String::from).unwrap_or_default()))
+                }
+                if state.is_json() && state.lookahead_2() == Some('u') && state.lookahead_3() == Some('{') {
+                    return Err("Code point escapes \\u{...} are not allowed in JSON".to_string())
+                }
+                chunk.escape(state)?;
+            },
+            // line terminators must be escaped, U+2028 and U+2029 are allowed since ES2019
+            Some('\n') | Some('\r') | None => return Err("Unterminated string literal".to_string()),
+            Some(c) if c < ' ' && state.is_json() => return Err(format!("Control character {:?} must be escaped in JSON", c)),
+            Some(_) => {
+                state.proceed();
+            },
+        }
+    }
+}
+
+// Contents of a string literal or a template chunk.
+// The unescaped runs are sliced from the input, and only copied into a buffer once an escape sequence shows up.
+struct Chunk {
+    // start of the current unescaped run
+    start: usize,
+    escaped: Option<String>,
+}
+
+impl Chunk {
+    fn new(state: &Lexer<'_>) -> Self {
+        Chunk { start: state.pos, escaped: None }
+    }
+
+    // Called at the backslash, parses the escape sequence.
+    fn escape(&mut self, state: &mut Lexer<'_>) -> Result<(), String> {
+        let run = &state.input[self.start..state.pos];
+        let escaped = self.escaped.get_or_insert_with(String::new);
+        escaped.push_str(run);
+        state.proceed();
+        if state.lookahead_1().is_none() {
+            return Err("Unterminated escape sequence".to_string())
+        }
+        parse_escape(state, escaped)?;
+        self.start = state.pos;
+        Ok(())
+    }
+
+    fn finish(self, state: &mut Lexer<'_>) -> Rc<str> {
+        let input = state.input;
+        let run = &input[self.start..state.pos];
+        match self.escaped {
+            Some(mut escaped) => {
+                escaped.push_str(run);
+                state.intern(&escaped)
+            },
+            None => state.intern(run),
+        }
+    }
+}
+
+// Parses an escape sequence right after the backslash, and pushes the escaped character.
+// Line continuations push nothing.
+fn parse_escape(state: &mut Lexer<'_>, string: &mut String) -> Result<(), String> {
+    let c = state.proceed().ok_or("Unterminated escape sequence".to_string())?;
+    match c {
+        'n' => string.push('\n'),
+        't' => string.push('\t'),
+        'r' => string.push('\r'),
+        'b' => string.push('\u{8}'),
+        'f' => string.push('\u{C}'),
+        'v' => string.push('\u{B}'),
+        '0' if !state.lookahead_1().map(|c| c.is_ascii_digit()).unwrap_or(false) => string.push('\0'),
+        '0'..='9' => return Err(format!("Octal escape sequence \\{} is not allowed in strict mode", c)),
+        'x' => {
+            let code = parse_hex_digits(state, 2)?;
+            // two hex digits are always a valid scalar value
+            string.push(char::from_u32(code).unwrap_or_default())
+        },
+        'u' => string.push(parse_unicode_escape(state)?),
+        // line continuations
+        '\r' => {
+            if state.lookahead_1() == Some('\n') {
+                state.proceed();
             }
-            for _ in 0..(20 - decimal.len()) {
-                decimal.push('0');
-            }
-            Ok(Token::Decimal(slice.parse::<i64>().unwrap() * if sign { -1 } else { 1 }, decimal.parse::<u64>().unwrap()))
         },
-        _ => {
-            // integer
-            Ok(Token::Integer(slice.parse::<i64>().unwrap() * if sign { -1 } else { 1 }))
+        '\n' | '\u{2028}' | '\u{2029}' => {},
+        // NonEscapeCharacter, including the quotes and the backslash itself
+        c => string.push(c),
+    }
+    Ok(())
+}
+
+// Parses exactly n hex digits.
+fn parse_hex_digits(state: &mut Lexer<'_>, n: usize) -> Result<u32, String> {
+    let mut code = 0;
+    for _ in 0..n {
+        match state.lookahead_1().and_then(|c| c.to_digit(16)) {
+            Some(digit) => {
+                code = code * 16 + digit;
+                state.proceed();
+            },
+            None => return Err(format!("Invalid hexadecimal escape sequence, expected {} hex digits", n)),
         }
     }
+    Ok(code)
 }
 
-pub fn parse_string(state: &mut Lexer) -> Result<Token, String> {
-    let mut string = String::new();
-    let enclosing = state.lookahead_1().filter(|c| *c == '"' || *c == '\'').ok_or("Expected string".to_string())?;
+// Parses the code point of \\uXXXX or \\u{X...}, right after the 'u'.
+fn parse_unicode_escape_code(state: &mut Lexer<'_>) -> Result<u32, String> {
+    if state.lookahead_1() != Some('{') {
+        return parse_hex_digits(state, 4)
+    }
+
     state.proceed();
-    while let Some(c) = state.lookahead_1() {
-        if c == enclosing {
+    let mut code: u32 = 0;
+    let mut digits = 0;
+    loop {
+        match state.lookahead_1() {
+            Some('}') if digits > 0 => {
+                state.proceed();
+                return Ok(code)
+            },
+            Some(c) if c.is_ascii_hexdigit() => {
+                code = code * 16 + c.to_digit(16).unwrap_or_default();
+                if code > 0x10FFFF {
+                    return Err("Unicode escape sequence is out of range, must be at most \\u{10FFFF}".to_string())
+                }
+                digits += 1;
+                state.proceed();
+            },
+            _ => return Err("Invalid unicode escape sequence, expected hex digits followed by }".to_string()),
+        }
+    }
+}
+
+// Parses a unicode escape right after the 'u', combining a surrogate pair \\uD83D\\uDE00 into a single character.
+// Lone surrogates cannot be represented in the string and are rejected.
+fn parse_unicode_escape(state: &mut Lexer<'_>) -> Result<char, String> {
+    let code = parse_unicode_escape_code(state)?;
+    match code {
+        0xD800..=0xDBFF => {
+            if state.lookahead_1() != Some('\\') || state.lookahead_2() != Some('u') {
+                return Err(format!("Lone surrogate \\u{:X} is not supported", code))
+            }
             state.proceed();
-            break;
-        } else {
-            string.push(c); // TODO: optimize, i think we can just slice the string
             state.proceed();
+            let low = parse_unicode_escape_code(state)?;
+            if !(0xDC00..=0xDFFF).contains(&low) {
+                return Err(format!("Lone surrogate \\u{:X} is not supported", code))
+            }
+            let combined = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+            char::from_u32(combined).ok_or(format!("Invalid surrogate pair \\u{:X}\\u{:X}", code, low))
+        },
+        0xDC00..=0xDFFF => Err(format!("Lone surrogate \\u{:X} is not supported", code)),
+        code => char::from_u32(code).ok_or(format!("Invalid code point \\u{{{:X}}}", code)),
+    }
+}
+
+// Template literals are lexed as
+// QuasiQuote String (Dollar LeftBrace ...tokens RightBrace String)* QuasiQuote
+// so that there is always one more string chunk than the substitutions.
+// Pushes the string chunk and returns the token that ends it, either the closing QuasiQuote or the LeftBrace of a substitution.
+fn parse_quasi_chunk(state: &mut Lexer<'_>, result: &mut Vec<Token>) -> Result<Token, String> {
+    let mut chunk = Chunk::new(state);
+    loop {
+        match state.lookahead_1() {
+            Some('`') => {
+                result.push(Token::String(chunk.finish(state)));
+                state.proceed();
+                return Ok(Token::QuasiQuote)
+            },
+            Some('$') if state.lookahead_2() == Some('{') => {
+                result.push(Token::String(chunk.finish(state)));
+                state.proceed();
+                state.proceed();
+                result.push(Token::Dollar);
+                state.quasi_stack.push(state.brace_depth);
+                return Ok(Token::LeftBrace)
+            },
+            Some('\\') => {
+                if state.lookahead_2().is_none() {
+                    return Err("Unterminated template literal".to_string())
+                }
+                chunk.escape(state)?;
+            },
+            Some(_) => {
+                state.proceed();
+            },
+            None => return Err("Unterminated template literal".to_string()),
         }
     }
-    Ok(Token::String(string.into()))
 }
 
-fn parse_ident(state: &mut Lexer) -> Result<Token, String> {
-    // [a-zA-Z_][a-zA-Z0-9_]*
-    let mut ident = String::new();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    match state.lookahead_1() {
-        Some(x) if x.is_ascii_alphabetic() || x == '_' => {
-            ident.push(x);
-            state.proceed();
+    #[test]
+    fn test_number_literals() {
+        let cases = [
+            ("1e18", Token::Integer(1_000_000_000_000_000_000)),
+            ("9_223_372_036_854_775_807", Token::Integer(i64::MAX)),
+            (".5", Token::Decimal(0, 5_000_000_000_000_000_000)),
+            ("1.25", Token::Decimal(1, 2_500_000_000_000_000_000)),
+            ("12.5e-1", Token::Decimal(1, 2_500_000_000_000_000_000)),
+            ("0.1000000000000000001", Token::Decimal(0, 1_000_000_000_000_000_001)),
+            ("0n", Token::Bigint(false, Box::new([0]))),
+            ("0x1_0000_0000_0000_0000n", Token::Bigint(false, Box::new([0, 1]))),
+            ("340282366920938463463374607431768211457n", Token::Bigint(false, Box::new([1, 0, 1]))),
+            ("0e999999999999", Token::Integer(0)),
+            // the boundaries of the exact range
+            ("0x7fff_ffff_ffff_ffff", Token::Integer(i64::MAX)),
+            ("9.223372036854775807e18", Token::Integer(i64::MAX)),
+            ("0.0000000000000000001", Token::Decimal(0, 1)),
+            ("1e-19", Token::Decimal(0, 1)),
+            ("9223372036854775807.9999999999999999999", Token::Decimal(i64::MAX, 9_999_999_999_999_999_999)),
+        ];
+        for (code, expected) in cases {
+            let tokens = lex_jessie(code.to_string()).unwrap();
+            assert_eq!(tokens, vec![expected, Token::EOF], "{}", code);
+        }
+
+        for code in ["1__0", "1_", "0_1", "07", "1._5", "0x", "1e", "1.5n", "1e3n", "3in", "0b12"] {
+            assert!(lex_jessie(code.to_string()).is_err(), "{}", code);
+        }
+
+        // just out of the exact range, rejected instead of rounded
+        for code in ["9223372036854775808", "0x8000000000000000", "1e19", "1.5e300", "12345678901234567890", "1e400", "0.00000000000000000001", "1e-20", "0.12345678901234567891", "3.141592653589793238462643"] {
+            let err = lex_jessie(code.to_string()).unwrap_err();
+            assert!(err.contains("out of range") || err.contains("cannot be represented exactly"), "{}: {}", code, err);
         }
-        _ => return Err("Expected identifier".to_string()),
     }
 
-    while let Some(x) = state.lookahead_1() {
-        if x.is_ascii_alphanumeric() || x == '_' {
-            ident.push(x);
-            state.proceed();
-        } else {
-            break;
+    #[test]
+    fn test_string_errors() {
+        for code in [r#""\u{110000}";"#, r#""\uD83D";"#, r#""\x4";"#, r#""\01";"#, "\"unterminated\n\";", "const \\u0031a = 1;"] {
+            let err = lex_jessie(code.to_string()).unwrap_err();
+            assert!(err.contains(" at 1:"), "{}: {}", code, err);
         }
     }
-    Ok(Token::Identifier(ident.into()))
 }
-
@@ -7,10 +7,12 @@
 //pub mod jessie_test;
 pub mod parser;
 pub mod lexer;
+pub mod interner;
 pub mod expression;
 pub mod function;
 pub mod statement;
 pub mod pattern;
+pub mod types;
 pub mod module;
 pub mod object;
 pub mod operation;
@@ -19,12 +21,16 @@ pub mod scope;
 pub mod common;
 mod map;
 pub mod jessie_parser;
+pub mod incremental;
+pub mod printer;
+pub mod conformance;
 
-pub use jessie_parser::JessieParserState;
+pub use jessie_parser::{JessieParserState, GrammarLevel};
 pub use lexer::{Lexer, Token};
 pub use expression::expression;
 pub use module::*;
 
 ///////
 
-//mod bench;
\ No newline at end of file
+#[cfg(test)]
+mod bench;
\ No newline at end of file
@@ -1,66 +1,91 @@
 use std::rc::Rc;
 
-use jessie_ast::{*, module::{ ExportClause, Module, ModuleDeclaration, ModuleItem}};
+use jessie_ast::{*, module::{ ExportClause, Module, ModuleDeclaration, ModuleItem, ImportDeclaration, ImportClause}};
 use utils::Map;
 
-use crate::{parser, statement::{block, const_decl, function_decl, statement}, JessieParserState, Token};
+use crate::{parser, statement::{const_decl, function_decl, statement_item}, common::identifier, jessie_parser::{repeated_elements, GrammarLevel}, expression::expression, types::{is_type_declaration, type_declaration}, JessieParserState, Token};
 
 type ParserError = parser::ParserError<Option<Token>>;
 
 pub fn script(state: &mut JessieParserState) -> Result<Script, ParserError> {
+    state.require_level(GrammarLevel::Jessie, "statement")?;
     state.enter_block();
 
     let mut statements = vec![];
-    while state.lookahead_1() != Some(Token::EOF) {
-        statements.push(statement(state)?);
+    let mut spans = vec![];
+    let mut comments = vec![];
+    while !matches!(state.lookahead_1(), Some(Token::EOF) | None) {
+        let start = state.position();
+        statements.push(statement_item(state)?);
+        spans.push(state.span_from(start));
+        comments.push(state.take_comments(start));
     }
 
+    let comments = state.closing_comments(comments);
     Ok(Script {
-        statements: Block {
-            declarations: state.exit_block(),
-            statements: statements.into_boxed_slice(),
-        }
+        statements: Block::with_spans(state.exit_block(), statements.into_boxed_slice(), spans.into_boxed_slice()).with_comments(comments),
     })
 }
 
+// Parses the script in recovery mode, returning the partial script along with every error found.
+// Statements that failed to parse are replaced with Statement::Error.
+pub fn script_recovering(state: &mut JessieParserState) -> (Script, Vec<ParserError>) {
+    state.recovering = true;
+    let script = script(state).unwrap_or_else(|err| {
+        state.diagnostics.push(err);
+        Script { statements: Block::new(Box::new([]), Box::new([])) }
+    });
+    (script, std::mem::take(&mut state.diagnostics))
+}
+
+// A single JSON value or Justin expression followed by the end of input, for the data formats.
+// At the Jessie level, any expression is accepted.
+pub fn document(state: &mut JessieParserState) -> Result<Expr, ParserError> {
+    let expr = expression(state)?;
+    match state.lookahead_1() {
+        Some(Token::EOF) | None => Ok(expr),
+        la => state.err_expected("end of input", la),
+    }
+}
+
 ///////////////////////
 // Module
 
 pub fn module(mut state: JessieParserState) -> Result<Module, ParserError> {
+    module_internal(&mut state)
+}
+
+// Parses the module in recovery mode, returning the partial module along with every error found.
+// Items that failed to parse are replaced with ModuleItem::Error.
+pub fn module_recovering(mut state: JessieParserState) -> (Module, Vec<ParserError>) {
+    state.recovering = true;
+    let module = module_internal(&mut state).unwrap_or_else(|err| {
+        state.diagnostics.push(err);
+        Module { body: vec![], comments: Box::new([]), scope: None }
+    });
+    (module, state.diagnostics)
+}
+
+fn module_internal(state: &mut JessieParserState) -> Result<Module, ParserError> {
+    state.require_level(GrammarLevel::Jessie, "module")?;
     let mut body = vec![];
+    let mut comments = vec![];
 
-    while let Some(_) = state.lookahead_1() {
-        if state.try_proceed(Token::Import) {
-            unimplemented!("import declaration")
-        }
+    // module level declarations are collected in the items, the block is only for the parser bookkeeping
+    state.enter_block();
+    let mut has_default_export = false;
 
-        let export_clause = if state.try_proceed(Token::Export) {
-            if state.try_proceed(Token::Default) {
-                ExportClause::ExportDefault
-            } else {
-                ExportClause::Export
-            }
-        } else {
-            ExportClause::NoExport
-        };
-    
-        let declaration = match state.lookahead_1() {
-            Some(Token::Const) => {
-                const_decl(&mut state)?
+    while !matches!(state.lookahead_1(), Some(Token::EOF) | None) {
+        let start = state.position();
+        let depth = state.scope.len();
+        match module_item(state, &mut has_default_export) {
+            Ok(item) => body.push(item),
+            Err(err) => {
+                state.recover(err, start, depth)?;
+                body.push(ModuleItem::Error(state.span_from(start)));
             },
-            Some(Token::Let) => {
-                return state.err_expected("either const or function for top level declaration", Some(Token::Let))
-            },
-            Some(Token::Function) => {
-                function_decl(&mut state)?
-            } 
-            t => return state.err_expected("module declaration", t),
-        };
-
-        body.push(ModuleItem::ModuleDeclaration(ModuleDeclaration {
-            export_clause,
-            declaration,
-        }));
+        }
+        comments.push(state.take_comments(start));
     }
 
     // once we have fully walked through the entire module, we have to virtually 'exit' the implicit top level scope and settle the unresolved variables
@@ -69,13 +94,199 @@ pub fn module(mut state: JessieParserState) -> Result<Module, ParserError> {
 
     // let used_builtins = state.scope.exit_module(builtins);
 
+    state.exit_block();
+
     Ok(Module {
         body,
+        comments: state.closing_comments(comments),
+        scope: None,
     })
 }
 
-/* 
-pub fn import_declaration(state: &mut ParserState, proxy: MutableDeclarationPointer) -> Result<ImportDeclaration, ParserError> {
-    unimplemented!("import declaration")
+// moduleItem ::= importDecl | "export"? "default"? moduleDeclaration
+// has_default_export is set by the first export default, and rejects the following ones
+fn module_item(state: &mut JessieParserState, has_default_export: &mut bool) -> Result<ModuleItem, ParserError> {
+    if state.lookahead_1() == Some(&Token::Import) {
+        return import_declaration(state).map(ModuleItem::ImportDeclaration)
+    }
+
+    let export_clause = if state.try_proceed(Token::Export) {
+        if state.try_proceed(Token::Default) {
+            if *has_default_export {
+                return state.err_invalid("module must not have more than one default export")
+            }
+            *has_default_export = true;
+            ExportClause::ExportDefault
+        } else {
+            ExportClause::Export
+        }
+    } else {
+        ExportClause::NoExport
+    };
+
+    if is_type_declaration(state) {
+        if export_clause == ExportClause::ExportDefault {
+            return state.err_invalid("a type cannot be the default export")
+        }
+        return type_declaration(state).map(|decl| ModuleItem::TypeDeclaration(export_clause, decl))
+    }
+
+    let declaration = match state.lookahead_1() {
+        Some(Token::Const) => {
+            const_decl(state)?
+        },
+        Some(Token::Let) => {
+            return state.err_expected("either const or function for top level declaration", Some(&Token::Let))
+        },
+        Some(Token::Function) => {
+            function_decl(state)?
+        } 
+        t => return state.err_expected("module declaration", t),
+    };
+
+    Ok(ModuleItem::ModuleDeclaration(ModuleDeclaration {
+        export_clause,
+        declaration,
+    }))
+}
+
+// importDecl ::= "import" importClause "from" STRING ";"
+pub fn import_declaration(state: &mut JessieParserState) -> Result<ImportDeclaration, ParserError> {
+    state.consume_1(Token::Import)?;
+
+    let import_clause = match state.lookahead_1() {
+        Some(Token::Asterisk) => {
+            // import * as name from source
+            state.proceed();
+            contextual_keyword(state, "as")?;
+            ImportClause::Namespace(Variable::new(identifier(state)?))
+        },
+        Some(Token::LeftBrace) => {
+            // import { name1, name2 as name3 } from source
+            let specifiers = repeated_elements(state, Some(Token::LeftBrace), Token::RightBrace, &import_specifier, true)?;
+            ImportClause::Named(specifiers.into_boxed_slice())
+        },
+        Some(Token::Identifier(_)) => {
+            // import name from source
+            ImportClause::Default(Variable::new(identifier(state)?))
+        },
+        la => return state.err_expected("import clause", la),
+    };
+
+    contextual_keyword(state, "from")?;
+
+    let source = match state.lookahead_1() {
+        Some(Token::String(source)) => state.proceed_then(source.to_string())?,
+        la => return state.err_expected("module specifier", la),
+    };
+
+    state.consume_1(Token::Semicolon)?;
+
+    Ok(ImportDeclaration { import_clause, source })
+}
+
+fn import_specifier(state: &mut JessieParserState) -> Result<(Rc<str>, Variable), ParserError> {
+    let name = identifier(state)?;
+    let local = match state.lookahead_1() {
+        Some(Token::Identifier(as_)) if &**as_ == "as" => {
+            state.proceed();
+            identifier(state)?
+        },
+        _ => name.clone(),
+    };
+    Ok((name, Variable::new(local)))
+}
+
+// "as" and "from" are not reserved, they are lexed as identifiers
+fn contextual_keyword(state: &mut JessieParserState, keyword: &'static str) -> Result<(), ParserError> {
+    match state.lookahead_1() {
+        Some(Token::Identifier(ident)) if &**ident == keyword => state.proceed_then(()),
+        la => state.err_expected(keyword, la),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexer::lex_jessie_with_spans;
+
+    use super::*;
+
+    #[test]
+    fn test_recovery() {
+        let code = "const x = 1;\nconst y = (x;\nfunction f() {\n  return x +;\n  return x;\n}\nconst z = x;\n".to_string();
+        let (tokenstream, spans) = lex_jessie_with_spans(code).unwrap();
+        let mut state = JessieParserState::with_spans(tokenstream, spans);
+        let (script, diagnostics) = script_recovering(&mut state);
+
+        let lines: Vec<u32> = diagnostics.iter().map(|err| err.span().unwrap().line).collect();
+        assert_eq!(lines, vec![2, 4]);
+
+        // the broken statements are replaced with placeholders, the rest is kept
+        let statements = &script.statements.statements;
+        assert_eq!(statements.len(), 4);
+        assert_eq!(statements[1], Statement::Error);
+        let Statement::LocalDeclaration(function) = &statements[2] else { panic!("expected function declaration") };
+        let Declaration::Function(function) = &**function else { panic!("expected function declaration") };
+        let ExprOrBlock::Block(body) = &function.borrow().body else { panic!("expected function body") };
+        assert_eq!(body.statements.len(), 2);
+        assert_eq!(body.statements[0], Statement::Error);
+
+        // unclosed blocks and unexpected tokens never panic
+        for code in ["function f( {", "} } ;", "const = ;", "({ f( })", "const {...a, b} = x;", "let [x"] {
+            let (tokenstream, spans) = lex_jessie_with_spans(code.to_string()).unwrap();
+            let mut state = JessieParserState::with_spans(tokenstream, spans);
+            let (_, diagnostics) = script_recovering(&mut state);
+            assert!(!diagnostics.is_empty(), "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_grammar_levels() {
+        fn parse(code: &str, level: GrammarLevel) -> Result<Expr, String> {
+            let mut state = JessieParserState::from_source(code, level)?;
+            document(&mut state).map_err(|err| format!("{:?}", err))
+        }
+
+        let json = [r#"{"a": [1, -2.5, 1e3, true, false, null], "b": {"c": "\u00e9\n"}}"#, "[]", "{}", r#""\/""#, "-0"];
+        // doubles in JSON, rejected as inexact literals at the other levels
+        let json_doubles = ["[1.5e300, -1e-400, 12345678901234567890]", "1e-20", "3.141592653589793238462643", "[0.30000000000000000001]"];
+        let justin = ["{a: 1, 'b': [2,], ...c}", "`a${1 + 2}`", "undefined", "/* note */ x.y(1) ? -z : 1n", "[...a, 0x10]", "{ get: 1, set }"];
+        let jessie = ["x = 1", "() => 1", "function () { return 1; }", "{ get a() { return 1; } }", "{ f() {} }", "{ get() {}, set(v) {} }"];
+
+        for code in json {
+            let expr = parse(code, GrammarLevel::Json).unwrap_or_else(|err| panic!("{}: {}", code, err));
+            // data is parsed the same way at every level
+            assert_eq!(Ok(expr.clone()), parse(code, GrammarLevel::Justin), "{}", code);
+            assert_eq!(Ok(expr), parse(code, GrammarLevel::Jessie), "{}", code);
+        }
+        for code in json_doubles {
+            parse(code, GrammarLevel::Json).unwrap_or_else(|err| panic!("{}: {}", code, err));
+            assert!(parse(code, GrammarLevel::Justin).is_err(), "{}", code);
+            assert!(parse(code, GrammarLevel::Jessie).is_err(), "{}", code);
+        }
+        for code in justin {
+            assert!(parse(code, GrammarLevel::Json).is_err(), "{}", code);
+            parse(code, GrammarLevel::Justin).unwrap_or_else(|err| panic!("{}: {}", code, err));
+        }
+        for code in jessie {
+            let err = parse(code, GrammarLevel::Justin).unwrap_err();
+            assert!(err.contains("not allowed in Justin"), "{}: {}", code, err);
+            parse(code, GrammarLevel::Jessie).unwrap_or_else(|err| panic!("{}: {}", code, err));
+        }
+        // arrow functions with a bare parameter, and in call arguments
+        for code in ["x => x", "f(() => 1)", "[1].map(x => x + 1)"] {
+            let err = parse(code, GrammarLevel::Justin).unwrap_err();
+            assert!(err.contains("arrow function is not allowed in Justin"), "{}: {}", code, err);
+            parse(code, GrammarLevel::Jessie).unwrap_or_else(|err| panic!("{}: {}", code, err));
+        }
+
+        // rejected by the lexer or the parser at the JSON level
+        for code in ["// c\n1", "'a'", "`a`", ".5", "1.", "0x1", "1_000", "1n", r#""\x41""#, r#""\u{41}""#, "\"\t\"", "[1,]", "{\"a\": 1,}", "{a: 1}", "- 1", "+1", "NaN", "1 2", "1e400", "[-1e400]"] {
+            assert!(parse(code, GrammarLevel::Json).is_err(), "{}", code);
+        }
+
+        // statements are only allowed in Jessie
+        let mut state = JessieParserState::from_source("1;", GrammarLevel::Justin).unwrap();
+        assert!(script(&mut state).is_err());
+    }
 }
-*/
@@ -1,5 +1,5 @@
 use jessie_ast::*;
-use crate::{jessie_parser::{JessieParserState, enclosed_element, repeated_elements}, Token, parser, expression::{expression, arg, call_and_unary_op, primary_expr}, common::identifier};
+use crate::{jessie_parser::{JessieParserState, enclosed_element, repeated_elements}, Token, parser, expression::{expression, arg, call_and_unary_op, primary_expr, quasi_expr}, common::identifier};
 
 type ParserState = JessieParserState; 
 type ParserError = parser::ParserError<Option<Token>>;
@@ -279,7 +279,7 @@ fn pow_expr_internal(state: &mut ParserState, result: Expr) -> Result<Expr, Pars
     // the case where the leftmost expression is NOT a UnaryExpression. 
 
     if let Some(la) = state.lookahead_1() {
-        if la != Token::AsteriskAsterisk {
+        if la != &Token::AsteriskAsterisk {
             return Ok(result)
         } 
     } else {
@@ -296,11 +296,11 @@ fn pow_expr(state: &mut ParserState) -> Result<Expr, ParserError> {
     println!("pow_expr");
     let (left, _) = call_and_unary_op(state)?;
     if let Expr::UnaryExpr(_) = left {
-        // Not a UnaryExpression, so we can parse the power expression.
-        pow_expr_with_leftmost(state, left)
-    } else {
         // Is a UnaryExpression, so we cannot parse the power expression.
         pow_expr_with_leftmost_no_power(state, left)
+    } else {
+        // Not a UnaryExpression, so we can parse the power expression.
+        pow_expr_with_leftmost(state, left)
     }
 }
 
@@ -314,7 +314,7 @@ fn pow_expr_with_leftmost_no_power(state: &mut ParserState, left: Expr) -> Resul
     // the leftmost expression is already parsed as a UnaryExpression, and the power operator cannot come after.
     // so we can skip the power expression parsing.
     if let Some(Token::AsteriskAsterisk) = state.lookahead_1() {
-        return state.err_expected("no power operator", Some(Token::AsteriskAsterisk))
+        return state.err_expected("no power operator", Some(&Token::AsteriskAsterisk))
     } else {
         Ok(left)
     }
@@ -324,11 +324,21 @@ fn call_post_op(state: &mut ParserState) -> Result<CallPostOp, ParserError> {
     match state.lookahead_1() {
         Some(Token::LeftParen) => repeated_elements(state, Some(Token::LeftParen), Token::RightParen, &arg, true).map(|x| CallPostOp::Call(x.into_boxed_slice())),
         Some(Token::LeftBracket) => enclosed_element(state, Token::LeftBracket, Token::RightBracket, &expression).map(|x| CallPostOp::Index(x)),
+        Some(Token::QuasiQuote) => quasi_expr(state).map(|x| CallPostOp::QuasiExpr(Box::new(x))),
         Some(Token::Dot) => {
             state.proceed();
             let ident = identifier(state)?;
             Ok(CallPostOp::Member(ident))
         },
+        Some(Token::QuestionDot) => {
+            state.proceed();
+            match state.lookahead_1() {
+                Some(Token::LeftParen) => repeated_elements(state, Some(Token::LeftParen), Token::RightParen, &arg, true).map(|x| CallPostOp::OptionalCall(x.into_boxed_slice())),
+                Some(Token::LeftBracket) => enclosed_element(state, Token::LeftBracket, Token::RightBracket, &expression).map(CallPostOp::OptionalIndex),
+                Some(Token::QuasiQuote) => state.err_invalid("tagged template in optional chain"),
+                _ => identifier(state).map(CallPostOp::OptionalMember),
+            }
+        },
         c => state.err_expected("index, member, or call operator", c),
     }
 }
@@ -337,17 +347,22 @@ pub fn call_expr_internal(state: &mut ParserState, mut expr: Expr) -> Result<(Ex
     let mut only_member_post_op = true;
 
     let mut post_ops = Vec::new();
+    let start = state.position();
 
-    while let Ok(post_op) = call_post_op(state) {
+    // errors inside a post operator, e.g. in the call arguments, are reported as they are
+    while let Some(Token::LeftParen | Token::LeftBracket | Token::QuasiQuote | Token::Dot | Token::QuestionDot) = state.lookahead_1() {
+        let post_op = call_post_op(state)?;
         match post_op {
-            CallPostOp::Call(_) => { only_member_post_op = false },
+            // optional chains are not assignable either
+            CallPostOp::Call(_) | CallPostOp::QuasiExpr(_) => { only_member_post_op = false },
+            CallPostOp::OptionalIndex(_) | CallPostOp::OptionalMember(_) | CallPostOp::OptionalCall(_) => { only_member_post_op = false },
             CallPostOp::Index(_) | CallPostOp::Member(_) => {},
         }
         post_ops.push(post_op);
     }
 
     if !post_ops.is_empty() {
-        expr = Expr::CallExpr(Box::new(CallExpr { expr, post_ops: post_ops.into_boxed_slice(), }));
+        expr = Expr::CallExpr(Box::new(CallExpr { span: expr.span().to(state.span_from(start)), expr, post_ops: post_ops.into_boxed_slice() }));
     }
 
     Ok((expr, only_member_post_op))
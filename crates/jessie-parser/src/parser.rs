@@ -1,28 +1,57 @@
 use core::fmt::Debug;
 use std::rc::Rc;
 
+use jessie_ast::Span;
+
 extern crate utils;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ParserError<C: Sized> {
     // A token is expected to be followed,
     // but a different token found.
-    ExpectedToken(String, String, C),
+    ExpectedToken(String, String, C, Span),
 
     // Not a valid syntax in Jessie.
-    InvalidExpression(String, String),
+    InvalidExpression(String, String, Span),
 
     // Valid syntax, but not implemented yet.
-    Unimplemented(String, String),
+    Unimplemented(String, String, Span),
 
     // Scoping error.
-    ScopeError(String, String, String),
+    ScopeError(String, String, String, Span),
 
     DuplicateDeclaration,
 
     UnresolvedVariable(Rc<str>),
 }
 
+impl<C> ParserError<C> {
+    // Location of the token where the error occured, if known.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            ParserError::ExpectedToken(_, _, _, span) |
+            ParserError::InvalidExpression(_, _, span) |
+            ParserError::Unimplemented(_, _, span) |
+            ParserError::ScopeError(_, _, _, span) => Some(*span).filter(|span| !span.is_unknown()),
+            _ => None,
+        }
+    }
+}
+
+impl<C: Debug> ParserError<C> {
+    // Human readable message, without the location.
+    pub fn message(&self) -> String {
+        match self {
+            ParserError::ExpectedToken(_, expected, found, _) => format!("expected {}, found {:?}", expected, found),
+            ParserError::InvalidExpression(_, message, _) => message.clone(),
+            ParserError::Unimplemented(_, message, _) => format!("unimplemented: {}", message),
+            ParserError::ScopeError(_, message, var, _) => format!("{}: {}", message, var),
+            ParserError::DuplicateDeclaration => "duplicate declaration".to_string(),
+            ParserError::UnresolvedVariable(name) => format!("unresolved variable {}", name),
+        }
+    }
+}
+
 
 
 
@@ -141,6 +170,8 @@ pub trait Node {
 pub struct ParserState<T> {
     pub input: Vec<T>,
     pub pos: usize,
+    // span of each input element, empty if not tracked
+    pub spans: Vec<Span>,
 }
 
 impl<T: ToString+Clone+Debug+ToString+PartialEq> ParserState<T> {
@@ -148,6 +179,29 @@ impl<T: ToString+Clone+Debug+ToString+PartialEq> ParserState<T> {
         Self {
             input,
             pos: 0,
+            spans: Vec::new(),
+        }
+    }
+
+    pub fn with_spans(input: Vec<T>, spans: Vec<Span>) -> Self {
+        Self {
+            input,
+            pos: 0,
+            spans,
+        }
+    }
+
+    // span of the next element, or of the last one at the end of input
+    pub fn span(&self) -> Span {
+        self.spans.get(self.pos).or(self.spans.last()).cloned().unwrap_or_default()
+    }
+
+    // span from the element at start to the last consumed element
+    pub fn span_from(&self, start: usize) -> Span {
+        let first = self.spans.get(start).cloned().unwrap_or_default();
+        match self.pos.checked_sub(1).and_then(|last| self.spans.get(last)) {
+            Some(last) if self.pos > start => first.to(*last),
+            _ => first,
         }
     }
 
@@ -156,38 +210,38 @@ impl<T: ToString+Clone+Debug+ToString+PartialEq> ParserState<T> {
         self.pos >= self.input.len()
     }
 
-    pub fn lookahead_1(&self) -> Option<T> {
-        self.input.get(self.pos).cloned()
+    pub fn lookahead_1(&self) -> Option<&T> {
+        self.input.get(self.pos)
     }
 
-    pub fn lookahead_2(&self) -> Option<T> {
-        self.input.get(self.pos + 1).cloned()
+    pub fn lookahead_2(&self) -> Option<&T> {
+        self.input.get(self.pos + 1)
     }
 
-    pub fn lookahead_3(&self) -> Option<T> {
-        self.input.get(self.pos + 2).cloned()
+    pub fn lookahead_3(&self) -> Option<&T> {
+        self.input.get(self.pos + 2)
     }
 
-    pub fn lookahead_4(&self) -> Option<T> {
-        self.input.get(self.pos + 3).cloned()
+    pub fn lookahead_4(&self) -> Option<&T> {
+        self.input.get(self.pos + 3)
     }
 
-    pub fn lookahead_n(&self, n: usize) -> Option<T> {
+    pub fn lookahead_n(&self, n: usize) -> Option<&T> {
         if n == 0 {
             panic!("lookahead_n(0) is not allowed");
         }
-        self.input.get(self.pos + n - 1).cloned()
+        self.input.get(self.pos + n - 1)
     }
 
-    pub fn proceed(&mut self) -> Option<T> {
+    pub fn proceed(&mut self) -> Option<&T> {
         let result = self.input.get(self.pos);
         if result.is_some() {
             self.pos += 1;
         }
-        result.cloned()
+        result
     }
     pub fn try_proceed(&mut self, c: T) -> bool {
-        if self.lookahead_1() == Some(c) {
+        if self.lookahead_1() == Some(&c) {
             self.proceed();
             true
         } else {
@@ -196,11 +250,11 @@ impl<T: ToString+Clone+Debug+ToString+PartialEq> ParserState<T> {
     }
 
     pub fn consume_1(&mut self, c: T) -> Result<(), ParserError<Option<T>>> {
-        if self.lookahead_1() == Some(c.clone()) {
+        if self.lookahead_1() == Some(&c) {
             self.proceed();
             Ok(())
         } else {
-            Err(ParserError::ExpectedToken(self.input_slice(), format!("consume_1: {:?}", c), self.lookahead_1()))
+            Err(ParserError::ExpectedToken(self.input_slice(), format!("consume_1: {:?}", c), self.lookahead_1().cloned(), self.span()))
         }
     }
 
@@ -208,7 +262,7 @@ impl<T: ToString+Clone+Debug+ToString+PartialEq> ParserState<T> {
         let pos = self.pos;
 
         for i in 0..s.len() {
-            if self.lookahead_1() == s.get(i).cloned() {
+            if self.lookahead_1() == s.get(i) {
                 self.proceed();
             } else {
                 self.pos = pos;
@@ -343,20 +397,20 @@ impl<T: ToString+Clone+Debug+ToString+PartialEq> ParserState<T> {
     }
 
     pub fn err_expected<R, C>(&self, message: &'static str, actual: C) -> Result<R, ParserError<C>> {
-        Err(ParserError::ExpectedToken(self.input_slice(), message.to_string(), actual))
+        Err(ParserError::ExpectedToken(self.input_slice(), message.to_string(), actual, self.span()))
     }
     
     
-    pub fn err_invalid<R, C>(&self, message: &'static str) -> Result<R, ParserError<C>> {
-        Err(ParserError::InvalidExpression(self.input_slice(), message.to_string()))
+    pub fn err_invalid<R, C>(&self, message: impl Into<String>) -> Result<R, ParserError<C>> {
+        Err(ParserError::InvalidExpression(self.input_slice(), message.into(), self.span()))
     }
     
     pub fn err_unimplemented<R, C>(&self, message: &'static str) -> Result<R, ParserError<C>> {
-        Err(ParserError::Unimplemented(self.input_slice(), message.to_string()))
+        Err(ParserError::Unimplemented(self.input_slice(), message.to_string(), self.span()))
     }
     
     pub fn err_scope<R, C>(&self, message: &'static str, var: String) -> Result<R, ParserError<C>> {
-        Err(ParserError::ScopeError(self.input_slice(), message.to_string(), var))
+        Err(ParserError::ScopeError(self.input_slice(), message.to_string(), var, self.span()))
     }
 }
 
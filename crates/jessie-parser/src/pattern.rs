@@ -1,7 +1,7 @@
 use jessie_ast::*;
 use crate::{Token, parser, expression};
-use crate::common::{identifier, use_variable};
-use crate::jessie_parser::{JessieParserState, repeated_elements};
+use crate::common::{identifier, use_variable, optional_type_ann};
+use crate::jessie_parser::{JessieParserState, repeated_elements, enclosed_element};
 
 type ParserState = JessieParserState; 
 type ParserError = parser
@@ -12,8 +12,20 @@ type ParserError = parser
 
 pub fn binding_pattern(state: &mut ParserState) -> Result<Pattern, ParserError> {
     match state.lookahead_1() {
-        Some(Token::LeftBracket) => repeated_elements(state, Some(Token::LeftBracket), Token::RightBracket, &param, false).map(|x| Pattern::ArrayPattern(Box::new(ArrayPattern(x.into_boxed_slice())))),
-        Some(Token::LeftBrace) => repeated_elements(state, Some(Token::LeftBrace), Token::RightBrace, &prop_param, false).map(|x| Pattern::RecordPattern(Box::new(RecordPattern(x.into_boxed_slice())))),
+        Some(Token::LeftBracket) => {
+            let elements = repeated_elements(state, Some(Token::LeftBracket), Token::RightBracket, &param, true)?;
+            if elements.iter().rev().skip(1).any(|element| matches!(element, Pattern::Rest(_))) {
+                return state.err_invalid("rest element must be last in array pattern")
+            }
+            Ok(Pattern::ArrayPattern(Box::new(ArrayPattern(elements.into_boxed_slice()))))
+        },
+        Some(Token::LeftBrace) => {
+            let props = repeated_elements(state, Some(Token::LeftBrace), Token::RightBrace, &prop_param, true)?;
+            if props.iter().rev().skip(1).any(|prop| matches!(prop, PropParam::Rest(_))) {
+                return state.err_invalid("rest property must be last in record pattern")
+            }
+            Ok(Pattern::RecordPattern(Box::new(RecordPattern(props.into_boxed_slice()))))
+        },
         c => state.err_expected("binding pattern", c),
     }
 }
@@ -38,14 +50,33 @@ pub fn param(state: &mut ParserState) -> Result<Pattern, ParserError> {
     }
 
     let pat = pattern(state)?;
-    if let Pattern::Variable(x) = &pat {
-        if state.try_proceed(Token::Equal) {
-            let expr = expression(state)?;
-            return Ok(Pattern::optional(*x.clone(), expr))
-        }
-    } 
+    optional_default(state, pat)
+}
+
+// param with an optional type annotation between the pattern and the default value
+pub fn typed_param(state: &mut ParserState) -> Result<(Pattern, Option<TypeAnn>), ParserError> {
+    if state.try_proceed(Token::DotDotDot) {
+        let pat = pattern(state)?;
+        let ty = optional_type_ann(state)?;
+        return Ok((Pattern::Rest(Box::new(pat)), ty))
+    }
 
-    Ok(pat)
+    let pat = pattern(state)?;
+    let ty = optional_type_ann(state)?;
+    Ok((optional_default(state, pat)?, ty))
+}
+
+// pattern = expr
+fn optional_default(state: &mut ParserState, pat: Pattern) -> Result<Pattern, ParserError> {
+    if !state.try_proceed(Token::Equal) {
+        return Ok(pat)
+    }
+
+    let expr = expression(state)?;
+    match pat.with_default(expr) {
+        Some(pat) => Ok(pat),
+        None => state.err_invalid("default value is not allowed here"),
+    }
 }
 
 fn prop_param(state: &mut ParserState) -> Result<PropParam, ParserError> {
@@ -53,26 +84,35 @@ fn prop_param(state: &mut ParserState) -> Result<PropParam, ParserError> {
         return Ok(PropParam::Rest(Box::new(use_variable(state)?)))
     }
 
+    if state.lookahead_1() == Some(&Token::LeftBracket) {
+        let key = enclosed_element(state, Token::LeftBracket, Token::RightBracket, &expression)?;
+        state.consume_1(Token::Colon)?;
+        let pat = pattern(state)?;
+        let pat = optional_default(state, pat)?;
+        return Ok(PropParam::Computed(key, pat))
+    }
+
+    let span = state.span();
     let key = identifier(state)?;
 
     match state.lookahead_1() {
         Some(Token::Colon) => {
             state.proceed();
             let pat = pattern(state)?;
+            let pat = optional_default(state, pat)?;
             Ok(PropParam::KeyValue(Box::new(Field{name: key}), pat))
         },
         Some(Token::Equal) => {
-            unimplemented!("default value in record pattern")
-            /* 
+            // shorthand with default value, represented as key: key = expr
             state.proceed();
             let expr = expression(state)?;
-            Ok(PropParam::(key, expr))
-            */
+            let var = Variable::new(key.clone()).with_span(span);
+            Ok(PropParam::KeyValue(Box::new(Field{name: key}), Pattern::optional(var, expr)))
         }
         _ => {
             //let var = state.scope.use_variable(key.clone());
             let field = Box::new(Field{name: key.clone()});
-            Ok(PropParam::Shorthand(field, Box::new(Variable::new(key))))
+            Ok(PropParam::Shorthand(field, Box::new(Variable::new(key).with_span(span))))
         }
     }
 }
@@ -0,0 +1,1192 @@
+// Code generator from the AST back to Jessie source.
+// Parsing the printed source gives back the same AST, with the exception of the spans and Statement::Error.
+// Parentheses are only printed where the AST has a ParenedExpr, or where the operator precedence requires them.
+
+use std::rc::Rc;
+
+use jessie_ast::*;
+
+use crate::lexer::{is_id_start, is_id_continue};
+
+// Binding power of the expressions, following the parser rather than the ECMAScript spec:
+// the relational, equality and bitwise operators share a single left associative level(eager_expr),
+// and ?? shares the level with ||.
+#[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
+pub enum Precedence {
+    Assignment, // assignments and arrow functions
+    Conditional,
+    OrElse,
+    AndThen,
+    Eager,
+    Shift,
+    Additive,
+    Multiplicative,
+    Power,
+    Unary,
+    Call, // call expressions and primary expressions
+}
+
+// Where the expression is printed, relative to the start of the enclosing expression.
+// The parser reads a function keyword at the start of an expression as a whole function expression,
+// without post ops or operators following it.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Position {
+    // the whole expression, right after the parser called expression()
+    Top,
+    // the leftmost operand of the expression at Top
+    Leftmost,
+    Inner,
+}
+
+// Words that the lexer never emits as Token::Identifier.
+// They cannot be used as unquoted property names, except get and set which the parser reads as names.
+const RESERVED: &[&str] = &[
+    "arguments", "async", "await", "break", "case", "catch", "class", "const", "continue", "debugger",
+    "default", "delete", "do", "else", "enum", "eval", "export", "extends", "false", "finally",
+    "for", "function", "if", "implements", "import", "in", "instanceof", "interface", "let",
+    "new", "null", "package", "private", "protected", "public", "require", "return", "static",
+    "super", "switch", "this", "throw", "true", "try", "typeof", "undefined", "var", "void",
+    "while", "with", "yield",
+];
+
+pub struct Printer {
+    // unit of indentation, repeated for each nesting level
+    indent: String,
+    depth: usize,
+    out: String,
+}
+
+impl Printer {
+    pub fn new() -> Self {
+        Printer {
+            indent: "    ".to_string(),
+            depth: 0,
+            out: String::new(),
+        }
+    }
+
+    pub fn with_indent(self, indent: impl Into<String>) -> Self {
+        Printer { indent: indent.into(), ..self }
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+
+    fn write(&mut self, s: &str) {
+        self.out.push_str(s);
+    }
+
+    fn newline(&mut self) {
+        self.out.push('\n');
+        for _ in 0..self.depth {
+            self.out.push_str(&self.indent);
+        }
+    }
+
+    // separated by ", "
+    fn list<T>(&mut self, items: &[T], mut print: impl FnMut(&mut Self, &T)) {
+        for (index, item) in items.iter().enumerate() {
+            if index > 0 {
+                self.write(", ");
+            }
+            print(self, item);
+        }
+    }
+
+    ///////////////////////
+    // Script and Module
+
+    pub fn script(&mut self, script: &Script) {
+        let block = &script.statements;
+        for (index, statement) in block.statements.iter().enumerate() {
+            self.leading_comments(block.comments_of(index), true);
+            self.statement(statement);
+            self.trailing_comments(block.comments_of(index), true);
+            self.newline();
+        }
+        self.closing_comments(block.comments_of(block.statements.len()));
+    }
+
+    pub fn module(&mut self, module: &Module) {
+        for (index, item) in module.body.iter().enumerate() {
+            self.leading_comments(module.comments_of(index), true);
+            match item {
+                ModuleItem::ImportDeclaration(import) => self.import_declaration(import),
+                ModuleItem::ModuleDeclaration(decl) => {
+                    match decl.export_clause {
+                        ExportClause::NoExport => {},
+                        ExportClause::Export => self.write("export "),
+                        ExportClause::ExportDefault => self.write("export default "),
+                    }
+                    self.declaration(&decl.declaration);
+                },
+                ModuleItem::TypeDeclaration(export_clause, decl) => {
+                    if *export_clause == ExportClause::Export {
+                        self.write("export ");
+                    }
+                    self.type_declaration(decl);
+                },
+                ModuleItem::Error(_) => self.write("/* error */"),
+            }
+            self.trailing_comments(module.comments_of(index), true);
+            self.newline();
+        }
+        self.closing_comments(module.comments_of(module.body.len()));
+    }
+
+    ///////////////////////
+    // Comments
+
+    fn comment(&mut self, comment: &Comment) {
+        match comment.kind {
+            CommentKind::Line => {
+                self.write("//");
+                self.write(&comment.text);
+            },
+            CommentKind::Block => {
+                self.write("/*");
+                self.write(&comment.text);
+                self.write("*/");
+            },
+        }
+    }
+
+    // Before the node, on their own lines, or inline for block comments if own_line is false
+    fn leading_comments(&mut self, comments: &[Comment], own_line: bool) {
+        for comment in comments.iter().filter(|comment| !comment.trailing) {
+            self.comment(comment);
+            if own_line || comment.kind == CommentKind::Line {
+                self.newline();
+            } else {
+                self.write(" ");
+            }
+        }
+    }
+
+    // After the node on the same line. A line comment ends the line, unless line_ends tells that the caller does.
+    fn trailing_comments(&mut self, comments: &[Comment], line_ends: bool) {
+        let mut trailing = comments.iter().filter(|comment| comment.trailing).peekable();
+        while let Some(comment) = trailing.next() {
+            self.write(" ");
+            self.comment(comment);
+            if comment.kind == CommentKind::Line && (trailing.peek().is_some() || !line_ends) {
+                self.newline();
+            }
+        }
+    }
+
+    // the comments at the end of a script or module
+    fn closing_comments(&mut self, comments: &[Comment]) {
+        for comment in comments {
+            self.comment(comment);
+            self.newline();
+        }
+    }
+
+    fn import_declaration(&mut self, import: &ImportDeclaration) {
+        self.write("import ");
+        match &import.import_clause {
+            ImportClause::Namespace(var) => {
+                self.write("* as ");
+                self.write(&var.name);
+            },
+            ImportClause::Named(names) => {
+                self.write("{ ");
+                self.list(names, |printer, (name, var)| {
+                    printer.write(name);
+                    if var.name != *name {
+                        printer.write(" as ");
+                        printer.write(&var.name);
+                    }
+                });
+                self.write(" }");
+            },
+            ImportClause::Default(var) => self.write(&var.name),
+        }
+        self.write(" from ");
+        self.write(&string_literal(&import.source));
+        self.write(";");
+    }
+
+    ///////////////////////
+    // Statements
+
+    pub fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::LocalDeclaration(decl) => self.declaration(decl),
+            Statement::Block(block) => self.block(block),
+            Statement::IfStatement(stmt) => self.if_statement(stmt),
+            Statement::WhileStatement(stmt) => {
+                self.write("while (");
+                self.expression(&stmt.condition);
+                self.write(") ");
+                self.block(&stmt.body);
+            },
+            Statement::ForOfStatement(stmt) => {
+                self.write(if stmt.is_const { "for (const " } else { "for (let " });
+                self.pattern(&stmt.pattern);
+                self.write(" of ");
+                self.expression(&stmt.iterable);
+                self.write(") ");
+                self.block(&stmt.body);
+            },
+            Statement::Continue => self.write("continue;"),
+            Statement::Break => self.write("break;"),
+            Statement::Return(expr) => {
+                self.write("return ");
+                self.expression(expr);
+                self.write(";");
+            },
+            Statement::ReturnEmpty => self.write("return;"),
+            Statement::Throw(expr) => {
+                self.write("throw ");
+                self.expression(expr);
+                self.write(";");
+            },
+            Statement::TryStatement(stmt) => {
+                self.write("try ");
+                self.block(&stmt.block);
+                if let Some(catch) = &stmt.catch {
+                    self.write(" catch (");
+                    self.pattern(&catch.param);
+                    self.write(") ");
+                    self.block(&catch.body);
+                }
+                if let Some(finally) = &stmt.finally {
+                    self.write(" finally ");
+                    self.block(finally);
+                }
+            },
+            Statement::SwitchStatement(stmt) => self.switch_statement(stmt),
+            Statement::TypeDeclaration(decl) => self.type_declaration(decl),
+            Statement::ExprStatement(expr) => {
+                // a statement starting with { or function is a block or a function declaration
+                if starts_with_brace(expr) || matches!(&**expr, Expr::Function(func) if func.name != FunctionName::Arrow) {
+                    self.write("(");
+                    self.expression(expr);
+                    self.write(")");
+                } else {
+                    self.expression(expr);
+                }
+                self.write(";");
+            },
+            // the source of a statement that failed to parse is not kept
+            Statement::Error => self.write("/* error */"),
+        }
+    }
+
+    fn declaration(&mut self, decl: &Declaration) {
+        match decl {
+            Declaration::Const(decls) => {
+                self.write("const ");
+                self.list(decls, Self::variable_declaration);
+                self.write(";");
+            },
+            Declaration::Let(decls) => {
+                self.write("let ");
+                self.list(decls, Self::variable_declaration);
+                self.write(";");
+            },
+            Declaration::Function(func) => {
+                let func = func.borrow();
+                self.leading_comments(&func.comments, true);
+                self.function_uncommented(&func);
+                self.trailing_comments(&func.comments, true);
+            },
+        }
+    }
+
+    fn variable_declaration(&mut self, decl: &VariableDeclaration) {
+        self.pattern(&decl.pattern);
+        self.type_ann_suffix(decl.type_ann.as_ref());
+        if let Some(value) = &decl.value {
+            self.write(" = ");
+            self.expression(value);
+        }
+    }
+
+    pub fn block(&mut self, block: &Block) {
+        let closing = block.comments_of(block.statements.len());
+        if block.statements.is_empty() && closing.is_empty() {
+            return self.write("{}")
+        }
+        self.write("{");
+        self.depth += 1;
+        for (index, statement) in block.statements.iter().enumerate() {
+            self.newline();
+            self.leading_comments(block.comments_of(index), true);
+            self.statement(statement);
+            self.trailing_comments(block.comments_of(index), true);
+        }
+        for comment in closing {
+            self.newline();
+            self.comment(comment);
+        }
+        self.depth -= 1;
+        self.newline();
+        self.write("}");
+    }
+
+    fn if_statement(&mut self, stmt: &IfStatement) {
+        self.write("if (");
+        self.expression(&stmt.condition);
+        self.write(") ");
+        self.block(&stmt.consequent);
+        match &stmt.alternate {
+            ElseArm::NoElse => {},
+            ElseArm::Else(block) => {
+                self.write(" else ");
+                self.block(block);
+            },
+            ElseArm::ElseIf(stmt) => {
+                self.write(" else ");
+                self.if_statement(stmt);
+            },
+        }
+    }
+
+    fn switch_statement(&mut self, stmt: &SwitchStatement) {
+        self.write("switch (");
+        self.expression(&stmt.discriminant);
+        self.write(") {");
+        self.depth += 1;
+        for clause in stmt.clauses.iter() {
+            for label in clause.labels.iter() {
+                self.newline();
+                match label {
+                    CaseLabel::Case(expr) => {
+                        self.write("case ");
+                        self.expression(expr);
+                        self.write(":");
+                    },
+                    CaseLabel::Default => self.write("default:"),
+                }
+            }
+            self.depth += 1;
+            for statement in clause.body.statements.iter() {
+                self.newline();
+                self.statement(statement);
+            }
+            self.depth -= 1;
+        }
+        self.depth -= 1;
+        if !stmt.clauses.is_empty() {
+            self.newline();
+        }
+        self.write("}");
+    }
+
+    ///////////////////////
+    // Expressions
+
+    pub fn expression(&mut self, expr: &Expr) {
+        self.expr(expr, Precedence::Assignment, Position::Top)
+    }
+
+    // Prints the expression as an operand that binds at least as tight as min.
+    fn expr(&mut self, expr: &Expr, min: Precedence, position: Position) {
+        let function_at_start = position == Position::Leftmost && matches!(expr, Expr::Function(func) if func.name != FunctionName::Arrow);
+        if precedence(expr) < min || function_at_start {
+            // parenthesized expression starts over from expression()
+            self.write("(");
+            self.expr_unparenthesized(expr, Position::Top);
+            self.write(")");
+        } else {
+            self.expr_unparenthesized(expr, position);
+        }
+    }
+
+    fn expr_unparenthesized(&mut self, expr: &Expr, position: Position) {
+        // the leftmost operand is still at the start of the expression
+        let leftmost = if position == Position::Inner { Position::Inner } else { Position::Leftmost };
+        match expr {
+            Expr::DataLiteral(literal) => self.data_literal(&literal.data),
+            Expr::Array(array) => {
+                self.write("[");
+                self.list(&array.0, Self::element);
+                self.write("]");
+            },
+            Expr::Record(record) => self.record(record),
+            Expr::Function(func) => self.function(func),
+            Expr::Assignment(assignment) => {
+                let Assignment(op, lvalue, value) = &**assignment;
+                match lvalue {
+                    LValue::Variable(var) => self.write(&var.name),
+                    LValue::CallLValue(call) => {
+                        self.expr(&call.expr, Precedence::Call, leftmost);
+                        for op in call.post_ops.iter() {
+                            match op {
+                                LValueCallPostOp::Index(index) => {
+                                    self.write("[");
+                                    self.expression(index);
+                                    self.write("]");
+                                },
+                                LValueCallPostOp::Member(name) => {
+                                    self.write(".");
+                                    self.write(name);
+                                },
+                            }
+                        }
+                    },
+                }
+                self.write(&format!(" {:?} ", op));
+                self.expression(value);
+            },
+            Expr::CondExpr(cond) => {
+                let CondExpr(condition, consequent, alternate) = &**cond;
+                self.expr(condition, Precedence::OrElse, leftmost);
+                self.write(" ? ");
+                self.expression(consequent);
+                self.write(" : ");
+                self.expression(alternate);
+            },
+            Expr::BinaryExpr(binary) => {
+                let BinaryExpr(op, left, right) = &**binary;
+                let level = binary_precedence(op);
+                if *op == BinaryOp::Pow {
+                    // right associative, and the left operand cannot be a unary expression
+                    self.expr(left, Precedence::Call, leftmost);
+                    self.write(" ** ");
+                    self.expr(right, Precedence::Power, Position::Inner);
+                } else {
+                    self.expr(left, level, leftmost);
+                    self.write(&format!(" {:?} ", op));
+                    self.expr(right, next_precedence(level), Position::Inner);
+                }
+            },
+            Expr::UnaryExpr(unary) => {
+                let mut previous = None;
+                for op in unary.op.iter() {
+                    // -- and ++ are lexed as the decrement and increment operators
+                    if matches!((previous, op), (Some(UnaryOp::Neg), UnaryOp::Neg) | (Some(UnaryOp::Pos), UnaryOp::Pos)) {
+                        self.write(" ");
+                    }
+                    self.write(&format!("{:?}", op));
+                    previous = Some(op.clone());
+                }
+                self.expr(&unary.expr, Precedence::Call, Position::Inner);
+            },
+            Expr::CallExpr(call) => {
+                self.expr(&call.expr, Precedence::Call, leftmost);
+                // 1.x would be lexed as a decimal followed by an identifier
+                if matches!(&call.expr, Expr::DataLiteral(literal) if matches!(literal.data, DataLiteral::Integer(_))) && matches!(call.post_ops.first(), Some(CallPostOp::Member(_))) {
+                    self.write(" ");
+                }
+                for op in call.post_ops.iter() {
+                    self.call_post_op(op);
+                }
+            },
+            Expr::QuasiExpr(quasi) => self.quasi(quasi),
+            Expr::ParenedExpr(expr) => {
+                self.write("(");
+                self.expression(expr);
+                self.write(")");
+            },
+            Expr::Variable(var) => self.write(&var.name),
+            Expr::Spread(expr) => {
+                self.write("...");
+                self.expression(expr);
+            },
+        }
+    }
+
+    // array elements and call arguments, which can be spread
+    fn element(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Spread(expr) => {
+                self.write("...");
+                self.expression(expr);
+            },
+            expr => self.expression(expr),
+        }
+    }
+
+    fn call_post_op(&mut self, op: &CallPostOp) {
+        match op {
+            CallPostOp::Index(index) => {
+                self.write("[");
+                self.expression(index);
+                self.write("]");
+            },
+            CallPostOp::Member(name) => {
+                self.write(".");
+                self.write(name);
+            },
+            CallPostOp::QuasiExpr(quasi) => self.quasi(quasi),
+            CallPostOp::Call(args) => {
+                self.write("(");
+                self.list(args, Self::element);
+                self.write(")");
+            },
+            CallPostOp::OptionalIndex(index) => {
+                self.write("?.[");
+                self.expression(index);
+                self.write("]");
+            },
+            CallPostOp::OptionalMember(name) => {
+                self.write("?.");
+                self.write(name);
+            },
+            CallPostOp::OptionalCall(args) => {
+                self.write("?.(");
+                self.list(args, Self::element);
+                self.write(")");
+            },
+        }
+    }
+
+    fn quasi(&mut self, quasi: &QuasiExpr) {
+        self.write("`");
+        for (index, string) in quasi.strings.iter().enumerate() {
+            self.write(&template_chunk(string));
+            if let Some(expr) = quasi.exprs.get(index) {
+                self.write("${");
+                self.expression(expr);
+                self.write("}");
+            }
+        }
+        self.write("`");
+    }
+
+    fn data_literal(&mut self, data: &DataLiteral) {
+        match data {
+            DataLiteral::Null => self.write("null"),
+            DataLiteral::False => self.write("false"),
+            DataLiteral::True => self.write("true"),
+            DataLiteral::Integer(int) => self.write(&int.to_string()),
+            DataLiteral::Decimal(int, fraction) => self.write(&decimal_literal(*int, *fraction)),
+            // only JSON lexes floats, in the shortest exponent form
+            DataLiteral::Float(float) => self.write(&format!("{:e}", float)),
+            DataLiteral::String(string) => self.write(&string_literal(string)),
+            DataLiteral::Undefined => self.write("undefined"),
+            DataLiteral::Bigint(sign, limbs) => self.write(&bigint_literal(*sign, limbs)),
+        }
+    }
+
+    fn record(&mut self, record: &Record) {
+        if record.0.is_empty() {
+            return self.write("{}")
+        }
+
+        // records with methods or comments are printed one property per line
+        let multiline = record.1.iter().any(|comments| !comments.is_empty()) || record.0.iter().any(|prop| match prop {
+            PropDef::Getter(_) | PropDef::Setter(_) | PropDef::MethodDef(_) => true,
+            PropDef::Computed(_, value) => is_method(value),
+            _ => false,
+        });
+
+        if !multiline {
+            self.write("{ ");
+            self.list(&record.0, Self::prop_def);
+            return self.write(" }")
+        }
+
+        self.write("{");
+        self.depth += 1;
+        for (index, prop) in record.0.iter().enumerate() {
+            // the comments of a method are on its function
+            let mut comments = match prop {
+                PropDef::Getter(func) | PropDef::Setter(func) | PropDef::MethodDef(func) => func.comments.to_vec(),
+                PropDef::Computed(_, value @ Expr::Function(func)) if is_method(value) => func.comments.to_vec(),
+                _ => vec![],
+            };
+            comments.extend(record.1.get(index).into_iter().flat_map(|comments| comments.iter().cloned()));
+            self.newline();
+            self.leading_comments(&comments, true);
+            self.prop_def(prop);
+            if index + 1 < record.0.len() {
+                self.write(",");
+            }
+            self.trailing_comments(&comments, true);
+        }
+        for comment in record.1.get(record.0.len()).map_or(&[][..], |comments| comments) {
+            self.newline();
+            self.comment(comment);
+        }
+        self.depth -= 1;
+        self.newline();
+        self.write("}");
+    }
+
+    fn prop_def(&mut self, prop: &PropDef) {
+        match prop {
+            PropDef::KeyValue(field, value) => {
+                self.write(&property_key(&field.name));
+                self.write(": ");
+                self.expression(value);
+            },
+            PropDef::Shorthand(_, var) => self.write(&var.name),
+            PropDef::Spread(expr) => {
+                self.write("...");
+                self.expression(expr);
+            },
+            PropDef::Getter(func) => {
+                self.write("get ");
+                self.method(func);
+            },
+            PropDef::Setter(func) => {
+                self.write("set ");
+                self.method(func);
+            },
+            PropDef::MethodDef(func) => self.method(func),
+            PropDef::Computed(key, value) => {
+                self.write("[");
+                self.expression(key);
+                self.write("]");
+                match value {
+                    // [key]: function () {} is parsed the same way
+                    Expr::Function(func) if is_method(value) => self.function_tail(func),
+                    value => {
+                        self.write(": ");
+                        self.expression(value);
+                    },
+                }
+            },
+        }
+    }
+
+    // name(params) { body }
+    fn method(&mut self, func: &Function) {
+        if let FunctionName::Named(name) = &func.name {
+            self.write(&property_key(name));
+        }
+        self.function_tail(func);
+    }
+
+    // in an expression, with the comments inline
+    fn function(&mut self, func: &Function) {
+        self.leading_comments(&func.comments, false);
+        self.function_uncommented(func);
+        self.trailing_comments(&func.comments, false);
+    }
+
+    fn function_uncommented(&mut self, func: &Function) {
+        match &func.name {
+            FunctionName::Arrow => {
+                self.signature(func);
+                self.write(" => ");
+                match &func.body {
+                    // concise bodies are parsed into a block with a single return statement
+                    ExprOrBlock::Block(block) => match &*block.statements {
+                        [Statement::Return(expr)] if !starts_with_brace(expr) => self.expression(expr),
+                        _ => self.block(block),
+                    },
+                    ExprOrBlock::Expr(expr) if starts_with_brace(expr) => {
+                        self.write("(");
+                        self.expression(expr);
+                        self.write(")");
+                    },
+                    ExprOrBlock::Expr(expr) => self.expression(expr),
+                }
+            },
+            FunctionName::Anonymous => {
+                self.write("function ");
+                self.function_tail(func);
+            },
+            FunctionName::Named(name) => {
+                self.write("function ");
+                self.write(name);
+                self.function_tail(func);
+            },
+        }
+    }
+
+    // (params) { body }
+    fn function_tail(&mut self, func: &Function) {
+        self.signature(func);
+        self.write(" ");
+        match &func.body {
+            ExprOrBlock::Block(block) => self.block(block),
+            ExprOrBlock::Expr(expr) => {
+                let statement = Statement::Return(Box::new(expr.clone()));
+                self.block(&Block::from(&[statement]));
+            },
+        }
+    }
+
+    // <T>(params): type
+    fn signature(&mut self, func: &Function) {
+        let Some(signature) = &func.signature else {
+            return self.parameters(&func.parameters)
+        };
+        self.type_params(&signature.type_params);
+        self.write("(");
+        for (index, param) in func.parameters.iter().enumerate() {
+            if index > 0 {
+                self.write(", ");
+            }
+            self.typed_param(param, signature.parameter(index));
+        }
+        self.write(")");
+        self.type_ann_suffix(signature.return_type.as_ref());
+    }
+
+    fn parameters(&mut self, parameters: &[Pattern]) {
+        self.write("(");
+        self.list(parameters, Self::pattern);
+        self.write(")");
+    }
+
+    // the annotation goes between the pattern and the default value
+    fn typed_param(&mut self, param: &Pattern, ty: Option<&TypeAnn>) {
+        match param {
+            Pattern::Optional(optional) if ty.is_some() => {
+                let OptionalPattern(_, left, default) = &**optional;
+                match left {
+                    LValueOptional::Variable(var) => self.write(&var.name),
+                    LValueOptional::ArrayPattern(array) => self.array_pattern(array),
+                    LValueOptional::RecordPattern(record) => self.record_pattern(record),
+                }
+                self.type_ann_suffix(ty);
+                self.write(" = ");
+                self.expression(default);
+            },
+            param => {
+                self.pattern(param);
+                self.type_ann_suffix(ty);
+            },
+        }
+    }
+
+    ///////////////////////
+    // Types
+
+    fn type_declaration(&mut self, decl: &TypeDeclaration) {
+        match decl {
+            TypeDeclaration::Alias(alias) => {
+                self.write("type ");
+                self.write(&alias.name);
+                self.type_params(&alias.type_params);
+                self.write(" = ");
+                self.type_ann(&alias.ty);
+                self.write(";");
+            },
+            TypeDeclaration::Interface(interface) => {
+                self.write("interface ");
+                self.write(&interface.name);
+                self.type_params(&interface.type_params);
+                if !interface.extends.is_empty() {
+                    self.write(" extends ");
+                    self.list(&interface.extends, Self::named_type);
+                }
+                self.write(" ");
+                self.record_type(&interface.members);
+            },
+        }
+    }
+
+    fn type_params(&mut self, params: &[Rc<str>]) {
+        if params.is_empty() {
+            return
+        }
+        self.write("<");
+        self.list(params, |printer, param| printer.write(param));
+        self.write(">");
+    }
+
+    fn type_ann_suffix(&mut self, ty: Option<&TypeAnn>) {
+        if let Some(ty) = ty {
+            self.write(": ");
+            self.type_ann(ty);
+        }
+    }
+
+    pub fn type_ann(&mut self, ty: &TypeAnn) {
+        match ty {
+            TypeAnn::Any => self.write("any"),
+            TypeAnn::Unknown => self.write("unknown"),
+            TypeAnn::Never => self.write("never"),
+            TypeAnn::Void => self.write("void"),
+            TypeAnn::Undefined => self.write("undefined"),
+            TypeAnn::Null => self.write("null"),
+            TypeAnn::Boolean => self.write("boolean"),
+            TypeAnn::Number => self.write("number"),
+            TypeAnn::String => self.write("string"),
+            TypeAnn::Bigint => self.write("bigint"),
+            TypeAnn::Literal(data) => self.data_literal(data),
+            TypeAnn::Named(named) => self.named_type(named),
+            TypeAnn::Array(element) => {
+                self.type_operand(element, 2);
+                self.write("[]");
+            },
+            TypeAnn::Tuple(elements) => {
+                self.write("[");
+                self.list(elements, Self::type_ann);
+                self.write("]");
+            },
+            TypeAnn::Record(props) => self.record_type(props),
+            TypeAnn::Function(func) => {
+                self.write("(");
+                self.list(&func.parameters, |printer, param| {
+                    if param.is_rest {
+                        printer.write("...");
+                    }
+                    printer.write(&param.name);
+                    printer.write(": ");
+                    printer.type_ann(&param.ty);
+                });
+                self.write(") => ");
+                self.type_ann(&func.return_type);
+            },
+            TypeAnn::Union(types) => {
+                for (index, ty) in types.iter().enumerate() {
+                    if index > 0 {
+                        self.write(" | ");
+                    }
+                    self.type_operand(ty, 0);
+                }
+            },
+            TypeAnn::Intersection(types) => {
+                for (index, ty) in types.iter().enumerate() {
+                    if index > 0 {
+                        self.write(" & ");
+                    }
+                    self.type_operand(ty, 1);
+                }
+            },
+        }
+    }
+
+    // parenthesizes the function and union types, and intersection types in an array(2) rather than a union(0) or an
+    // intersection(1)
+    fn type_operand(&mut self, ty: &TypeAnn, level: u8) {
+        let parenthesized = match ty {
+            TypeAnn::Function(_) => true,
+            TypeAnn::Union(_) => true,
+            TypeAnn::Intersection(_) => level >= 1,
+            _ => false,
+        };
+        if parenthesized {
+            self.write("(");
+            self.type_ann(ty);
+            self.write(")");
+        } else {
+            self.type_ann(ty);
+        }
+    }
+
+    fn named_type(&mut self, named: &NamedType) {
+        self.write(&named.name);
+        if !named.args.is_empty() {
+            self.write("<");
+            self.list(&named.args, Self::type_ann);
+            self.write(">");
+        }
+    }
+
+    fn record_type(&mut self, props: &[PropType]) {
+        if props.is_empty() {
+            return self.write("{}")
+        }
+        self.write("{ ");
+        for prop in props.iter() {
+            self.write(&property_key(&prop.name));
+            if prop.optional {
+                self.write("?");
+            }
+            self.write(": ");
+            self.type_ann(&prop.ty);
+            self.write("; ");
+        }
+        self.write("}");
+    }
+
+    ///////////////////////
+    // Patterns
+
+    pub fn pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Rest(pattern) => {
+                self.write("...");
+                self.pattern(pattern);
+            },
+            Pattern::Optional(optional) => {
+                let OptionalPattern(_, left, default) = &**optional;
+                match left {
+                    LValueOptional::Variable(var) => self.write(&var.name),
+                    LValueOptional::ArrayPattern(array) => self.array_pattern(array),
+                    LValueOptional::RecordPattern(record) => self.record_pattern(record),
+                }
+                self.write(" = ");
+                self.expression(default);
+            },
+            Pattern::ArrayPattern(array) => self.array_pattern(array),
+            Pattern::RecordPattern(record) => self.record_pattern(record),
+            Pattern::Variable(var) => self.write(&var.name),
+        }
+    }
+
+    fn array_pattern(&mut self, array: &ArrayPattern) {
+        self.write("[");
+        self.list(&array.0, Self::pattern);
+        self.write("]");
+    }
+
+    fn record_pattern(&mut self, record: &RecordPattern) {
+        if record.0.is_empty() {
+            return self.write("{}")
+        }
+        self.write("{ ");
+        self.list(&record.0, Self::prop_param);
+        self.write(" }");
+    }
+
+    fn prop_param(&mut self, prop: &PropParam) {
+        match prop {
+            // shorthand with a default value
+            PropParam::KeyValue(field, Pattern::Optional(optional)) if matches!(&optional.1, LValueOptional::Variable(var) if var.name == field.name) => {
+                self.pattern(&Pattern::Optional(optional.clone()))
+            },
+            // keys of record patterns are identifiers only
+            PropParam::KeyValue(field, pattern) => {
+                self.write(&field.name);
+                self.write(": ");
+                self.pattern(pattern);
+            },
+            PropParam::Shorthand(_, var) => self.write(&var.name),
+            PropParam::Rest(var) => {
+                self.write("...");
+                self.write(&var.name);
+            },
+            PropParam::Computed(key, pattern) => {
+                self.write("[");
+                self.expression(key);
+                self.write("]: ");
+                self.pattern(pattern);
+            },
+        }
+    }
+}
+
+pub fn print_script(script: &Script) -> String {
+    let mut printer = Printer::new();
+    printer.script(script);
+    printer.finish()
+}
+
+pub fn print_module(module: &Module) -> String {
+    let mut printer = Printer::new();
+    printer.module(module);
+    printer.finish()
+}
+
+pub fn print_expression(expr: &Expr) -> String {
+    let mut printer = Printer::new();
+    printer.expression(expr);
+    printer.finish()
+}
+
+pub fn precedence(expr: &Expr) -> Precedence {
+    match expr {
+        Expr::Assignment(_) => Precedence::Assignment,
+        Expr::Function(func) if func.name == FunctionName::Arrow => Precedence::Assignment,
+        Expr::CondExpr(_) => Precedence::Conditional,
+        Expr::BinaryExpr(binary) => binary_precedence(&binary.0),
+        Expr::UnaryExpr(_) => Precedence::Unary,
+        // negative literals are printed with a sign, they only come from the ASTs built by hand
+        Expr::DataLiteral(literal) => match &literal.data {
+            DataLiteral::Integer(int) if *int < 0 => Precedence::Unary,
+            DataLiteral::Decimal(int, _) if *int < 0 => Precedence::Unary,
+            DataLiteral::Bigint(true, limbs) if limbs.iter().any(|limb| *limb != 0) => Precedence::Unary,
+            _ => Precedence::Call,
+        },
+        _ => Precedence::Call,
+    }
+}
+
+pub fn binary_precedence(op: &BinaryOp) -> Precedence {
+    match op {
+        BinaryOp::Or | BinaryOp::Coalesce => Precedence::OrElse,
+        BinaryOp::And => Precedence::AndThen,
+        BinaryOp::BitOr | BinaryOp::BitXor | BinaryOp::BitAnd |
+        BinaryOp::StrictEqual | BinaryOp::StrictNotEqual |
+        BinaryOp::LessThan | BinaryOp::LessThanEqual | BinaryOp::GreaterThan | BinaryOp::GreaterThanEqual => Precedence::Eager,
+        BinaryOp::BitLeftShift | BinaryOp::BitRightShift | BinaryOp::BitUnsignedRightShift => Precedence::Shift,
+        BinaryOp::Add | BinaryOp::Sub => Precedence::Additive,
+        BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => Precedence::Multiplicative,
+        BinaryOp::Pow => Precedence::Power,
+    }
+}
+
+// right operands of the left associative operators bind one level tighter
+pub fn next_precedence(level: Precedence) -> Precedence {
+    match level {
+        Precedence::Assignment => Precedence::Conditional,
+        Precedence::Conditional => Precedence::OrElse,
+        Precedence::OrElse => Precedence::AndThen,
+        Precedence::AndThen => Precedence::Eager,
+        Precedence::Eager => Precedence::Shift,
+        Precedence::Shift => Precedence::Additive,
+        Precedence::Additive => Precedence::Multiplicative,
+        Precedence::Multiplicative => Precedence::Power,
+        Precedence::Power => Precedence::Unary,
+        Precedence::Unary | Precedence::Call => Precedence::Call,
+    }
+}
+
+// whether the printed expression starts with {, which would be read as a block
+pub fn starts_with_brace(expr: &Expr) -> bool {
+    match expr {
+        Expr::Record(_) => true,
+        Expr::BinaryExpr(binary) => precedence(&binary.1) >= binary_precedence(&binary.0) && starts_with_brace(&binary.1),
+        Expr::CondExpr(cond) => precedence(&cond.0) >= Precedence::OrElse && starts_with_brace(&cond.0),
+        Expr::CallExpr(call) => starts_with_brace(&call.expr),
+        Expr::Assignment(assignment) => match &assignment.1 {
+            LValue::CallLValue(call) => starts_with_brace(&call.expr),
+            LValue::Variable(_) => false,
+        },
+        _ => false,
+    }
+}
+
+// anonymous function values of computed properties are printed as methods
+fn is_method(expr: &Expr) -> bool {
+    matches!(expr, Expr::Function(func) if func.name == FunctionName::Anonymous)
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if is_id_start(c) => chars.all(is_id_continue),
+        _ => false,
+    }
+}
+
+// Property names are either identifiers or string literals.
+pub fn property_key(name: &str) -> String {
+    if is_identifier(name) && !RESERVED.contains(&name) {
+        name.to_string()
+    } else {
+        string_literal(name)
+    }
+}
+
+// Double quoted string literal, with the quotes, backslashes and control characters escaped.
+pub fn string_literal(string: &str) -> String {
+    let mut result = String::with_capacity(string.len() + 2);
+    result.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            c => push_escaped(&mut result, c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+// Cooked template chunk, with the backquotes, backslashes and substitution starts escaped.
+fn template_chunk(string: &str) -> String {
+    let mut result = String::with_capacity(string.len());
+    let mut chars = string.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '`' => result.push_str("\\`"),
+            '\\' => result.push_str("\\\\"),
+            '$' if chars.peek() == Some(&'{') => result.push_str("\\$"),
+            // line feeds are kept as they are, the template literals are multiline
+            '\n' => result.push('\n'),
+            c => push_escaped(&mut result, c),
+        }
+    }
+    result
+}
+
+fn push_escaped(result: &mut String, c: char) {
+    match c {
+        '\n' => result.push_str("\\n"),
+        '\r' => result.push_str("\\r"),
+        '\t' => result.push_str("\\t"),
+        '\u{8}' => result.push_str("\\b"),
+        '\u{C}' => result.push_str("\\f"),
+        c if c < ' ' || c == '\u{7F}' => result.push_str(&format!("\\u{:04X}", c as u32)),
+        c => result.push(c),
+    }
+}
+
+// integer + fraction / 10^19, printed as a plain decimal without exponent
+fn decimal_literal(int: i64, fraction: u64) -> String {
+    const SCALE: i128 = 10_000_000_000_000_000_000;
+    let value = int as i128 * SCALE + fraction as i128;
+    let (sign, value) = if value < 0 { ("-", -value) } else { ("", value) };
+    let fraction = format!("{:019}", value % SCALE);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        format!("{}{}", sign, value / SCALE)
+    } else {
+        format!("{}{}.{}", sign, value / SCALE, fraction)
+    }
+}
+
+// little endian u64 limbs, printed in decimal
+fn bigint_literal(sign: bool, limbs: &[u64]) -> String {
+    const CHUNK: u128 = 10_000_000_000_000_000_000;
+    let mut limbs = limbs.to_vec();
+    let mut chunks = vec![];
+    while limbs.iter().any(|limb| *limb != 0) {
+        // divide by 10^19 from the most significant limb, the remainder is the next chunk of digits
+        let mut remainder: u128 = 0;
+        for limb in limbs.iter_mut().rev() {
+            let value = (remainder << 64) | *limb as u128;
+            *limb = (value / CHUNK) as u64;
+            remainder = value % CHUNK;
+        }
+        chunks.push(remainder as u64);
+    }
+
+    let mut digits = match chunks.pop() {
+        Some(first) => first.to_string(),
+        None => return "0n".to_string(),
+    };
+    for chunk in chunks.iter().rev() {
+        digits.push_str(&format!("{:019}", chunk));
+    }
+    format!("{}{}n", if sign { "-" } else { "" }, digits)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{lexer::lex_jessie, script, JessieParserState};
+
+    use super::*;
+
+    fn parse(code: &str) -> Script {
+        let tokenstream = lex_jessie(code).unwrap_or_else(|err| panic!("{}\n{}", err, code));
+        let mut state = JessieParserState::new(tokenstream);
+        script(&mut state).unwrap_or_else(|err| panic!("{:?}\n{}", err, code))
+    }
+
+    #[test]
+    fn test_printer() {
+        let cases = [
+            ("x = a ? b : c ? d : e;", "x = a ? b : c ? d : e;\n"),
+            ("f(2 ** -x, - -y, typeof typeof z);", "f(2 ** -x, - -y, typeof typeof z);\n"),
+            ("const o = {'a-b': 1, \"if\": 2, c, ...d};", "const o = { \"a-b\": 1, \"if\": 2, c, ...d };\n"),
+            ("const s = '\\'\"\\n\\u0001';", "const s = \"'\\\"\\n\\u0001\";\n"),
+            ("const t = tag`a${x}\\`\\${b}`;", "const t = tag`a${x}\\`\\${b}`;\n"),
+            ("const n = [0x10n, .5, 1e-3, 1_000];", "const n = [16n, 0.5, 0.001, 1000];\n"),
+            ("const o = {get: 1, set};", "const o = { get: 1, set };\n"),
+            ("const f = (x) => { return {x}; };", "const f = (x) => {\n    return { x };\n};\n"),
+            ("if (a) { b(); } else if (c) {} else { d(); }", "if (a) {\n    b();\n} else if (c) {} else {\n    d();\n}\n"),
+        ];
+        for (code, expected) in cases {
+            let printed = print_script(&parse(code));
+            assert_eq!(printed, expected);
+            assert_eq!(parse(&printed), parse(code), "{}", code);
+        }
+
+        let mut printer = Printer::new().with_indent("\t");
+        printer.script(&parse("while (x) { if (y) { break; } }"));
+        assert_eq!(printer.finish(), "while (x) {\n\tif (y) {\n\t\tbreak;\n\t}\n}\n");
+
+        // parentheses are inserted for the trees built without ParenedExpr
+        let var = |name: &str| Expr::Variable(Box::new(Variable::new(name.into())));
+        let binary = |op, left, right| Expr::BinaryExpr(Box::new(BinaryExpr(op, left, right)));
+        let expr = binary(BinaryOp::Mul, binary(BinaryOp::Add, var("a"), var("b")), binary(BinaryOp::Sub, var("c"), binary(BinaryOp::Sub, var("d"), var("e"))));
+        assert_eq!(print_expression(&expr), "(a + b) * (c - (d - e))");
+        let expr = binary(BinaryOp::Pow, binary(BinaryOp::Pow, var("a"), var("b")), Expr::UnaryExpr(Box::new(UnaryExpr { op: Box::new([UnaryOp::Neg]), expr: DataLiteral::Integer(-1).into(), span: Span::default() })));
+        assert_eq!(print_expression(&expr), "(a ** b) ** -(-1)");
+    }
+}
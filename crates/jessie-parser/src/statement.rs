@@ -2,7 +2,7 @@ use core::panic;
 use std::{rc::Rc, cell::RefCell};
 
 use jessie_ast::*;
-use crate::{jessie_parser::{JessieParserState, repeated_elements}, parser, Token, pattern::binding_pattern, expression, common::{use_variable, identifier}, function::function_internal};
+use crate::{jessie_parser::{JessieParserState, repeated_elements}, parser, Token, pattern::{binding_pattern, pattern}, expression, common::{use_variable, identifier, optional_type_ann}, function::function_internal, types::{is_type_declaration, type_declaration}};
 
 type ParserState = JessieParserState; 
 type ParserError = parser::ParserError<Option<Token>>;
@@ -18,13 +18,12 @@ pub fn statement(state: &mut ParserState) -> Result<Statement, ParserError> {
         Some(Token::Const) => const_decl(state).map(|x| Statement::LocalDeclaration(Box::new(x))),
         Some(Token::Let) => let_decl(state).map(|x| Statement::LocalDeclaration(Box::new(x))),
         Some(Token::Function) => function_decl(state).map(|decl| Statement::LocalDeclaration(Box::new(decl))),
+        Some(Token::Interface) | Some(Token::Identifier(_)) if is_type_declaration(state) => type_declaration(state).map(|x| Statement::TypeDeclaration(Box::new(x))),
         Some(Token::If) => if_statement(state).map(|x| Statement::IfStatement(Box::new(x))),
         Some(Token::While) => while_statement(state).map(|x| Statement::WhileStatement(Box::new(x))),
-        Some(Token::Try) => {
-            unimplemented!("try statement")
-            //state.proceed();
-            //try_statement(state).map(Statement::TryStatement)
-        },
+        Some(Token::For) => for_of_statement(state).map(|x| Statement::ForOfStatement(Box::new(x))),
+        Some(Token::Switch) => switch_statement(state).map(|x| Statement::SwitchStatement(Box::new(x))),
+        Some(Token::Try) => try_statement(state).map(|x| Statement::TryStatement(Box::new(x))),
         Some(Token::Throw) => {
             state.proceed();
             let res = expression(state).map(|x| Statement::Throw(Box::new(x)));
@@ -60,8 +59,18 @@ pub fn statement(state: &mut ParserState) -> Result<Statement, ParserError> {
     }
 }
 
+// statementItem, or Statement::Error in recovery mode
+pub fn statement_item(state: &mut ParserState) -> Result<Statement, ParserError> {
+    let start = state.position();
+    let depth = state.scope.len();
+    match statement(state) {
+        Err(err) => state.recover(err, start, depth).map(|_| Statement::Error),
+        ok => ok,
+    }
+}
+
 pub fn const_decl(state: &mut ParserState) -> Result<Declaration, ParserError> {
-    let bindings = repeated_elements(state, Some(Token::Const), Token::Semicolon, &binding, false)?.into_iter().map(|(pattern, value)| VariableDeclaration{pattern, value}).collect();
+    let bindings = repeated_elements(state, Some(Token::Const), Token::Semicolon, &binding, false)?.into_iter().map(|(pattern, type_ann, value)| VariableDeclaration{pattern, type_ann, value}).collect();
 
     //state.scope.declare_const(bindings).ok_or(ParserError::DuplicateDeclaration)
     let decl = Declaration::Const(bindings);
@@ -70,7 +79,7 @@ pub fn const_decl(state: &mut ParserState) -> Result<Declaration, ParserError> {
 }
 
 fn let_decl(state: &mut ParserState) -> Result<Declaration, ParserError> {
-    let bindings = repeated_elements(state, Some(Token::Let), Token::Semicolon, &binding, false)?.into_iter().map(|(pattern, value)| VariableDeclaration{pattern, value}).collect();
+    let bindings = repeated_elements(state, Some(Token::Let), Token::Semicolon, &binding, false)?.into_iter().map(|(pattern, type_ann, value)| VariableDeclaration{pattern, type_ann, value}).collect();
 
     //state.scope.declare_let(bindings).ok_or(ParserError::DuplicateDeclaration)
     let decl = Declaration::Let(bindings);
@@ -79,12 +88,13 @@ fn let_decl(state: &mut ParserState) -> Result<Declaration, ParserError> {
 }
 
 pub fn function_decl(state: &mut ParserState) -> Result<Declaration, ParserError> {
+    let start = state.position();
     state.consume_1(Token::Function)?;
     let name = identifier(state)?;
     
     //let parent_scope = state.scope.enter_block();
     // TODO: support recursive reference to function
-    let function = function_internal(state, FunctionName::Named(name))?;
+    let function = function_internal(state, FunctionName::Named(name), start)?;
     //state.scope.exit_block();
     //let decl = state.scope.declare_function(function).ok_or(ParserError::DuplicateDeclaration)?;
     let decl = Declaration::Function(Rc::new(RefCell::new(function)));
@@ -92,47 +102,56 @@ pub fn function_decl(state: &mut ParserState) -> Result<Declaration, ParserError
     Ok(decl)
 }
 
-pub fn binding(state: &mut ParserState) -> Result<(Pattern, Option<Expr>), ParserError> {
+pub fn binding(state: &mut ParserState) -> Result<(Pattern, Option<TypeAnn>, Option<Expr>), ParserError> {
     match state.lookahead_1() {
         Some(Token::LeftBrace) | Some(Token::LeftBracket) => {
             let pattern = binding_pattern(state)?;
+            let type_ann = optional_type_ann(state)?;
             state.consume_1(Token::Equal)?;
             let expr = expression(state)?;
-            Ok((pattern, Some(expr)))
+            Ok((pattern, type_ann, Some(expr)))
         },
         _ => {
             let var = use_variable(state)?;
+            let type_ann = optional_type_ann(state)?;
             let expr = if state.try_proceed(Token::Equal) {
                 Some(expression(state)?)
             } else {
                 None
             };
-            Ok((Pattern::Variable(Box::new(var)), expr))
+            Ok((Pattern::Variable(Box::new(var)), type_ann, expr))
         }
     }
 }
 
 pub fn block(state: &mut ParserState) -> Result<Block, ParserError> {
     state.enter_block();
-    let statements = block_raw(state)?;
+    let (statements, spans, comments) = block_raw(state)?;
     let declarations = state.exit_block();
 
     // Unbound uses list is only needed for function declarations, so we can ignore it here.
 
-    Ok(Block{declarations, statements})
+    Ok(Block::with_spans(declarations, statements, spans).with_comments(comments))
 }
 
-pub fn block_raw(state: &mut ParserState) -> Result<Box<[Statement]>, ParserError> {
+// returns the statements with their spans and comments
+pub fn block_raw(state: &mut ParserState) -> Result<(Box<[Statement]>, Box<[Span]>, Box<[Comments]>), ParserError> {
     state.consume_1(Token::LeftBrace)?;
 
     let mut statements = vec![];
-    while state.lookahead_1() != Some(Token::RightBrace) {
-        statements.push(statement(state)?);
+    let mut spans = vec![];
+    let mut comments = vec![];
+    while !matches!(state.lookahead_1(), Some(Token::RightBrace) | Some(Token::EOF) | None) {
+        let start = state.position();
+        statements.push(statement_item(state)?);
+        spans.push(state.span_from(start));
+        comments.push(state.take_comments(start));
     }
 
+    let comments = state.closing_comments(comments);
     state.consume_1(Token::RightBrace)?;
 
-    Ok(statements.into_boxed_slice())
+    Ok((statements.into_boxed_slice(), spans.into_boxed_slice(), comments))
 }
 
 fn if_statement(state: &mut ParserState) -> Result<IfStatement, ParserError> {
@@ -143,7 +162,7 @@ fn if_statement(state: &mut ParserState) -> Result<IfStatement, ParserError> {
     let consequent = block(state)?;
 
     let alternate = if state.try_proceed(Token::Else) {
-        if state.try_proceed(Token::If) {
+        if state.lookahead_1() == Some(&Token::If) {
             ElseArm::ElseIf(if_statement(state).map(Box::new)?)
         } else {
             ElseArm::Else(block(state)?)
@@ -165,4 +184,163 @@ pub fn while_statement(state: &mut ParserState) -> Result<WhileStatement, Parser
     Ok(WhileStatement { condition, body })
 }
 
+pub fn for_of_statement(state: &mut ParserState) -> Result<ForOfStatement, ParserError> {
+    state.consume_1(Token::For)?;
+    state.consume_1(Token::LeftParen)?;
+
+    let is_const = match state.lookahead_1() {
+        Some(Token::Const) => state.proceed_then(true)?,
+        Some(Token::Let) => state.proceed_then(false)?,
+        la => return state.err_expected("const or let in for-of head", la),
+    };
+
+    // The binding is declared in the body block scope, so every iteration gets a fresh one.
+    state.enter_block();
+    let pattern = pattern(state)?;
+
+    // "of" is a contextual keyword, lexed as an identifier
+    match state.lookahead_1() {
+        Some(Token::Identifier(of)) if &**of == "of" => state.proceed(),
+        la => return state.err_expected("of", la),
+    }
+
+    let iterable = expression(state)?;
+    state.consume_1(Token::RightParen)?;
+
+    let binding = Box::new([VariableDeclaration{pattern: pattern.clone(), type_ann: None, value: None}]);
+    let decl = if is_const { Declaration::Const(binding) } else { Declaration::Let(binding) };
+    state.scope.last_mut().unwrap().push(decl);
+
+    let (statements, spans, comments) = block_raw(state)?;
+    let declarations = state.exit_block();
+
+    Ok(ForOfStatement { is_const, pattern, iterable, body: Block::with_spans(declarations, statements, spans).with_comments(comments) })
+}
+
+pub fn switch_statement(state: &mut ParserState) -> Result<SwitchStatement, ParserError> {
+    state.consume_1(Token::Switch)?;
+    state.consume_1(Token::LeftParen)?;
+    let discriminant = expression(state)?;
+    state.consume_1(Token::RightParen)?;
+
+    state.consume_1(Token::LeftBrace)?;
+    let mut clauses = vec![];
+    let mut has_default = false;
+    while state.lookahead_1() != Some(&Token::RightBrace) {
+        clauses.push(switch_clause(state, &mut has_default)?);
+    }
+    state.consume_1(Token::RightBrace)?;
+
+    Ok(SwitchStatement { discriminant, clauses: clauses.into_boxed_slice() })
+}
+
+// switchClause ::= caseLabel+ statementItem* terminator
+// A braced clause body is parsed as a block statement, its last statement is checked instead.
+fn switch_clause(state: &mut ParserState, has_default: &mut bool) -> Result<SwitchClause, ParserError> {
+    let mut labels = vec![case_label(state, has_default)?];
+    while let Some(Token::Case) | Some(Token::Default) = state.lookahead_1() {
+        labels.push(case_label(state, has_default)?);
+    }
+
+    state.enter_block();
+    let mut statements = vec![];
+    let mut spans = vec![];
+    while !matches!(state.lookahead_1(), Some(Token::Case) | Some(Token::Default) | Some(Token::RightBrace) | Some(Token::EOF) | None) {
+        let start = state.position();
+        statements.push(statement_item(state)?);
+        spans.push(state.span_from(start));
+    }
+    let declarations = state.exit_block();
+    let body = Block::with_spans(declarations, statements.into_boxed_slice(), spans.into_boxed_slice());
+
+    // Jessie does not allow fall-through, every clause must end with a terminator
+    if !ends_with_terminator(&body.statements) {
+        return state.err_invalid("switch clause must end with break, continue, return or throw")
+    }
 
+    Ok(SwitchClause { labels: labels.into_boxed_slice(), body })
+}
+
+fn ends_with_terminator(statements: &[Statement]) -> bool {
+    match statements.last() {
+        Some(Statement::Break) | Some(Statement::Continue) | Some(Statement::Return(_)) | Some(Statement::ReturnEmpty) | Some(Statement::Throw(_)) => true,
+        Some(Statement::Block(block)) => ends_with_terminator(&block.statements),
+        _ => false,
+    }
+}
+
+// has_default is set by the first default label of the switch statement, and rejects the following ones
+fn case_label(state: &mut ParserState, has_default: &mut bool) -> Result<CaseLabel, ParserError> {
+    let label = match state.lookahead_1() {
+        Some(Token::Case) => {
+            state.proceed();
+            CaseLabel::Case(expression(state)?)
+        },
+        Some(Token::Default) if *has_default => return state.err_invalid("switch statement must not have more than one default clause"),
+        Some(Token::Default) => {
+            *has_default = true;
+            state.proceed_then(CaseLabel::Default)?
+        },
+        la => return state.err_expected("case or default", la),
+    };
+    state.consume_1(Token::Colon)?;
+    Ok(label)
+}
+
+pub fn try_statement(state: &mut ParserState) -> Result<TryStatement, ParserError> {
+    state.consume_1(Token::Try)?;
+    let try_block = block(state)?;
+
+    let catch = if state.lookahead_1() == Some(&Token::Catch) {
+        Some(catch_clause(state)?)
+    } else {
+        None
+    };
+
+    let finally = if state.try_proceed(Token::Finally) {
+        Some(block(state)?)
+    } else {
+        None
+    };
+
+    if catch.is_none() && finally.is_none() {
+        let la = state.lookahead_1();
+        return state.err_expected("catch or finally", la)
+    }
+
+    Ok(TryStatement { block: try_block, catch, finally })
+}
+
+fn catch_clause(state: &mut ParserState) -> Result<CatchClause, ParserError> {
+    state.consume_1(Token::Catch)?;
+    state.consume_1(Token::LeftParen)?;
+
+    // The catch parameter lives in the same block scope as the catch body,
+    // declared as a let binding without initializer. The interpreter initializes it with the thrown value.
+    state.enter_block();
+    let param = pattern(state)?;
+    state.consume_1(Token::RightParen)?;
+    state.scope.last_mut().unwrap().push(Declaration::Let(Box::new([VariableDeclaration{pattern: param.clone(), type_ann: None, value: None}])));
+
+    let (statements, spans, comments) = block_raw(state)?;
+    let declarations = state.exit_block();
+
+    Ok(CatchClause { param, body: Block::with_spans(declarations, statements, spans).with_comments(comments) })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{lexer::lex_jessie, script};
+
+    use super::*;
+
+    #[test]
+    fn test_switch_fallthrough() {
+        // clauses without a terminator, and more than one default clause, are rejected at parse time
+        for code in ["switch (1) { case 1: 1; case 2: break; }", "(() => { switch (1) { default: return 1; default: return 2; } })();", "switch (1) { case 1: default: break; case 2: default: break; }"] {
+            let tokenstream = lex_jessie(code.to_string()).unwrap();
+            let mut state = JessieParserState::new(tokenstream);
+            assert!(script(&mut state).is_err(), "{}", code);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use std::rc::Rc;
+
+use jessie_ast::*;
+use crate::{jessie_parser::{JessieParserState, GrammarLevel, repeated_elements}, Token, parser, common::identifier, expression::prop_name};
+
+type ParserState = JessieParserState;
+type ParserError = parser::ParserError<Option<Token>>;
+
+///////////////////////
+// Tessie types
+//
+// type ::= "|"? intersection ("|" intersection)*
+// intersection ::= postfix ("&" postfix)*
+// postfix ::= primary ("[" "]")*
+// primary ::= keyword | literal | name typeArgs? | "(" type ")" | "(" paramTypes ")" "=>" type
+//           | "[" types "]" | "{" (propType (";" | ","))* "}"
+
+pub fn type_ann(state: &mut ParserState) -> Result<TypeAnn, ParserError> {
+    state.try_proceed(Token::Bar);
+    let mut types = vec![intersection_type(state)?];
+    while state.try_proceed(Token::Bar) {
+        types.push(intersection_type(state)?);
+    }
+    if types.len() == 1 {
+        return Ok(types.pop().unwrap())
+    }
+    Ok(TypeAnn::Union(types.into_boxed_slice()))
+}
+
+fn intersection_type(state: &mut ParserState) -> Result<TypeAnn, ParserError> {
+    let mut types = vec![postfix_type(state)?];
+    while state.try_proceed(Token::Ampersand) {
+        types.push(postfix_type(state)?);
+    }
+    if types.len() == 1 {
+        return Ok(types.pop().unwrap())
+    }
+    Ok(TypeAnn::Intersection(types.into_boxed_slice()))
+}
+
+fn postfix_type(state: &mut ParserState) -> Result<TypeAnn, ParserError> {
+    let mut ty = primary_type(state)?;
+    while state.lookahead_1() == Some(&Token::LeftBracket) && state.lookahead_2() == Some(&Token::RightBracket) {
+        state.proceed();
+        state.proceed();
+        ty = TypeAnn::Array(Box::new(ty));
+    }
+    Ok(ty)
+}
+
+fn primary_type(state: &mut ParserState) -> Result<TypeAnn, ParserError> {
+    match state.lookahead_1() {
+        Some(Token::Void) => state.proceed_then(TypeAnn::Void),
+        Some(Token::Undefined) => state.proceed_then(TypeAnn::Undefined),
+        Some(Token::Null) => state.proceed_then(TypeAnn::Null),
+        Some(Token::True) => state.proceed_then(TypeAnn::Literal(Box::new(DataLiteral::True))),
+        Some(Token::False) => state.proceed_then(TypeAnn::Literal(Box::new(DataLiteral::False))),
+        Some(Token::String(s)) => {
+            let literal = DataLiteral::String(s.clone());
+            state.proceed_then(TypeAnn::Literal(Box::new(literal)))
+        },
+        Some(Token::Integer(n)) => {
+            let literal = DataLiteral::Integer(*n);
+            state.proceed_then(TypeAnn::Literal(Box::new(literal)))
+        },
+        Some(Token::Decimal(i, f)) => {
+            let literal = DataLiteral::Decimal(*i, *f);
+            state.proceed_then(TypeAnn::Literal(Box::new(literal)))
+        },
+        Some(Token::LeftParen) => {
+            state.proceed();
+            let ty = type_ann(state)?;
+            state.consume_1(Token::RightParen)?;
+            Ok(ty)
+        },
+        Some(Token::ArrowLeftParen) => {
+            let parameters = param_types(state, Token::ArrowLeftParen, Token::ArrowRightParen)?;
+            state.consume_1(Token::FatArrow)?;
+            let return_type = type_ann(state)?;
+            Ok(TypeAnn::Function(Box::new(FunctionType { parameters, return_type })))
+        },
+        Some(Token::LeftBracket) => {
+            let elements = repeated_elements(state, Some(Token::LeftBracket), Token::RightBracket, &type_ann, true)?;
+            Ok(TypeAnn::Tuple(elements.into_boxed_slice()))
+        },
+        Some(Token::LeftBrace) => record_type(state).map(TypeAnn::Record),
+        Some(Token::Identifier(name)) => {
+            let keyword = match &**name {
+                "any" => Some(TypeAnn::Any),
+                "unknown" => Some(TypeAnn::Unknown),
+                "never" => Some(TypeAnn::Never),
+                "boolean" => Some(TypeAnn::Boolean),
+                "number" => Some(TypeAnn::Number),
+                "string" => Some(TypeAnn::String),
+                "bigint" => Some(TypeAnn::Bigint),
+                _ => None,
+            };
+            match keyword {
+                Some(ty) => state.proceed_then(ty),
+                None => named_type(state).map(|named| TypeAnn::Named(Box::new(named))),
+            }
+        },
+        la => state.err_expected("type", la),
+    }
+}
+
+fn named_type(state: &mut ParserState) -> Result<NamedType, ParserError> {
+    let name = identifier(state)?;
+    let args = if state.lookahead_1() == Some(&Token::LAngle) {
+        type_args(state)?
+    } else {
+        Box::new([])
+    };
+    Ok(NamedType { name, args })
+}
+
+// "<" type ("," type)* ">"
+fn type_args(state: &mut ParserState) -> Result<Box<[TypeAnn]>, ParserError> {
+    state.consume_1(Token::LAngle)?;
+    let mut args = vec![type_ann(state)?];
+    while state.try_proceed(Token::Comma) {
+        args.push(type_ann(state)?);
+    }
+    close_angle(state)?;
+    Ok(args.into_boxed_slice())
+}
+
+// The lexer does not know about type arguments, and reads the closing angles of Array<Array<T>> as a shift operator.
+fn close_angle(state: &mut ParserState) -> Result<(), ParserError> {
+    state.split_angle();
+    state.consume_1(Token::RAngle)
+}
+
+// ("<" name ("," name)* ">")?, empty if absent
+pub fn type_params(state: &mut ParserState) -> Result<Box<[Rc<str>]>, ParserError> {
+    if !state.try_proceed(Token::LAngle) {
+        return Ok(Box::new([]))
+    }
+    let mut params = vec![identifier(state)?];
+    while state.try_proceed(Token::Comma) {
+        params.push(identifier(state)?);
+    }
+    close_angle(state)?;
+    Ok(params.into_boxed_slice())
+}
+
+fn param_types(state: &mut ParserState, open: Token, close: Token) -> Result<Box<[ParamType]>, ParserError> {
+    let parameters = repeated_elements(state, Some(open), close, &param_type, true)?;
+    if parameters.iter().rev().skip(1).any(|param| param.is_rest) {
+        return state.err_invalid("rest parameter must be last")
+    }
+    Ok(parameters.into_boxed_slice())
+}
+
+fn param_type(state: &mut ParserState) -> Result<ParamType, ParserError> {
+    let is_rest = state.try_proceed(Token::DotDotDot);
+    let name = identifier(state)?;
+    state.consume_1(Token::Colon)?;
+    let ty = type_ann(state)?;
+    Ok(ParamType { name, is_rest, ty })
+}
+
+// "{" (propType (";" | ","))* "}", the last separator is optional
+fn record_type(state: &mut ParserState) -> Result<Box<[PropType]>, ParserError> {
+    state.consume_1(Token::LeftBrace)?;
+    let mut props = Vec::new();
+    while !state.try_proceed(Token::RightBrace) {
+        props.push(prop_type(state)?);
+        if !state.try_proceed(Token::Semicolon) && !state.try_proceed(Token::Comma) {
+            state.consume_1(Token::RightBrace)?;
+            break
+        }
+    }
+    Ok(props.into_boxed_slice())
+}
+
+// name "?"? ":" type, or a method name "(" paramTypes ")" ":" type
+fn prop_type(state: &mut ParserState) -> Result<PropType, ParserError> {
+    let name = prop_name(state)?.name;
+    let optional = state.try_proceed(Token::Question);
+    if matches!(state.lookahead_1(), Some(Token::LeftParen | Token::ArrowLeftParen)) {
+        let close = if state.lookahead_1() == Some(&Token::LeftParen) { Token::RightParen } else { Token::ArrowRightParen };
+        let open = state.lookahead_1().cloned().unwrap();
+        let parameters = param_types(state, open, close)?;
+        state.consume_1(Token::Colon)?;
+        let return_type = type_ann(state)?;
+        return Ok(PropType { name, optional, ty: TypeAnn::Function(Box::new(FunctionType { parameters, return_type })) })
+    }
+    state.consume_1(Token::Colon)?;
+    let ty = type_ann(state)?;
+    Ok(PropType { name, optional, ty })
+}
+
+// `type` is not a reserved word, it starts a declaration only when followed by a name
+pub fn is_type_declaration(state: &ParserState) -> bool {
+    match (state.lookahead_1(), state.lookahead_2()) {
+        (Some(Token::Interface), _) => true,
+        (Some(Token::Identifier(keyword)), Some(Token::Identifier(_))) => state.level >= GrammarLevel::Tessie && &**keyword == "type",
+        _ => false,
+    }
+}
+
+// "type" name typeParams? "=" type ";"
+// "interface" name typeParams? ("extends" namedType ("," namedType)*)? "{" propTypes "}"
+pub fn type_declaration(state: &mut ParserState) -> Result<TypeDeclaration, ParserError> {
+    if state.try_proceed(Token::Interface) {
+        state.require_level(GrammarLevel::Tessie, "interface declaration")?;
+        let name = identifier(state)?;
+        let type_params = type_params(state)?;
+        let mut extends = Vec::new();
+        if state.try_proceed(Token::Extends) {
+            extends.push(named_type(state)?);
+            while state.try_proceed(Token::Comma) {
+                extends.push(named_type(state)?);
+            }
+        }
+        let members = record_type(state)?;
+        return Ok(TypeDeclaration::Interface(Box::new(Interface { name, type_params, extends: extends.into_boxed_slice(), members })))
+    }
+
+    state.require_level(GrammarLevel::Tessie, "type alias")?;
+    identifier(state)?; // type
+    let name = identifier(state)?;
+    let type_params = type_params(state)?;
+    state.consume_1(Token::Equal)?;
+    let ty = type_ann(state)?;
+    state.consume_1(Token::Semicolon)?;
+    Ok(TypeDeclaration::Alias(Box::new(TypeAlias { name, type_params, ty })))
+}
@@ -0,0 +1,41 @@
+use std::fmt::Display;
+
+use jessie_ast::Span;
+
+// Scoping error, located at the variable or statement that caused it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopeError {
+    pub message: &'static str,
+    pub span: Span,
+}
+
+impl ScopeError {
+    pub fn new(message: &'static str, span: Span) -> Self {
+        ScopeError { message, span }
+    }
+
+    // attach the span if the error is not located yet
+    pub fn or_span(self, span: Span) -> Self {
+        if self.span.is_unknown() {
+            ScopeError { span, ..self }
+        } else {
+            self
+        }
+    }
+}
+
+impl From<&'static str> for ScopeError {
+    fn from(message: &'static str) -> Self {
+        ScopeError::new(message, Span::default())
+    }
+}
+
+impl Display for ScopeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.span.is_unknown() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{} at {}:{}", self.message, self.span.line, self.span.column)
+        }
+    }
+}
@@ -1,15 +1,13 @@
 #![feature(box_patterns)]
 
-pub mod statement;
-pub mod expression;
 pub mod scope;
 pub mod function;
 pub mod state;
+pub mod error;
 
 mod scope_test;
 
-pub use statement::*;
-pub use expression::*;
 pub use scope::*;
 pub use function::*;
-pub use state::*;
\ No newline at end of file
+pub use state::*;
+pub use error::*;
\ No newline at end of file
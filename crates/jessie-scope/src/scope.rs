@@ -1,53 +1,133 @@
 use std::{rc::Rc, cell::RefCell};
 
-use jessie_ast::{Block, ExprOrBlock, Function, LValueOptional, OptionalPattern, Pattern, PropParam, Script, Variable, VariableDeclaration};
+use jessie_ast::{Block, Declaration, Expr, ExprOrBlock, Function, LValueOptional, Pattern, PropParam, Script, Statement, Variable, Module, ModuleItem, VisitMut};
 
-use crate::{state::ScopeState, scope_statement, scope_expression};
+use crate::{error::ScopeError, state::ScopeState};
 
-pub fn scope_script<T: Clone>(state: &mut ScopeState<T>, script: &mut Script) -> Result<(), &'static str> {
-    state.enter_script()?;
-    state.enter_block(&mut script.statements)?; 
-    for stmt in script.statements.statements.iter_mut() {
-        scope_statement(state, stmt)?;
+// Scoping resolves every variable use to its declaration, walking the AST with VisitMut.
+// Declarations are made on entering the enclosing block or function, before any of its statements are scoped.
+impl<T: Clone> VisitMut for ScopeState<T> {
+    type Error = ScopeError;
+
+    fn visit_script(&mut self, script: &mut Script) -> Result<(), ScopeError> {
+        self.enter_script()?;
+        self.visit_block(&mut script.statements)?;
+        self.exit_script()?;
+        Ok(())
+    }
+
+    fn visit_module(&mut self, module: &mut Module) -> Result<(), ScopeError> {
+        self.enter_script()?;
+
+        // imports and module level declarations are all visible from the whole module
+        for item in module.body.iter_mut() {
+            match item {
+                ModuleItem::ImportDeclaration(import) => self.declare_import(import)?,
+                ModuleItem::ModuleDeclaration(decl) => self.declare_declaration(&mut decl.declaration)?,
+                ModuleItem::TypeDeclaration(..) | ModuleItem::Error(_) => {},
+            }
+        }
+
+        for item in module.body.iter_mut() {
+            if let ModuleItem::ModuleDeclaration(decl) = item {
+                self.visit_declaration(&mut decl.declaration)?;
+            }
+        }
+
+        module.scope = Some(Box::new(self.exit_module()?));
+        Ok(())
+    }
+
+    fn visit_block(&mut self, block: &mut Block) -> Result<(), ScopeError> {
+        self.enter_block(block)?;
+        let spans = &block.spans;
+        for (i, stmt) in block.statements.iter_mut().enumerate() {
+            self.visit_statement(stmt).map_err(|err| err.or_span(spans.get(i).cloned().unwrap_or_default()))?;
+        }
+        self.exit_block();
+        Ok(())
+    }
+
+    fn visit_function(&mut self, func: &mut Function) -> Result<(), ScopeError> {
+        self.enter_function(func)?;
+
+        match &mut func.body {
+            ExprOrBlock::Expr(expr) => self.visit_expr(expr),
+            ExprOrBlock::Block(block) => self.visit_block(block),
+        }?;
+
+        let scope = self.exit_function();
+
+        func.scope = Some(Box::new(scope));
+
+        Ok(())
+    }
+
+    fn visit_variable(&mut self, var: &mut Variable) -> Result<(), ScopeError> {
+        self.use_variable(var)
+    }
+
+    // Only the default values and computed keys in a pattern are scoped, the variables are declared by ScopeState::declare_pattern
+
+    fn visit_pattern(&mut self, pattern: &mut Pattern) -> Result<(), ScopeError> {
+        match pattern {
+            Pattern::Variable(_) => Ok(()),
+            pattern => self.walk_pattern(pattern),
+        }
+    }
+
+    fn visit_lvalue_optional(&mut self, lvalue: &mut LValueOptional) -> Result<(), ScopeError> {
+        match lvalue {
+            LValueOptional::Variable(_) => Ok(()),
+            lvalue => self.walk_lvalue_optional(lvalue),
+        }
+    }
+
+    fn visit_prop_param(&mut self, prop: &mut PropParam) -> Result<(), ScopeError> {
+        match prop {
+            PropParam::Shorthand(_, _) | PropParam::Rest(_) => Ok(()),
+            prop => self.walk_prop_param(prop),
+        }
     }
-    //let declarations = state.exit_script();
-    state.exit_block();
-    state.exit_script()?;
-    Ok(())
 }
 
+pub fn scope_script<T: Clone>(state: &mut ScopeState<T>, script: &mut Script) -> Result<(), ScopeError> {
+    state.visit_script(script)
+}
 
-pub fn scope_variable<T: Clone>(state: &mut ScopeState<T>, var: &mut Variable) -> Result<(), &'static str> {
-    state.use_variable(var)
+pub fn scope_module<T: Clone>(state: &mut ScopeState<T>, module: &mut Module) -> Result<(), ScopeError> {
+    state.visit_module(module)
 }
 
-pub fn scope_function_declaration<T: Clone>(state: &mut ScopeState<T>, func: Rc<RefCell<Function>>) -> Result<(), &'static str> {
-    state.declare_function(func.clone())?;
-    scope_function(state, &mut*func.borrow_mut())
+pub fn scope_statement<T: Clone>(state: &mut ScopeState<T>, statement: &mut Statement) -> Result<(), ScopeError> {
+    state.visit_statement(statement)
 }
 
-pub fn scope_function<T: Clone>(state: &mut ScopeState<T>, func: &mut Function) -> Result<(), &'static str> {
-    state.enter_function(func)?;
+pub fn scope_declaration<T: Clone>(state: &mut ScopeState<T>, decl: &mut Declaration) -> Result<(), ScopeError> {
+    state.visit_declaration(decl)
+}
 
-    match &mut func.body {
-        ExprOrBlock::Expr(expr) => scope_expression(state, expr),
-        ExprOrBlock::Block(block) => scope_block(state, block),
-    }?;
+pub fn scope_expression<T: Clone>(state: &mut ScopeState<T>, expr: &mut Expr) -> Result<(), ScopeError> {
+    state.visit_expr(expr)
+}
 
-    let scope = state.exit_function();
+pub fn scope_pattern<T: Clone>(state: &mut ScopeState<T>, pattern: &mut Pattern) -> Result<(), ScopeError> {
+    state.visit_pattern(pattern)
+}
 
-    func.scope = Some(Box::new(scope));
+pub fn scope_variable<T: Clone>(state: &mut ScopeState<T>, var: &mut Variable) -> Result<(), ScopeError> {
+    state.use_variable(var)
+}
 
-    Ok(())
+pub fn scope_function_declaration<T: Clone>(state: &mut ScopeState<T>, func: Rc<RefCell<Function>>) -> Result<(), ScopeError> {
+    state.declare_function(func.clone())?;
+    state.visit_function(&mut*func.borrow_mut())
 }
 
+pub fn scope_function<T: Clone>(state: &mut ScopeState<T>, func: &mut Function) -> Result<(), ScopeError> {
+    state.visit_function(func)
+}
 
-pub fn scope_block<T: Clone>(state: &mut ScopeState<T>, block: &mut Block) -> Result<(), &'static str> {
-    state.enter_block(block);
-    println!("enter block {:?} /// {:?}", block.statements, block.declarations);
-    for stmt in block.statements.iter_mut() {
-        scope_statement(state, stmt)?;
-    }
-    state.exit_block();
-    Ok(())
-}
\ No newline at end of file
+pub fn scope_block<T: Clone>(state: &mut ScopeState<T>, block: &mut Block) -> Result<(), ScopeError> {
+    state.visit_block(block)
+}
@@ -1,9 +1,9 @@
 use std::{cell::RefCell, rc::Rc, thread::{current, Scope}};
 
-use jessie_ast::{Block, Declaration, Function, LValueOptional, LocalVariable, Module, OptionalPattern, Pattern, PropParam, Variable, VariableIndex};
+use jessie_ast::{ArrayPattern, Block, Declaration, Function, ImportDeclaration, LValueOptional, LocalVariable, Module, OptionalPattern, Pattern, PropParam, RecordPattern, Variable, VariableIndex};
 use utils::{Map, MapPool};
 
-use crate::scope_expression;
+use crate::{scope_pattern, error::ScopeError};
 
 //type VariableMapPool = MapPool<Variable>;
 type VariableMap = Map<Variable>;
@@ -89,7 +89,15 @@ impl<T: Clone> ScopeState<T> {
         self.module_scope.function_scopes.last_mut()
     }
 
-    pub fn use_variable(&mut self, var: &mut Variable) -> Result<(), &'static str> {
+    // The variable is replaced with the declared one, keeping the span of the use site.
+    pub fn use_variable(&mut self, var: &mut Variable) -> Result<(), ScopeError> {
+        let span = var.span;
+        let result = self.resolve_variable(var);
+        var.span = span;
+        result.map_err(|message| ScopeError::new(message, span))
+    }
+
+    fn resolve_variable(&mut self, var: &mut Variable) -> Result<(), &'static str> {
         // Recursively search for the variable, in reverse order, for each function scope
         // If the function scope has the variable, the variable is replaced with the declared variable.
         // If not, we replace the variable with a capture variable, which points to the (not yet initialized) parent variable.
@@ -160,60 +168,79 @@ impl<T: Clone> ScopeState<T> {
         }
     }
 
-    pub fn declare_function(&mut self, func: Rc<RefCell<Function>>) -> Result<(), &'static str> {
-        self.current_function().unwrap().declare_function(func)
+    pub fn declare_function(&mut self, func: Rc<RefCell<Function>>) -> Result<(), ScopeError> {
+        let span = func.borrow().span;
+        self.current_function().unwrap().declare_function(func).map_err(|message| ScopeError::new(message, span))
     }
     
-    pub fn declare_let_variable(&mut self, var: &mut Variable) -> Result<(), &'static str> {
+    pub fn declare_let_variable(&mut self, var: &mut Variable) -> Result<(), ScopeError> {
         let current_function = self.current_function().unwrap();
         
-        current_function.declare_variable(var, VariableIndex::Local(false, current_function.locals.len() as u32))
+        current_function.declare_variable(var, VariableIndex::Local(false, current_function.locals.len() as u32)).map_err(|message| ScopeError::new(message, var.span))
     }
 
-    pub fn declare_const_variable(&mut self, var: &mut Variable) -> Result<(), &'static str> {
+    pub fn declare_const_variable(&mut self, var: &mut Variable) -> Result<(), ScopeError> {
         let current_function = self.current_function().unwrap();
         
-        current_function.declare_variable(var, VariableIndex::Local(true, current_function.locals.len() as u32))
+        current_function.declare_variable(var, VariableIndex::Local(true, current_function.locals.len() as u32)).map_err(|message| ScopeError::new(message, var.span))
     }
 
-    pub fn declare_parameter(&mut self, var: &mut Variable) -> Result<(), &'static str> {
+    pub fn declare_parameter(&mut self, var: &mut Variable) -> Result<(), ScopeError> {
         let current_function = self.current_function().unwrap();
 
-        current_function.declare_variable(var, VariableIndex::Parameter(current_function.parameters.len() as u32))?;
+        current_function.declare_variable(var, VariableIndex::Parameter(current_function.parameters.len() as u32)).map_err(|message| ScopeError::new(message, var.span))?;
 
         current_function.parameters.push(LocalVariable::new(var.clone()));
 
         Ok(())
     }
 
-    pub fn declare_pattern(&mut self, pattern: &mut Pattern, f: &mut impl FnMut(&mut Self, &mut Variable) -> Result<(), &'static str>) -> Result<(), &'static str> {
+    // argument slot of a destructured parameter, not visible by name
+    fn declare_unnamed_parameter(&mut self) {
+        let current_function = self.current_function().unwrap();
+        let index = VariableIndex::Parameter(current_function.parameters.len() as u32);
+        current_function.parameters.push(LocalVariable::new(Variable::declared("".into(), index)));
+    }
+
+    // declares every leaf variable of the pattern with f.
+    // The expressions in the pattern are scoped separately by scope_pattern, as declarations are cloned into the block.
+    pub fn declare_pattern(&mut self, pattern: &mut Pattern, f: &mut impl FnMut(&mut Self, &mut Variable) -> Result<(), ScopeError>) -> Result<(), ScopeError> {
         match pattern {
             Pattern::Variable(var) => f(self, var.as_mut()),
-            Pattern::ArrayPattern(arr) => arr.0.iter_mut().try_for_each(|pat| self.declare_pattern(pat, f)),
-            Pattern::RecordPattern(rec) => rec.0.iter_mut().try_for_each(|prop| {
-                match prop {
-                    PropParam::KeyValue(_, value) => self.declare_pattern(value, f),
-                    PropParam::Shorthand(key, var) => f(self, var),
-                    PropParam::Rest(var) => f(self, var)
-                }
-            }),
+            Pattern::ArrayPattern(arr) => self.declare_array_pattern(arr, f),
+            Pattern::RecordPattern(rec) => self.declare_record_pattern(rec, f),
             Pattern::Optional(pat) => {
-                match pat.as_mut() {
-                    OptionalPattern(_, LValueOptional::Variable(left), right) => {
-                        f(self, left.as_mut())?;
-                        scope_expression(self, right)
-                    }
+                let OptionalPattern(_, left, _) = pat.as_mut();
+                match left {
+                    LValueOptional::Variable(var) => f(self, var.as_mut()),
+                    LValueOptional::ArrayPattern(arr) => self.declare_array_pattern(arr, f),
+                    LValueOptional::RecordPattern(rec) => self.declare_record_pattern(rec, f),
                 }
             }
             Pattern::Rest(pattern) => self.declare_pattern(pattern, f),
         }
     }
 
+    fn declare_array_pattern(&mut self, arr: &mut ArrayPattern, f: &mut impl FnMut(&mut Self, &mut Variable) -> Result<(), ScopeError>) -> Result<(), ScopeError> {
+        arr.0.iter_mut().try_for_each(|pat| self.declare_pattern(pat, f))
+    }
+
+    fn declare_record_pattern(&mut self, rec: &mut RecordPattern, f: &mut impl FnMut(&mut Self, &mut Variable) -> Result<(), ScopeError>) -> Result<(), ScopeError> {
+        rec.0.iter_mut().try_for_each(|prop| {
+            match prop {
+                PropParam::KeyValue(_, value) => self.declare_pattern(value, f),
+                PropParam::Shorthand(_, var) => f(self, var),
+                PropParam::Rest(var) => f(self, var),
+                PropParam::Computed(_, value) => self.declare_pattern(value, f),
+            }
+        })
+    }
+
     // using function as a wrapper for the script
     // hacky, TODO fix
-    pub fn enter_script(&mut self) -> Result<(), &'static str> {
+    pub fn enter_script(&mut self) -> Result<(), ScopeError> {
         if self.module_scope.function_scopes.len() > 0 {
-            return Err("Script already entered");
+            return Err("Script already entered".into());
         }
 
         self.module_scope.function_scopes.push(FunctionScope{
@@ -228,20 +255,36 @@ impl<T: Clone> ScopeState<T> {
         Ok(())
     }
 
-    pub fn exit_script(&mut self) -> Result<Box<[LocalVariable]>, &'static str> {
+    // module body is scoped as a script, but the scope is kept for the interpreter to allocate the module frame
+    pub fn exit_module(&mut self) -> Result<jessie_ast::FunctionScope, ScopeError> {
+        let scope = self.module_scope.function_scopes.pop().unwrap();
+
+        if scope.captures.len() > 0 {
+            return Err("module should not have captures, some variables are not scoped and not in the builtins".into());
+        }
+
+        Ok(jessie_ast::FunctionScope{
+            parameters: scope.parameters.into(),
+            locals: scope.locals.into(),
+            captures: scope.captures.into(),
+            functions: scope.functions.into(),
+        })
+    }
+
+    pub fn exit_script(&mut self) -> Result<Box<[LocalVariable]>, ScopeError> {
         let scope = self.module_scope.function_scopes.pop().unwrap(); 
 
         if scope.parameters.len() > 0 {
             panic!("script should not have parameters");
         }
         if scope.captures.len() > 0 {
-            return Err("script should not have captures, some variables are not scoped and not in the builtins");
+            return Err("script should not have captures, some variables are not scoped and not in the builtins".into());
         }
 
         Ok(scope.locals.into()) // should we also return the functions? for hoisting? TODO
     }
 
-    pub fn enter_function(&mut self, func: &mut Function) -> Result<(), &'static str>{
+    pub fn enter_function(&mut self, func: &mut Function) -> Result<(), ScopeError>{
         self.module_scope.function_scopes.push(FunctionScope{
             parameters: Vec::with_capacity(func.parameters.len()),
             locals: Vec::new(),
@@ -251,8 +294,21 @@ impl<T: Clone> ScopeState<T> {
             block_scopes: vec![BlockScope{declared_variables: VariableMap::default()}],
         });
 
+        // destructured parameters take an unnamed parameter slot for the argument,
+        // and their variables are declared as locals, initialized on function entry
+        for param in func.parameters.iter_mut() {
+            match param {
+                Pattern::Variable(var) => self.declare_parameter(var)?,
+                _ => {
+                    self.declare_unnamed_parameter();
+                    self.declare_pattern(param, &mut Self::declare_let_variable)?;
+                }
+            }
+        }
+
+        // default values can refer to the preceding parameters
         for param in func.parameters.iter_mut() {
-            self.declare_pattern(param, &mut Self::declare_parameter)?;
+            scope_pattern(self, param)?;
         }
 
         Ok(())
@@ -269,28 +325,43 @@ impl<T: Clone> ScopeState<T> {
         }
     }
 
-    pub fn enter_block(&mut self, block: &mut Block) -> Result<(), &'static str> {
+    pub fn enter_block(&mut self, block: &mut Block) -> Result<(), ScopeError> {
         let mut declared_variables = VariableMap::with_capacity_and_hasher(block.statements.len(), Default::default());
         self.current_function().unwrap().block_scopes.push(BlockScope{
             declared_variables
         });
 
         for decl in block.declarations.iter_mut() {
-            match decl {
-                Declaration::Const(decls) => {
-                    for decl in decls.iter_mut() {
-                        self.declare_pattern(&mut decl.pattern, &mut Self::declare_const_variable)?;
-                    }
-                }
-                Declaration::Let(decls) => {
-                    for decl in decls.iter_mut() {
-                        self.declare_pattern(&mut decl.pattern, &mut Self::declare_let_variable)?;
-                    }
+            self.declare_declaration(decl)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn declare_declaration(&mut self, decl: &mut Declaration) -> Result<(), ScopeError> {
+        match decl {
+            Declaration::Const(decls) => {
+                for decl in decls.iter_mut() {
+                    self.declare_pattern(&mut decl.pattern, &mut Self::declare_const_variable)?;
                 }
-                Declaration::Function(func) => {
-                    self.declare_function(func.clone())?;
+            }
+            Declaration::Let(decls) => {
+                for decl in decls.iter_mut() {
+                    self.declare_pattern(&mut decl.pattern, &mut Self::declare_let_variable)?;
                 }
             }
+            Declaration::Function(func) => {
+                self.declare_function(func.clone())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // imported names are const bindings in the module scope
+    pub fn declare_import(&mut self, import: &mut ImportDeclaration) -> Result<(), ScopeError> {
+        for var in import.import_clause.bindings_mut() {
+            self.declare_const_variable(var)?;
         }
 
         Ok(())
@@ -1,16 +1,16 @@
 use core::panic;
-use std::{borrow::Borrow, mem::{replace, self}, rc::Rc};
+use std::{cell::RefCell, mem::replace, rc::Rc};
 
-use jessie_ast::{Array, AssignOp, Assignment, BinaryExpr, BinaryOp, CallExpr, CallLValue, CallPostOp, CondExpr, DataLiteral, Expr, ExprOrBlock, Function, LValue, LValueCallPostOp, PropDef, Record, UnaryExpr, UnaryOp, Variable, VariableIndex};
+use jessie_ast::{Array, AssignOp, Assignment, BinaryExpr, BinaryOp, CallExpr, QuasiExpr, CallPostOp, CondExpr, DataLiteral, Expr, ExprOrBlock, Function, LValue, LValueCallPostOp, LocalVariable, Pattern, PropDef, Record, UnaryExpr, UnaryOp, Variable, VariableIndex};
 use kala_repr::{slot::Slot, object::Property, completion::Completion, function::Frame};
 
-use crate::{interpreter::Interpreter, operation::{strict_equal, strict_not_equal, less_than, less_than_or_equal, greater_than, greater_than_or_equal, add, sub, mul, div, modulo, pow}, statement::eval_block};
+use crate::{interpreter::Interpreter, operation::{strict_equal, strict_not_equal, less_than, less_than_or_equal, greater_than, greater_than_or_equal, add, sub, mul, div, modulo, pow, bit_and, bit_or, bit_xor, bit_left_shift, bit_right_shift, bit_unsigned_right_shift}, statement::{eval_block, initialize_pattern}};
 
 
 pub fn eval_expr(interpreter: &mut Interpreter, expr: &Expr) -> Completion {
     println!("eval_expr: {:?}", expr);
     match expr {
-        Expr::DataLiteral(lit) => eval_literal(lit),
+        Expr::DataLiteral(lit) => eval_literal(&lit.data),
         Expr::Array(array) => eval_array(interpreter, array),
         Expr::Record(obj) => eval_record(interpreter, obj),
         Expr::Function(func) => eval_function(interpreter, func),
@@ -19,6 +19,7 @@ pub fn eval_expr(interpreter: &mut Interpreter, expr: &Expr) -> Completion {
         Expr::BinaryExpr(binary) => eval_binary(interpreter, binary),
         Expr::UnaryExpr(unary) => eval_unary(interpreter, unary),
         Expr::CallExpr(call) => eval_call(interpreter, call),
+        Expr::QuasiExpr(quasi) => eval_quasi(interpreter, quasi),
         Expr::ParenedExpr(parened) => eval_expr(interpreter, &*parened),
         Expr::Variable(var) => eval_variable(interpreter, var.as_ref().clone()),
         Expr::Spread(spread) => unreachable!("Spread should be handled by eval_array"),
@@ -31,10 +32,15 @@ fn eval_literal(lit: &DataLiteral) -> Completion {
         DataLiteral::False => Completion::Value(Slot::new_false()),
         DataLiteral::True => Completion::Value(Slot::new_true()),
         DataLiteral::Integer(s) => Completion::Value(Slot::new_integer(*s)),
+        // decimal fraction to binary fraction
         DataLiteral::Decimal(i, f) => Completion::Value(Slot::new_number(*i, *f)),
+        DataLiteral::Float(f) => match Slot::new_float(*f) {
+            Some(number) => Completion::Value(number),
+            None => Completion::Throw(Slot::new_string("RangeError: the number literal is out of the range of a number")),
+        },
         DataLiteral::Undefined => Completion::Value(Slot::new_undefined()),
         DataLiteral::String(s) => Completion::Value(Slot::new_string(s.clone())),
-        DataLiteral::Bigint(sign, abs) => unimplemented!("bigint literal"),
+        DataLiteral::Bigint(sign, abs) => Completion::Value(Slot::new_bigint(*sign, abs)),
     }
 }
 
@@ -95,6 +101,21 @@ fn eval_record(interpreter: &mut Interpreter, obj: &Record) -> Completion {
                     ));
                 }
             }
+            PropDef::MethodDef(func) => {
+                props.push(Property::data(
+                    func.get_name().unwrap(),
+                    eval_function(interpreter, func)?,
+                ));
+            }
+            PropDef::Computed(key, value) => {
+                let key: Rc<str> = eval_expr(interpreter, key)?.to_string().into();
+                let value = eval_expr(interpreter, value)?;
+                // keys known only at runtime may repeat, the last one wins
+                match props.iter_mut().find(|prop| prop.key == key) {
+                    Some(prop) => *prop = Property::data(key, value),
+                    None => props.push(Property::data(key, value)),
+                }
+            }
             PropDef::Spread(spread) => unimplemented!("spread in record literal")
         }
     }
@@ -115,7 +136,7 @@ fn exit_function(interpreter: &mut Interpreter, arguments_len: usize, captures_l
 }
 */
 
-fn eval_function(interpreter: &mut Interpreter, func: &Function) -> Completion {
+pub(crate) fn eval_function(interpreter: &mut Interpreter, func: &Function) -> Completion {
 
     //let mut local_initializers: Vec<Option<Box<dyn FnOnce(&mut Frame) -> Completion>>> = Vec::with_capacity(func.locals.len());
     let builtins = interpreter.builtins.clone();
@@ -145,8 +166,17 @@ fn eval_function(interpreter: &mut Interpreter, func: &Function) -> Completion {
 
         let mut frame_value = std::mem::take(frame);
 
-        // push arguments
-        frame_value.slots.extend(arguments);
+        // one argument per parameter, the rest parameter takes the remaining arguments as an array
+        let mut arguments = arguments;
+        if let Some(rest_index) = func.parameters.iter().position(|param| matches!(param, Pattern::Rest(_))) {
+            let rest = if arguments.len() > rest_index { arguments.split_off(rest_index) } else { Vec::new() };
+            arguments.resize(rest_index, Slot::new_undefined());
+            arguments.push(Slot::new_array(rest));
+        }
+        arguments.resize(func.parameters.len().max(arguments.len()), Slot::new_undefined());
+
+        // push arguments, reversed so that get_argument(0) is the first argument
+        frame_value.slots.extend(arguments.into_iter().rev());
 
         println!("pushed arguments: {:?}", frame_value.slots);
 
@@ -160,35 +190,23 @@ fn eval_function(interpreter: &mut Interpreter, func: &Function) -> Completion {
             current_frame: frame_value, 
         };
 
-        // promote local variables to heap if escaping
-        for (index, local) in func.locals().iter().enumerate() {
-            if local.is_escaping {
-                let local_slot = function_interpreter.current_frame.get_local(index);
-                *local_slot = Slot::new_variable_slot();
-                println!("promoted local variable to heap: {:?}@{:?}", local_slot, index)
+        promote_escaping_locals(&mut function_interpreter, func.locals());
+
+        // destructure the parameters other than plain variables into their locals
+        for (index, param) in func.parameters.iter().enumerate() {
+            if let Pattern::Variable(_) = param {
+                continue
+            }
+            let argument = function_interpreter.current_frame.get_argument(index).clone();
+            if let Completion::Throw(error) = initialize_pattern(&mut function_interpreter, param, argument) {
+                let _ = replace(frame, function_interpreter.current_frame);
+                frame.exit_function_frame(recovery);
+                return Completion::Throw(error)
             }
         }
 
 
-        // hoist(pre-declare) function declarations
-        for (function_var, local_function) in func.functions().iter() {
-            //let function: &Function = &local_function.as_ref().try_borrow().unwrap();
-            // local variable promotion is done above, legacy, commented out for record
-            /* 
-            for local_function_capture in function.captures() {
-                if let VariableIndex::Local(_, index) = local_function_capture.index() {
-                    let captured_var = function_interpreter.current_frame.get_local(index as usize);
-
-                    // promote local variable to heap
-                    *captured_var = Slot::new_variable_slot();
-                    println!("promoted local variable to heap: {:?}@{:?}", captured_var, index)
-                }
-            }
-            */
-
-            let local_evaluated_function = eval_function(&mut function_interpreter, &local_function.as_ref().borrow())?;
-            *function_interpreter.current_frame.get_local(function_var.index().unwrap_local() as usize) = local_evaluated_function;
-        }
+        hoist_functions(&mut function_interpreter, func.functions())?;
 
         let result = match &func.body {
             ExprOrBlock::Block(block) => eval_block(&mut function_interpreter, block),
@@ -208,8 +226,43 @@ fn eval_function(interpreter: &mut Interpreter, func: &Function) -> Completion {
     Completion::Value(function)
 }
 
-fn assign(interpreter: &mut Interpreter, lhs: &LValue, rhs: Slot) -> Completion {
+// promote local variables to heap if escaping, so that the closures share them
+pub(crate) fn promote_escaping_locals(interpreter: &mut Interpreter, locals: &[LocalVariable]) {
+    for (index, local) in locals.iter().enumerate() {
+        if local.is_escaping {
+            *interpreter.current_frame.get_local(index) = Slot::new_variable_slot();
+        }
+    }
+}
+
+// hoist(pre-declare) function declarations, after the locals are promoted
+pub(crate) fn hoist_functions(interpreter: &mut Interpreter, functions: &[(Variable, Rc<RefCell<Function>>)]) -> Completion {
+    for (function_var, local_function) in functions.iter() {
+        let local_evaluated_function = eval_function(interpreter, &local_function.as_ref().borrow())?;
+        *interpreter.current_frame.get_local(function_var.index().unwrap_local() as usize) = local_evaluated_function;
+    }
+    Completion::Normal
+}
+
+// the reference is resolved once, the current value is read before evaluating the right hand side
+// logical assignments short-circuit, neither evaluating the right hand side nor assigning
+fn assign(interpreter: &mut Interpreter, op: &AssignOp, lhs: &LValue, rhs: &Expr) -> Completion {
     if let LValue::Variable(var) = lhs {
+        let rhs = match op {
+            AssignOp::Assign => eval_expr(interpreter, rhs)?,
+            AssignOp::AssignOr | AssignOp::AssignAnd | AssignOp::AssignCoalesce => {
+                let current = eval_variable(interpreter, (**var).clone())?;
+                if !logical_assigns(op, &current) {
+                    return Completion::Value(current)
+                }
+                eval_expr(interpreter, rhs)?
+            },
+            _ => {
+                let current = eval_variable(interpreter, (**var).clone())?;
+                let rhs = eval_expr(interpreter, rhs)?;
+                compound_operation(op, &current, &rhs)?
+            }
+        };
         let lvalue = match var.index() {
             VariableIndex::Local(_, index) => interpreter.current_frame.get_local(index as usize),
             VariableIndex::Captured(index) => interpreter.current_frame.get_capture(index as usize),
@@ -217,93 +270,112 @@ fn assign(interpreter: &mut Interpreter, lhs: &LValue, rhs: Slot) -> Completion
             VariableIndex::Static(_) => todo!("static variable assignment"), 
         };
         lvalue.set(rhs);
-        return Completion::Value(lvalue.clone())
+        return Completion::Value(lvalue.get())
     }
 
 
 
     let LValue::CallLValue(lvalue) = lhs else { unreachable!("invalid lvalue") };
-    let mut obj = eval_expr(interpreter, &lvalue.expr)?;
 
-    // This whole part is ugly
-    // please please please refactor this
-    enum ElementOrProperty<'a> {
-        Element(&'a mut Slot),
-        Property(&'a mut Property),
+    // The object and the key are resolved once into owned values. The property is looked up again
+    // to assign it, as evaluating the right hand side may add properties and move the existing ones.
+    enum Key {
+        Index(usize),
+        Member(Rc<str>),
     }
 
+    fn read(interpreter: &mut Interpreter, object: &mut Slot, key: &Key) -> Completion {
+        match key {
+            Key::Index(index) => Completion::Value(object.get_element(*index).cloned()?),
+            Key::Member(name) => match object.get_property(name) {
+                Some(property) => property.get(&mut interpreter.current_frame),
+                // a property the object does not have yet, read as undefined and created by the assignment
+                None => Completion::Value(Slot::UNDEFINED),
+            },
+        }
+    }
 
-    let mut prop = match lvalue.post_ops[0] {
-        LValueCallPostOp::Index(ref index) => {
-            let index = eval_expr(interpreter, index)?;
-            ElementOrProperty::Element(
-                obj.get_element(index.unwrap_integer().unwrap().try_into().unwrap())?,
-            )
-        },
-        LValueCallPostOp::Member(ref member) => {
-            ElementOrProperty::Property(obj.get_property(member)?)
-        },
-    };   
-
-    let mut left = Slot::UNINITIALIZED;
-
-    for op in &lvalue.post_ops[1..] {
-        left = match prop {
-            ElementOrProperty::Element(slot) => slot.clone(),
-            ElementOrProperty::Property(prop) => prop.get(&mut interpreter.current_frame)?,
-        };
-
-        prop = match op {
-            LValueCallPostOp::Index(ref index) => {
+    let mut object = eval_expr(interpreter, &lvalue.expr)?;
+    let mut key = None;
+    for post_op in lvalue.post_ops.iter() {
+        if let Some(key) = key.take() {
+            object = read(interpreter, &mut object, &key)?;
+        }
+        key = Some(match post_op {
+            LValueCallPostOp::Index(index) => {
                 let index = eval_expr(interpreter, index)?;
-                ElementOrProperty::Element(
-                    left.get_element(index.unwrap_integer().unwrap().try_into().unwrap())?,
-                )
-            },
-            LValueCallPostOp::Member(ref member) => {
-                ElementOrProperty::Property(left.get_property(member)?)
+                Key::Index(index.unwrap_integer().unwrap().try_into().unwrap())
             },
+            LValueCallPostOp::Member(member) => Key::Member(member.clone()),
+        });
+    }
+    let key = key.expect("lvalue without post operations");
+
+    let rhs = match op {
+        AssignOp::Assign => eval_expr(interpreter, rhs)?,
+        AssignOp::AssignOr | AssignOp::AssignAnd | AssignOp::AssignCoalesce => {
+            let current = read(interpreter, &mut object, &key)?;
+            if !logical_assigns(op, &current) {
+                return Completion::Value(current)
+            }
+            eval_expr(interpreter, rhs)?
+        },
+        _ => {
+            let current = read(interpreter, &mut object, &key)?;
+            let rhs = eval_expr(interpreter, rhs)?;
+            compound_operation(op, &current, &rhs)?
         }
     };
 
-
-
-    match prop {
-        ElementOrProperty::Element(slot) => {
+    match key {
+        Key::Index(_) if object.is_frozen() => Completion::Throw(Slot::new_string("TypeError: cannot assign to an element of a frozen array")),
+        Key::Index(index) => {
+            let slot = object.get_element(index)?;
             slot.set(rhs);
             Completion::Value(slot.clone())
         },
-        ElementOrProperty::Property(prop) => {
-            prop.set(&mut interpreter.current_frame, rhs)
-        }
+        Key::Member(name) => match object.get_property(&name) {
+            Some(property) => property.set(&mut interpreter.current_frame, rhs),
+            None => match object.insert_property(&name, rhs.clone()) {
+                Some(()) => Completion::Value(rhs),
+                None => Completion::Throw(Slot::new_string("TypeError: cannot create a property on a non-object value")),
+            },
+        },
     }
 }
 
-fn eval_lvalue(interpreter: &mut Interpreter, lvalue: &LValue) -> Completion {
-    // we are "widening" the type of lvalue to be an expr. this is safe under the current AST design. TODO: make this more safe
-    let lhs_expr: &Expr = unsafe{mem::transmute(lvalue)};
-    eval_expr(interpreter, lhs_expr)
+fn compound_operation(op: &AssignOp, lhs: &Slot, rhs: &Slot) -> Completion {
+    match op {
+        AssignOp::Assign | AssignOp::AssignOr | AssignOp::AssignAnd | AssignOp::AssignCoalesce => unreachable!("not a compound assignment"),
+        AssignOp::AssignAdd => lhs.op_add(rhs),
+        AssignOp::AssignSub => lhs.op_sub(rhs),
+        AssignOp::AssignMul => lhs.op_mul(rhs),
+        AssignOp::AssignDiv => lhs.op_div(rhs),
+        AssignOp::AssignMod => lhs.op_modulo(rhs),
+        AssignOp::AssignExp => lhs.op_pow(rhs),
+        AssignOp::AssignLShift => Completion::Value(lhs.op_left_shift(rhs)),
+        AssignOp::AssignRShift => Completion::Value(lhs.op_right_shift(rhs)),
+        AssignOp::AssignURShift => Completion::Value(lhs.op_unsigned_right_shift(rhs)),
+        AssignOp::AssignBitAnd => Completion::Value(lhs.op_bit_and(rhs)),
+        AssignOp::AssignBitXor => Completion::Value(lhs.op_bit_xor(rhs)),
+        AssignOp::AssignBitOr => Completion::Value(lhs.op_bit_or(rhs)),
+    }
 }
 
-fn eval_assignment(interpreter: &mut Interpreter, assignment: &Assignment) -> Completion {
-    let rhs = eval_expr(interpreter, &assignment.2)?;
-
-    match assignment.0 {
-        AssignOp::Assign => assign(interpreter, &assignment.1, rhs),
-        AssignOp::AssignAdd => {
-            let lhs = eval_lvalue(interpreter, &assignment.1)?; 
-            let result = lhs.op_add(&rhs);
-            assign(interpreter, &assignment.1, result)
-        },
-        AssignOp::AssignSub => {
-            let lhs = eval_lvalue(interpreter, &assignment.1)?;
-            let result = lhs.op_sub(&rhs);
-            assign(interpreter, &assignment.1, result)
-        }
-        _ => unimplemented!("assignment with operator")
+// whether x op= y assigns, the same condition as evaluating y in x op y
+fn logical_assigns(op: &AssignOp, current: &Slot) -> bool {
+    match op {
+        AssignOp::AssignOr => !current.is_truthy(),
+        AssignOp::AssignAnd => current.is_truthy(),
+        AssignOp::AssignCoalesce => current.is_nullish(),
+        _ => unreachable!("not a logical assignment"),
     }
 }
 
+fn eval_assignment(interpreter: &mut Interpreter, assignment: &Assignment) -> Completion {
+    assign(interpreter, &assignment.0, &assignment.1, &assignment.2)
+}
+
 fn eval_cond(interpreter: &mut Interpreter, expr: &CondExpr) -> Completion {
     let cond = eval_expr(interpreter, &expr.0)?;
     if cond.is_truthy() {
@@ -340,9 +412,9 @@ fn eval_binary(interpreter: &mut Interpreter, expr: &BinaryExpr) -> Completion {
             }
         },
 
-        BinaryOp::BitAnd => unimplemented!("bitand"), // bit_and(interpreter, expr.1, expr.2),
-        BinaryOp::BitOr => unimplemented!("bitor"), // bit_or(interpreter, expr.1, expr.2),
-        BinaryOp::BitXor => unimplemented!("bitxor"), // bit_xor(interpreter, expr.1, expr.2),
+        BinaryOp::BitAnd => bit_and(interpreter, &expr.1, &expr.2),
+        BinaryOp::BitOr => bit_or(interpreter, &expr.1, &expr.2),
+        BinaryOp::BitXor => bit_xor(interpreter, &expr.1, &expr.2),
 
         BinaryOp::StrictEqual => strict_equal(interpreter, &expr.1, &expr.2),
         BinaryOp::StrictNotEqual => strict_not_equal(interpreter, &expr.1,&expr.2),
@@ -352,9 +424,9 @@ fn eval_binary(interpreter: &mut Interpreter, expr: &BinaryExpr) -> Completion {
         BinaryOp::GreaterThan => greater_than(interpreter, &expr.1, &expr.2),
         BinaryOp::GreaterThanEqual => greater_than_or_equal(interpreter, &expr.1, &expr.2),
 
-        BinaryOp::BitLeftShift => unimplemented!("leftshift"), // bit_left_shift(interpreter, expr.1, expr.2),
-        BinaryOp::BitRightShift => unimplemented!("rightshift"), // bit_right_shift(interpreter, expr.1, expr.2),
-        BinaryOp::BitUnsignedRightShift => unimplemented!("urightshift"), // bit_unsigned_right_shift(interpreter, expr.1, expr.2),
+        BinaryOp::BitLeftShift => bit_left_shift(interpreter, &expr.1, &expr.2),
+        BinaryOp::BitRightShift => bit_right_shift(interpreter, &expr.1, &expr.2),
+        BinaryOp::BitUnsignedRightShift => bit_unsigned_right_shift(interpreter, &expr.1, &expr.2),
 
         BinaryOp::Add => add(interpreter, &expr.1, &expr.2),
         BinaryOp::Sub => sub(interpreter, &expr.1, &expr.2),
@@ -366,22 +438,20 @@ fn eval_binary(interpreter: &mut Interpreter, expr: &BinaryExpr) -> Completion {
 }
 
 fn eval_unary(interpreter: &mut Interpreter, expr: &UnaryExpr) -> Completion {
-    let mut res = Slot::new_uninitialized();
-    for op in expr.op.iter() {
+    let mut res = eval_expr(interpreter, &expr.expr)?;
+    // the operators are in source order, the one closest to the operand applies first
+    for op in expr.op.iter().rev() {
         res = match op {
             UnaryOp::Not => {
-                let operand = eval_expr(interpreter, &expr.expr)?;
-                Completion::Value(Slot::new_boolean(!operand.is_truthy()))
+                Completion::Value(Slot::new_boolean(!res.is_truthy()))
             }
             UnaryOp::BitNot => {
                 unimplemented!("bitwise not")
             }
             UnaryOp::Neg => {
-                let operand = eval_expr(interpreter, &expr.expr)?;
-                Completion::Value(operand.op_neg())
+                Completion::Value(res.op_neg())
             }
             UnaryOp::Pos => {
-                let operand = eval_expr(interpreter, &expr.expr)?;
                 unimplemented!("pos")
             }
             UnaryOp::TypeOf => {
@@ -401,26 +471,68 @@ fn eval_call(interpreter: &mut Interpreter, expr: &CallExpr) -> Completion {
 
     let mut callee = eval_expr(interpreter, &expr.expr)?;
     for op in expr.post_ops.iter() {
+        if callee.is_nullish() {
+            // the whole chain short-circuits on the optional operators,
+            // the other property accesses throw
+            if op.is_optional() {
+                return Completion::Value(Slot::new_undefined())
+            }
+            if let CallPostOp::Index(_) | CallPostOp::Member(_) = op {
+                return Completion::Throw(Slot::new_string(format!("TypeError: cannot read properties of {}", callee.to_string())))
+            }
+        }
+
         match op {
-            CallPostOp::Index(index) => {
+            CallPostOp::Index(index) | CallPostOp::OptionalIndex(index) => {
                 let index_slot = eval_expr(interpreter, &index)?;
                 let index = index_slot.unwrap_integer().unwrap(); // TODO: non-smi array index
                 callee = callee.get_element(index.try_into().unwrap()).cloned()?;
             }
-            CallPostOp::Member(member) => {
+            CallPostOp::Member(member) | CallPostOp::OptionalMember(member) => {
                 println!("object: {:?}", callee);
                 println!("member: {:?}", member);
-                callee = callee.get_property(&member).unwrap().get(&mut interpreter.current_frame)?;
-
+                callee = match callee.get_property(&member) {
+                    Some(property) => property.get(&mut interpreter.current_frame)?,
+                    None => Slot::new_undefined(),
+                };
             }
-            CallPostOp::Call(args) => {
+            CallPostOp::Call(args) | CallPostOp::OptionalCall(args) => {
                 callee = call(interpreter, callee, args)?; // wtf use either Vec or Box<[]>
             }
+            CallPostOp::QuasiExpr(quasi) => {
+                callee = tagged_call(interpreter, callee, quasi)?;
+            }
         }
     }
     Completion::Value(callee)
 }
 
+// Untagged template, substitutions are converted to strings and concatenated
+fn eval_quasi(interpreter: &mut Interpreter, quasi: &QuasiExpr) -> Completion {
+    let mut string = quasi.strings[0].to_string();
+    for (expr, chunk) in quasi.exprs.iter().zip(quasi.strings[1..].iter()) {
+        let value = eval_expr(interpreter, expr)?;
+        string.push_str(&value.to_string());
+        string.push_str(chunk);
+    }
+    Completion::Value(Slot::new_string(string))
+}
+
+// Tagged template, the tag function is called with the array of string chunks followed by the substitution values
+fn tagged_call(interpreter: &mut Interpreter, callee: Slot, quasi: &QuasiExpr) -> Completion {
+    // the strings array is frozen, so that the tag can rely on the chunks being the ones in the source
+    let mut strings = Slot::new_array(quasi.strings.iter().map(|s| Slot::new_string(s.clone())).collect());
+    strings.freeze();
+
+    let mut arguments = Vec::with_capacity(quasi.exprs.len() + 1);
+    arguments.push(strings);
+    for expr in quasi.exprs.iter() {
+        arguments.push(eval_expr(interpreter, expr)?);
+    }
+
+    call_with_arguments(interpreter, callee, arguments)
+}
+
 fn call(interpreter: &mut Interpreter, callee: Slot, args: &Box<[Expr]>) -> Completion {
     let argument_completions = args.into_iter().map(|arg| eval_expr(interpreter, &arg));
 
@@ -429,6 +541,11 @@ fn call(interpreter: &mut Interpreter, callee: Slot, args: &Box<[Expr]>) -> Comp
     for arg_completion in argument_completions {
         arguments.push(arg_completion?)
     }
+
+    call_with_arguments(interpreter, callee, arguments)
+}
+
+fn call_with_arguments(interpreter: &mut Interpreter, callee: Slot, mut arguments: Vec<Slot>) -> Completion {
     let result = callee.call(&mut interpreter.current_frame, &mut arguments);
 
     println!("call result: {:?}", result);
@@ -442,5 +559,5 @@ fn call(interpreter: &mut Interpreter, callee: Slot, args: &Box<[Expr]>) -> Comp
 }
 
 fn eval_variable(interpreter: &mut Interpreter, var: Variable) -> Completion {
-    Completion::Value(interpreter.fetch_variable(var.index()).map(|slot| slot.clone())?)
+    Completion::Value(interpreter.fetch_variable(var.index()).map(|slot| slot.get())?)
 }
\ No newline at end of file
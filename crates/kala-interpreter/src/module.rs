@@ -1,13 +1,13 @@
 use std::cell::{OnceCell, Cell};
 use std::rc::Rc;
 
-use jessie_ast::Declaration;
-use jessie_ast::module::{Module, ModuleItem, ExportClause, Script};
+use jessie_ast::{Declaration, FunctionScope, Pattern};
+use jessie_ast::module::{Module, ModuleItem, ExportClause, ImportClause, Script};
 use kala_repr::function::Frame;
 use kala_repr::{completion::Completion, slot::Slot};
 use utils::map::Map;
 
-use crate::expression::eval_expr;
+use crate::expression::{promote_escaping_locals, hoist_functions};
 use crate::interpreter::Interpreter;
 use crate::statement::{eval_local_declaration, eval_statement};
 
@@ -26,20 +26,59 @@ pub fn eval_script(
     Completion::Value(result)
 }
 
+// imports are the namespace objects of the imported modules, one for each
+// import declaration in the order of Module::imports(), resolved by the host.
 pub fn eval_module(
     builtins: Vec<Slot>, 
     module: Module,
+    imports: Vec<Slot>,
 ) -> Completion {
-    let mut export_default = OnceCell::new();
+    let export_default = OnceCell::new();
+
+    let scope = match module.scope.as_ref() {
+        Some(scope) => scope,
+        None => return Completion::Throw(Slot::new_string("TypeError: module is not scoped before evaluation")),
+    };
 
     let mut interpreter = Interpreter::new(builtins, Frame::empty());
-    for item in module.body {
+    interpreter.current_frame.enter_function_frame(vec![], scope.locals.len());
+
+    promote_escaping_locals(&mut interpreter, &scope.locals);
+
+    if module.imports().count() != imports.len() {
+        return Completion::Throw(Slot::new_string("TypeError: number of imported modules does not match the import declarations"))
+    }
+
+    for (import, mut namespace) in module.imports().zip(imports.into_iter()) {
+        match &import.import_clause {
+            ImportClause::Namespace(var) => {
+                interpreter.fetch_variable(var.index())?.set(namespace);
+            }
+            ImportClause::Default(var) => {
+                let value = import_binding(&mut interpreter, &mut namespace, &"default".into(), &import.source)?;
+                interpreter.fetch_variable(var.index())?.set(value);
+            }
+            ImportClause::Named(names) => {
+                for (name, var) in names.iter() {
+                    let value = import_binding(&mut interpreter, &mut namespace, name, &import.source)?;
+                    interpreter.fetch_variable(var.index())?.set(value);
+                }
+            }
+        }
+    }
+
+    hoist_functions(&mut interpreter, &scope.functions)?;
+
+    for item in module.body.iter() {
         match item {
-            ModuleItem::ImportDeclaration(_) => unimplemented!("import"),
+            ModuleItem::ImportDeclaration(_) => {}, // already bound above
+            ModuleItem::TypeDeclaration(..) => {}, // types are erased
+            ModuleItem::Error(_) => return Completion::Throw(Slot::new_string("SyntaxError: module item failed to parse")),
             ModuleItem::ModuleDeclaration(decl) => {
-                let slot = eval_local_declaration(&mut interpreter, &decl.declaration)?;
+                eval_local_declaration(&mut interpreter, &decl.declaration)?;
                 if decl.export_clause == ExportClause::ExportDefault {
-                    export_default.set(slot);
+                    // the parser rejects a second default export
+                    let _ = export_default.set(declared_value(&mut interpreter, scope, &decl.declaration)?);
                 }
             }
         }
@@ -50,4 +89,31 @@ pub fn eval_module(
     } else {
         Completion::Normal
     }
-}
\ No newline at end of file
+}
+
+fn import_binding(interpreter: &mut Interpreter, namespace: &mut Slot, name: &Rc<str>, source: &str) -> Completion {
+    match namespace.get_property(name) {
+        Some(property) => match property.get(&mut interpreter.current_frame) {
+            Completion::Return(value) => Completion::Value(value),
+            Completion::ReturnEmpty => Completion::Value(Slot::new_undefined()),
+            completion => completion,
+        },
+        None => Completion::Throw(Slot::new_string(format!("SyntaxError: module {} does not provide an export named {}", source, name))),
+    }
+}
+
+// value of the (single) variable declared by an export default declaration
+fn declared_value(interpreter: &mut Interpreter, scope: &FunctionScope, decl: &Declaration) -> Completion {
+    let var = match decl {
+        Declaration::Function(func) => scope.functions.iter().find(|(_, f)| Rc::ptr_eq(f, func)).map(|(var, _)| var.clone()),
+        Declaration::Const(decls) | Declaration::Let(decls) => match decls.first().map(|decl| &decl.pattern) {
+            Some(Pattern::Variable(var)) => Some(var.as_ref().clone()),
+            _ => None,
+        },
+    };
+
+    match var {
+        Some(var) => Completion::Value(interpreter.fetch_variable(var.index())?.get()),
+        None => Completion::Value(Slot::new_undefined()),
+    }
+}
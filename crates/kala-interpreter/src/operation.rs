@@ -3,12 +3,41 @@ use kala_repr::slot::Slot;
 use kala_repr::completion::Completion;
 
 use crate::{interpreter::Interpreter, expression::eval_expr};
-/* 
-pub fn bit_and(interpreter: &mut Interpreter, x: Expr, y: Expr) -> Completion {
+pub fn bit_and(interpreter: &mut Interpreter, x: &Expr, y: &Expr) -> Completion {
     let x = eval_expr(interpreter, x)?;
     let y = eval_expr(interpreter, y)?;
+    Completion::Value(Slot::op_bit_and(&x, &y))
+}
+
+pub fn bit_or(interpreter: &mut Interpreter, x: &Expr, y: &Expr) -> Completion {
+    let x = eval_expr(interpreter, x)?;
+    let y = eval_expr(interpreter, y)?;
+    Completion::Value(Slot::op_bit_or(&x, &y))
+}
+
+pub fn bit_xor(interpreter: &mut Interpreter, x: &Expr, y: &Expr) -> Completion {
+    let x = eval_expr(interpreter, x)?;
+    let y = eval_expr(interpreter, y)?;
+    Completion::Value(Slot::op_bit_xor(&x, &y))
+}
+
+pub fn bit_left_shift(interpreter: &mut Interpreter, x: &Expr, y: &Expr) -> Completion {
+    let x = eval_expr(interpreter, x)?;
+    let y = eval_expr(interpreter, y)?;
+    Completion::Value(Slot::op_left_shift(&x, &y))
+}
+
+pub fn bit_right_shift(interpreter: &mut Interpreter, x: &Expr, y: &Expr) -> Completion {
+    let x = eval_expr(interpreter, x)?;
+    let y = eval_expr(interpreter, y)?;
+    Completion::Value(Slot::op_right_shift(&x, &y))
+}
+
+pub fn bit_unsigned_right_shift(interpreter: &mut Interpreter, x: &Expr, y: &Expr) -> Completion {
+    let x = eval_expr(interpreter, x)?;
+    let y = eval_expr(interpreter, y)?;
+    Completion::Value(Slot::op_unsigned_right_shift(&x, &y))
 }
-*/
 
 pub fn strict_equal(interpreter: &mut Interpreter, x: &Expr, y: &Expr) -> Completion {
     let x = eval_expr(interpreter, x)?;
@@ -49,37 +78,36 @@ pub fn greater_than_or_equal(interpreter: &mut Interpreter, x: &Expr, y: &Expr)
 pub fn add(interpreter: &mut Interpreter, x: &Expr, y: &Expr) -> Completion {
     let x = eval_expr(interpreter, x)?;
     let y = eval_expr(interpreter, y)?;
-    let res = Completion::Value(Slot::op_add(&x, &y));
-    res
+    Slot::op_add(&x, &y)
 }
 
 pub fn sub(interpreter: &mut Interpreter, x: &Expr, y: &Expr) -> Completion {
     let x = eval_expr(interpreter, x)?;
     let y = eval_expr(interpreter, y)?;
-    Completion::Value(Slot::op_sub(&x, &y))
+    Slot::op_sub(&x, &y)
 }
 
 pub fn mul(interpreter: &mut Interpreter, x: &Expr, y: &Expr) -> Completion {
     let x = eval_expr(interpreter, x)?;
     let y = eval_expr(interpreter, y)?;
-    Completion::Value(Slot::op_mul(&x, &y))
+    Slot::op_mul(&x, &y)
 }
 
 pub fn div(interpreter: &mut Interpreter, x: &Expr, y: &Expr) -> Completion {
     let x = eval_expr(interpreter, x)?;
     let y = eval_expr(interpreter, y)?;
-    Completion::Value(Slot::op_div(&x, &y))
+    Slot::op_div(&x, &y)
 }
 
 pub fn modulo(interpreter: &mut Interpreter, x: &Expr, y: &Expr) -> Completion {
     let x = eval_expr(interpreter, x)?;
     let y = eval_expr(interpreter, y)?;
-    Completion::Value(Slot::op_modulo(&x, &y))
+    Slot::op_modulo(&x, &y)
 }
 
 pub fn pow(interpreter: &mut Interpreter, x: &Expr, y: &Expr) -> Completion {
     let x = eval_expr(interpreter, x)?;
     let y = eval_expr(interpreter, y)?;
-    Completion::Value(Slot::op_pow(&x, &y))
+    Slot::op_pow(&x, &y)
 }
 
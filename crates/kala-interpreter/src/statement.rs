@@ -1,11 +1,11 @@
 use core::panic;
 use std::{rc::{self, Rc}};
 
-use jessie_ast::{Statement, IfStatement, ElseArm, WhileStatement, Block, Expr, VariableIndex, Declaration, VariableDeclaration, Pattern, Function};
+use jessie_ast::{PropParam, LValueOptional, OptionalPattern, ArrayPattern, RecordPattern, Statement, IfStatement, ElseArm, WhileStatement, ForOfStatement, TryStatement, CatchClause, SwitchStatement, CaseLabel, Block, Expr, VariableIndex, Declaration, VariableDeclaration, Pattern, Function};
 
 use crate::{expression::eval_expr, interpreter::Interpreter};
 
-use kala_repr::{completion::Completion, slot::Slot};
+use kala_repr::{completion::Completion, slot::{Slot, SlotTag}, iterator::SlotIterator, object::Property};
 
 pub fn eval_statement(interpreter: &mut Interpreter, statement: &Statement) -> Completion {
     match statement {
@@ -13,12 +13,18 @@ pub fn eval_statement(interpreter: &mut Interpreter, statement: &Statement) -> C
         Statement::Block(block) => eval_block(interpreter, &block),
         Statement::IfStatement(if_statement) => eval_if(interpreter, &if_statement),
         Statement::WhileStatement(while_statement) => eval_while(interpreter, &while_statement),
+        Statement::ForOfStatement(for_of_statement) => eval_for_of(interpreter, &for_of_statement),
         Statement::Continue => Completion::Continue,
         Statement::Break => Completion::Break,
         Statement::Return(expr) => Completion::Return(eval_expr(interpreter, &*expr)?),
         Statement::ReturnEmpty => Completion::ReturnEmpty,
         Statement::Throw(expr) => Completion::Throw(eval_expr(interpreter, &*expr)?),
+        Statement::TryStatement(try_statement) => eval_try(interpreter, &try_statement),
+        Statement::SwitchStatement(switch_statement) => eval_switch(interpreter, &switch_statement),
         Statement::ExprStatement(expr) => eval_expr(interpreter, &expr).into(),
+        // types are erased
+        Statement::TypeDeclaration(_) => Completion::Normal,
+        Statement::Error => Completion::Throw(Slot::new_string("SyntaxError: statement failed to parse")),
     }
 }
 
@@ -57,7 +63,103 @@ pub fn initialize_pattern(interpreter: &mut Interpreter, pattern: &Pattern, init
             println!("Initialized variable {:?}", variable);
             Completion::Normal
         }
-        _ => unimplemented!("initialize_pattern"),
+        Pattern::Optional(optional) => {
+            let OptionalPattern(_, left, default) = optional.as_ref();
+            let value = if initializer == Slot::new_undefined() {
+                eval_expr(interpreter, default)?
+            } else {
+                initializer
+            };
+            match left {
+                LValueOptional::Variable(var) => initialize_pattern(interpreter, &Pattern::Variable(var.clone()), value),
+                LValueOptional::ArrayPattern(array) => initialize_array_pattern(interpreter, array, value),
+                LValueOptional::RecordPattern(record) => initialize_record_pattern(interpreter, record, value),
+            }
+        }
+        Pattern::ArrayPattern(array) => initialize_array_pattern(interpreter, array, initializer),
+        Pattern::RecordPattern(record) => initialize_record_pattern(interpreter, record, initializer),
+        // rest elements are collected by the enclosing array pattern or parameter list
+        Pattern::Rest(pattern) => initialize_pattern(interpreter, pattern, initializer),
+    }
+}
+
+fn initialize_array_pattern(interpreter: &mut Interpreter, pattern: &ArrayPattern, mut initializer: Slot) -> Completion {
+    let iterable = match initializer.iterate(&mut interpreter.current_frame) {
+        Some(iterable) => iterable?,
+        None => return Completion::Throw(Slot::new_string("TypeError: array pattern initializer is not iterable")),
+    };
+    let mut iterator = SlotIterator::new(iterable);
+
+    for element in pattern.0.iter() {
+        if let Pattern::Rest(rest) = element {
+            let mut elements = Vec::new();
+            while let Some(value) = iterator.next(&mut interpreter.current_frame) {
+                elements.push(value?);
+            }
+            return initialize_pattern(interpreter, rest, Slot::new_array(elements))
+        }
+
+        let value = match iterator.next(&mut interpreter.current_frame) {
+            Some(value) => value?,
+            None => Slot::new_undefined(),
+        };
+        initialize_pattern(interpreter, element, value)?;
+    }
+
+    Completion::Normal
+}
+
+fn initialize_record_pattern(interpreter: &mut Interpreter, pattern: &RecordPattern, mut initializer: Slot) -> Completion {
+    if initializer == Slot::new_undefined() || initializer == Slot::new_null() {
+        return Completion::Throw(Slot::new_string("TypeError: cannot destructure undefined or null"))
+    }
+
+    let mut used_keys = Vec::with_capacity(pattern.0.len());
+
+    for prop in pattern.0.iter() {
+        match prop {
+            PropParam::KeyValue(field, pattern) => {
+                let value = get_property_value(interpreter, &mut initializer, &field.name)?;
+                used_keys.push(field.name.clone());
+                initialize_pattern(interpreter, pattern, value)?;
+            }
+            PropParam::Shorthand(field, var) => {
+                let value = get_property_value(interpreter, &mut initializer, &field.name)?;
+                used_keys.push(field.name.clone());
+                initialize_pattern(interpreter, &Pattern::Variable(var.clone()), value)?;
+            }
+            PropParam::Computed(key, pattern) => {
+                let key: Rc<str> = eval_expr(interpreter, key)?.to_string().into();
+                let value = get_property_value(interpreter, &mut initializer, &key)?;
+                used_keys.push(key);
+                initialize_pattern(interpreter, pattern, value)?;
+            }
+            PropParam::Rest(var) => {
+                let mut rest = Vec::new();
+                for key in initializer.property_keys() {
+                    if used_keys.contains(&key) {
+                        continue
+                    }
+                    let value = get_property_value(interpreter, &mut initializer, &key)?;
+                    rest.push(Property::data(key, value));
+                }
+                initialize_pattern(interpreter, &Pattern::Variable(var.clone()), Slot::new_object(rest))?;
+            }
+        }
+    }
+
+    Completion::Normal
+}
+
+// Missing properties are undefined.
+fn get_property_value(interpreter: &mut Interpreter, object: &mut Slot, name: &Rc<str>) -> Completion {
+    match object.get_property(name) {
+        Some(property) => match property.get(&mut interpreter.current_frame) {
+            Completion::Return(value) => Completion::Value(value),
+            Completion::ReturnEmpty | Completion::Normal => Completion::Value(Slot::new_undefined()),
+            completion => completion,
+        },
+        None => Completion::Value(Slot::new_undefined()),
     }
 }
 
@@ -122,7 +224,140 @@ pub fn eval_while(interpreter: &mut Interpreter, statement: &WhileStatement) ->
     Completion::Normal
 }
 
+pub fn eval_for_of(interpreter: &mut Interpreter, statement: &ForOfStatement) -> Completion {
+    let mut iterable = eval_expr(interpreter, &statement.iterable)?;
+    let iterable = match iterable.iterate(&mut interpreter.current_frame) {
+        Some(iterable) => iterable?,
+        None => return Completion::Throw(Slot::new_string("TypeError: not iterable")),
+    };
+    let mut iterator = SlotIterator::new(iterable);
+
+    while let Some(element) = iterator.next(&mut interpreter.current_frame) {
+        let element = element?;
+
+        // each iteration gets a fresh binding, so closures created in the
+        // previous iterations keep their own copy of the variable
+        refresh_pattern(interpreter, &statement.pattern);
+        let completion = match initialize_pattern(interpreter, &statement.pattern, element) {
+            Completion::Throw(error) => Completion::Throw(error),
+            _ => eval_block(interpreter, &statement.body),
+        };
+
+        match completion {
+            Completion::Continue | Completion::Normal | Completion::Value(_) => continue,
+            // the thrown error takes precedence over the one from closing the iterator
+            Completion::Throw(error) => {
+                let _ = iterator.close(&mut interpreter.current_frame);
+                return Completion::Throw(error)
+            },
+            Completion::Break => {
+                iterator.close(&mut interpreter.current_frame)?;
+                break
+            },
+            abrupt => {
+                iterator.close(&mut interpreter.current_frame)?;
+                return abrupt
+            },
+        }
+    }
+
+    Completion::Normal
+}
+
+// Replaces the heap cells of escaping local bindings in the pattern.
+fn refresh_pattern(interpreter: &mut Interpreter, pattern: &Pattern) {
+    match pattern {
+        Pattern::Variable(var) => {
+            if let VariableIndex::Local(_, index) = var.index() {
+                let slot = interpreter.current_frame.get_local(index as usize);
+                if let SlotTag::Pointer = slot.get_tag() {
+                    *slot = Slot::new_variable_slot();
+                }
+            }
+        }
+        Pattern::Rest(pattern) => refresh_pattern(interpreter, pattern),
+        Pattern::Optional(optional) => match &optional.1 {
+            LValueOptional::Variable(var) => refresh_pattern(interpreter, &Pattern::Variable(var.clone())),
+            LValueOptional::ArrayPattern(array) => refresh_pattern(interpreter, &Pattern::ArrayPattern(array.clone())),
+            LValueOptional::RecordPattern(record) => refresh_pattern(interpreter, &Pattern::RecordPattern(record.clone())),
+        },
+        Pattern::ArrayPattern(array) => {
+            for element in array.0.iter() {
+                refresh_pattern(interpreter, element);
+            }
+        }
+        Pattern::RecordPattern(record) => {
+            for prop in record.0.iter() {
+                match prop {
+                    PropParam::KeyValue(_, pattern) | PropParam::Computed(_, pattern) => refresh_pattern(interpreter, pattern),
+                    PropParam::Shorthand(_, var) | PropParam::Rest(var) => refresh_pattern(interpreter, &Pattern::Variable(var.clone())),
+                }
+            }
+        }
+    }
+}
+
 pub fn eval_throw(interpreter: &mut Interpreter, expr: &Expr) -> Completion {
     let exception = eval_expr(interpreter, &expr)?;
     Completion::Throw(exception)
-}
\ No newline at end of file
+}
+
+pub fn eval_switch(interpreter: &mut Interpreter, statement: &SwitchStatement) -> Completion {
+    let discriminant = eval_expr(interpreter, &statement.discriminant)?;
+
+    // case labels are evaluated in order until one matches, default is taken only if none does
+    let mut default = None;
+    let mut matched = None;
+    'clauses: for clause in statement.clauses.iter() {
+        for label in clause.labels.iter() {
+            match label {
+                CaseLabel::Case(expr) => {
+                    let case = eval_expr(interpreter, expr)?;
+                    if discriminant.op_strict_equal(&case).is_truthy() {
+                        matched = Some(clause);
+                        break 'clauses;
+                    }
+                }
+                CaseLabel::Default => default = Some(clause),
+            }
+        }
+    }
+
+    let clause = match matched.or(default) {
+        Some(clause) => clause,
+        None => return Completion::Normal,
+    };
+
+    // no fall-through, every clause ends with a terminator
+    match eval_block(interpreter, &clause.body) {
+        Completion::Break => Completion::Normal,
+        completion => completion,
+    }
+}
+
+pub fn eval_try(interpreter: &mut Interpreter, statement: &TryStatement) -> Completion {
+    let mut completion = eval_block(interpreter, &statement.block);
+
+    if let Completion::Throw(exception) = completion {
+        completion = match &statement.catch {
+            Some(catch) => eval_catch(interpreter, catch, exception),
+            None => Completion::Throw(exception),
+        }
+    }
+
+    if let Some(finally) = &statement.finally {
+        // finally always runs. If it completes abruptly(return, break, continue, throw),
+        // its completion overrides the pending one, otherwise the pending one is restored.
+        match eval_block(interpreter, finally) {
+            Completion::Normal | Completion::Value(_) => {},
+            abrupt => return abrupt,
+        }
+    }
+
+    completion
+}
+
+fn eval_catch(interpreter: &mut Interpreter, catch: &CatchClause, exception: Slot) -> Completion {
+    initialize_pattern(interpreter, &catch.param, exception)?;
+    eval_block(interpreter, &catch.body)
+}
@@ -1,10 +1,14 @@
 use super::slot::Slot;
 
+// frozen arrays reject element assignments, e.g. the strings array passed to a template tag
 #[derive(Clone)]
-pub struct Array(pub Vec<Slot>);
+pub struct Array {
+    pub elements: Vec<Slot>,
+    pub frozen: bool,
+}
 
 impl Array {
     pub fn get_element(&mut self, index: usize) -> Option<&mut Slot> {
-        self.0.get_mut(index)
+        self.elements.get_mut(index)
     }
 }
\ No newline at end of file
@@ -0,0 +1,217 @@
+use std::{cmp::Ordering, rc::Rc};
+
+use crate::reference::Reference;
+
+// Arbitrary precision integer, the sign and the magnitude in little endian u64 limbs.
+// The magnitude has no leading zero limbs, and zero is not negative, so equal values have equal representations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bigint {
+    pub negative: bool,
+    pub limbs: Rc<[u64]>,
+}
+
+impl Bigint {
+    pub fn new(negative: bool, limbs: &[u64]) -> Self {
+        let len = limbs.iter().rposition(|limb| *limb != 0).map_or(0, |last| last + 1);
+        Bigint {
+            negative: negative && len != 0,
+            limbs: limbs[..len].into(),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub(crate) fn op_strict_equal_internal(&self, other: &Self) -> bool {
+        self == other
+    }
+
+    pub(crate) fn op_neg(&self) -> Self {
+        Bigint {
+            negative: !self.negative && !self.is_zero(),
+            limbs: self.limbs.clone(),
+        }
+    }
+
+    pub(crate) fn op_less_than_internal(&self, other: &Self) -> bool {
+        match (self.negative, other.negative) {
+            (true, false) => true,
+            (false, true) => false,
+            (false, false) => compare(&self.limbs, &other.limbs) == Ordering::Less,
+            (true, true) => compare(&other.limbs, &self.limbs) == Ordering::Less,
+        }
+    }
+
+    pub(crate) fn op_add_internal(&self, other: &Self) -> Self {
+        if self.negative == other.negative {
+            return Bigint::new(self.negative, &add(&self.limbs, &other.limbs))
+        }
+
+        // the sign of the operand with the larger magnitude
+        match compare(&self.limbs, &other.limbs) {
+            Ordering::Less => Bigint::new(other.negative, &sub(&other.limbs, &self.limbs)),
+            _ => Bigint::new(self.negative, &sub(&self.limbs, &other.limbs)),
+        }
+    }
+
+    pub(crate) fn op_sub_internal(&self, other: &Self) -> Self {
+        self.op_add_internal(&other.op_neg())
+    }
+
+    pub(crate) fn op_mul_internal(&self, other: &Self) -> Self {
+        Bigint::new(self.negative != other.negative, &mul(&self.limbs, &other.limbs))
+    }
+
+    // the quotient truncated towards zero, None for a zero divisor
+    pub(crate) fn op_div_internal(&self, other: &Self) -> Option<Self> {
+        if other.is_zero() {
+            return None
+        }
+
+        let (quotient, _) = div_rem(&self.limbs, &other.limbs);
+        Some(Bigint::new(self.negative != other.negative, &quotient))
+    }
+
+    // the remainder has the sign of the dividend, None for a zero divisor
+    pub(crate) fn op_modulo_internal(&self, other: &Self) -> Option<Self> {
+        if other.is_zero() {
+            return None
+        }
+
+        let (_, remainder) = div_rem(&self.limbs, &other.limbs);
+        Some(Bigint::new(self.negative, &remainder))
+    }
+
+    // None for a negative exponent, or one that does not fit in a limb
+    pub(crate) fn op_pow_internal(&self, other: &Self) -> Option<Self> {
+        if other.negative || other.limbs.len() > 1 {
+            return None
+        }
+
+        // exponentiation by squaring
+        let mut exponent = other.limbs.first().copied().unwrap_or(0);
+        let mut base = self.clone();
+        let mut result = Bigint::new(false, &[1]);
+        while exponent != 0 {
+            if exponent & 1 == 1 {
+                result = result.op_mul_internal(&base);
+            }
+            exponent >>= 1;
+            if exponent != 0 {
+                base = base.op_mul_internal(&base);
+            }
+        }
+        Some(result)
+    }
+}
+
+// Operations on the magnitudes, little endian limbs without leading zeros.
+
+fn compare(x: &[u64], y: &[u64]) -> Ordering {
+    x.len().cmp(&y.len()).then_with(|| x.iter().rev().cmp(y.iter().rev()))
+}
+
+fn add(x: &[u64], y: &[u64]) -> Vec<u64> {
+    let (x, y) = if x.len() < y.len() { (y, x) } else { (x, y) };
+    let mut result = Vec::with_capacity(x.len() + 1);
+    let mut carry = false;
+    for (i, limb) in x.iter().enumerate() {
+        let (sum, overflow) = limb.carrying_add(y.get(i).copied().unwrap_or(0), carry);
+        result.push(sum);
+        carry = overflow;
+    }
+    result.push(carry as u64);
+    result
+}
+
+// x - y for x >= y
+fn sub(x: &[u64], y: &[u64]) -> Vec<u64> {
+    let mut result = Vec::with_capacity(x.len());
+    let mut borrow = false;
+    for (i, limb) in x.iter().enumerate() {
+        let (difference, underflow) = limb.borrowing_sub(y.get(i).copied().unwrap_or(0), borrow);
+        result.push(difference);
+        borrow = underflow;
+    }
+    result
+}
+
+fn mul(x: &[u64], y: &[u64]) -> Vec<u64> {
+    let mut result = vec![0u64; x.len() + y.len()];
+    for (i, a) in x.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, b) in y.iter().enumerate() {
+            let product = *a as u128 * *b as u128 + result[i + j] as u128 + carry as u128;
+            result[i + j] = product as u64;
+            carry = (product >> 64) as u64;
+        }
+        result[i + y.len()] = carry;
+    }
+    result
+}
+
+// long division one bit at a time, the divisor is not zero
+fn div_rem(x: &[u64], y: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    let mut quotient = vec![0u64; x.len()];
+    let mut remainder: Vec<u64> = Vec::with_capacity(y.len() + 1);
+    for bit in (0..x.len() * 64).rev() {
+        // remainder = remainder * 2 + the next bit of x
+        let mut carry = (x[bit / 64] >> (bit % 64)) & 1;
+        for limb in remainder.iter_mut() {
+            let next = *limb >> 63;
+            *limb = (*limb << 1) | carry;
+            carry = next;
+        }
+        if carry != 0 {
+            remainder.push(carry);
+        }
+
+        if compare(&remainder, y) != Ordering::Less {
+            remainder = sub(&remainder, y);
+            while remainder.last() == Some(&0) {
+                remainder.pop();
+            }
+            quotient[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+    (quotient, remainder)
+}
+
+impl ToString for Bigint {
+    fn to_string(&self) -> String {
+        if self.is_zero() {
+            return "0".to_string()
+        }
+
+        // repeated division by 10^19, the largest power of ten in a limb
+        const CHUNK: u128 = 10_000_000_000_000_000_000;
+        let mut limbs = self.limbs.to_vec();
+        let mut chunks = Vec::new();
+        while !limbs.is_empty() {
+            let mut remainder: u128 = 0;
+            for limb in limbs.iter_mut().rev() {
+                let current = (remainder << 64) | *limb as u128;
+                *limb = (current / CHUNK) as u64;
+                remainder = current % CHUNK;
+            }
+            chunks.push(remainder as u64);
+            while limbs.last() == Some(&0) {
+                limbs.pop();
+            }
+        }
+
+        let mut string = if self.negative { "-".to_string() } else { String::new() };
+        string.push_str(&chunks.pop().unwrap().to_string());
+        for chunk in chunks.iter().rev() {
+            string.push_str(&format!("{:019}", chunk));
+        }
+        string
+    }
+}
+
+impl Into<Reference> for Bigint {
+    fn into(self) -> Reference {
+        Reference::Bigint(self)
+    }
+}
@@ -143,4 +143,7 @@ impl Into<Completion> for Evaluation {
     fn into(self) -> Completion {
         unsafe {std::mem::transmute(self)}
     }
-}*/
\ No newline at end of file
+}*/
+impl ops::Residual<Slot> for Completion {
+    type TryType = Completion;
+}
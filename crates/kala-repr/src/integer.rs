@@ -8,9 +8,11 @@ pub struct Integer(pub isize); // tagged with 0b0001
 const INTEGER_MASK: isize = !0b1111;
 
 impl Integer {
+    // the payload is the 60(or 28) bits above the tag, so the value must
+    // survive the shift by 4 with its sign intact
     #[cfg(target_pointer_width="64")]
     pub fn new(x: i64) -> Option<Self> {
-        if x>>60 == 0 || x>>60 == -1 {
+        if x>>59 == 0 || x>>59 == -1 {
             let mut res = Integer((x << 4) as isize);
             res.tag();
             Some(res)
@@ -21,10 +23,10 @@ impl Integer {
 
     #[cfg(target_pointer_width="32")]
     pub fn new(x: i64) -> Option<Self> {
-        if x>>28 == 0 || x>>28 == -1 {
+        if x>>27 == 0 || x>>27 == -1 {
             let mut res = Integer((x << 4) as isize);
             res.tag();
-            res
+            Some(res)
         } else {
             None
         }
@@ -99,7 +101,8 @@ impl Integer {
     }
 
     pub(crate) fn op_neg(&self) -> Self {
-        let mut res = Integer(-self.0);
+        // negate the untagged value, negating the tag bit would borrow from the value
+        let mut res = Integer(-(self.0 & INTEGER_MASK));
         res.tag();
         res
     }
@@ -0,0 +1,132 @@
+use std::rc::Rc;
+
+use crate::{slot::{Slot, SlotTag}, reference::Reference, function::Frame, completion::Completion};
+
+// Iteration protocol used by for-of loops.
+// Arrays and strings are iterated natively. Host objects opt in by exposing a
+// function under ITERATOR_HOOK, which returns an iterator object whose next()
+// returns { value, done } records, same as ECMAScript iterator results.
+// The iterator object may also have a return() method, called when the loop
+// exits before the iteration is done.
+pub const ITERATOR_HOOK: &str = "@@iterator";
+
+pub enum SlotIterator {
+    // holds the array itself, so the length is reread on every step
+    Array(Slot, usize),
+    // iterates by code points, index is a byte offset
+    String(Rc<str>, usize),
+    // iterator object returned by the host hook
+    Host(Slot),
+}
+
+impl Slot {
+    // Returns None if the slot is not iterable.
+    pub fn iterate(&mut self, frame: &mut Frame) -> Option<Completion> {
+        match self.get_tag() {
+            SlotTag::Pointer => self.unwrap_mut_pointer().iterate(frame),
+            SlotTag::Reference => match self.unwrap_mut_reference() {
+                Reference::Array(_) => Some(Completion::Value(self.clone())),
+                Reference::String(_) => Some(Completion::Value(self.clone())),
+                Reference::Object(object) => {
+                    let hook = object.index_mut_property_by_string(ITERATOR_HOOK)?;
+                    let hook = match hook.get(frame) {
+                        Completion::Value(hook) => hook,
+                        Completion::Throw(error) => return Some(Completion::Throw(error)),
+                        _ => return None,
+                    };
+                    match hook.call(frame, &mut vec![]) {
+                        Completion::Value(iterator) | Completion::Return(iterator) => Some(Completion::Value(iterator)),
+                        Completion::Throw(error) => Some(Completion::Throw(error)),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl SlotIterator {
+    // Takes the result of Slot::iterate.
+    pub fn new(iterable: Slot) -> Self {
+        if let SlotTag::Reference = iterable.get_tag() {
+            match iterable.unwrap_reference() {
+                Reference::Array(_) => return SlotIterator::Array(iterable, 0),
+                Reference::String(string) => return SlotIterator::String(string.clone(), 0),
+                _ => {},
+            }
+        }
+        SlotIterator::Host(iterable)
+    }
+
+    // Returns None when the iteration is done.
+    pub fn next(&mut self, frame: &mut Frame) -> Option<Completion> {
+        match self {
+            SlotIterator::Array(array, index) => {
+                let element = array.get_element(*index)?.clone();
+                *index += 1;
+                Some(Completion::Value(element))
+            },
+            SlotIterator::String(string, index) => {
+                let c = string[*index..].chars().next()?;
+                *index += c.len_utf8();
+                Some(Completion::Value(Slot::new_string(c.to_string())))
+            },
+            SlotIterator::Host(iterator) => {
+                let next = match iterator.get_property(&"next".into()) {
+                    Some(next) => next.get(frame),
+                    None => return Some(Completion::Throw(Slot::new_string("TypeError: iterator has no next method"))),
+                };
+                let mut result = match next {
+                    Completion::Value(next) => match next.call(frame, &mut vec![]) {
+                        Completion::Value(result) | Completion::Return(result) => result,
+                        Completion::Throw(error) => return Some(Completion::Throw(error)),
+                        _ => return Some(Completion::Throw(Slot::new_string("TypeError: iterator result is not an object"))),
+                    },
+                    throw => return Some(throw),
+                };
+                let done = match get_result_property(&mut result, "done", frame) {
+                    Completion::Value(done) => done.is_truthy(),
+                    throw => return Some(throw),
+                };
+                if done {
+                    return None
+                }
+                Some(get_result_property(&mut result, "value", frame))
+            },
+        }
+    }
+
+    // Called on break, return or throw out of the loop. Only the host iterators
+    // are notified, through their return() method if any.
+    pub fn close(&mut self, frame: &mut Frame) -> Completion {
+        let SlotIterator::Host(iterator) = self else {
+            return Completion::Normal
+        };
+        let close = match iterator.get_property(&"return".into()) {
+            Some(close) => close.get(frame)?,
+            None => return Completion::Normal,
+        };
+        if close.is_nullish() {
+            return Completion::Normal
+        }
+        match close.call(frame, &mut vec![]) {
+            Completion::Throw(error) => Completion::Throw(error),
+            _ => Completion::Normal,
+        }
+    }
+}
+
+// Reads a property of an iterator result, through its getter if any.
+// Missing properties are undefined.
+fn get_result_property(result: &mut Slot, name: &str, frame: &mut Frame) -> Completion {
+    match result.get_property(&name.into()) {
+        Some(property) => match property.get(frame) {
+            Completion::Return(value) => Completion::Value(value),
+            Completion::ReturnEmpty | Completion::Normal => Completion::Value(Slot::new_undefined()),
+            completion => completion,
+        },
+        None => Completion::Value(Slot::new_undefined()),
+    }
+}
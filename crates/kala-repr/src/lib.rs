@@ -1,6 +1,7 @@
-#![feature(bigint_helper_methods)] 
+#![feature(signed_bigint_helpers)] 
 #![feature(unique_rc_arc)]
 #![feature(try_trait_v2)]
+#![feature(try_trait_v2_residual)]
 pub mod slot;
 pub mod reference;
 pub mod constant;
@@ -11,4 +12,5 @@ pub mod array;
 pub mod function;
 pub mod operation;
 pub mod completion;
-pub mod error;
\ No newline at end of file
+pub mod iterator;
+pub mod error;pub mod bigint;
@@ -1,4 +1,4 @@
-use std::mem::{MaybeUninit, transmute};
+use std::mem::transmute;
 
 use crate::{integer::Integer, reference::Reference};
 
@@ -16,6 +16,13 @@ pub const NEGATIVE_INFINITY: Number = Number {
     x3: 0x8000_0000u32 as i32,
 };
 
+pub const NAN: Number = Number {
+    x0: 1,
+    x1: 0,
+    x2: 0,
+    x3: 0x8000_0000u32 as i32,
+};
+
 pub const NUMBER_ZERO: Number = Number {
     x0: 0,
     x1: 0,
@@ -23,6 +30,9 @@ pub const NUMBER_ZERO: Number = Number {
     x3: 0,
 };
 
+// the decimal fixed point scale, 19 fractional digits like the decimal literals
+const SCALE: i128 = 10_000_000_000_000_000_000;
+
 #[cfg(target_endian="little")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Number {
@@ -33,125 +43,310 @@ pub struct Number {
 }
 
 impl Number {
-    #[cfg(target_endian="little")] 
+    #[cfg(target_endian="little")]
     fn assert_memory_sanity(&self) {
         unsafe {
-            assert_eq!(transmute::<Number, i128>(Number{x0: 0, x1: 0, x2: 1, x3: 0}), 1i128);
-            assert_eq!(transmute::<Number, i128>(Number{x0: u32::MAX-1, x1: u32::MAX, x2: u32::MAX, x3: i32::MIN}), i128::MIN+1);
+            assert_eq!(transmute::<Number, i128>(Number{x0: 1, x1: 0, x2: 0, x3: 0}), 1i128);
+            assert_eq!(transmute::<Number, i128>(Number{x0: 1, x1: 0, x2: 0, x3: i32::MIN}), i128::MIN+1);
         }
     }
 
-    #[cfg(target_endian="little")]    
+    // i + f / 10^19, the integer part and the fraction in 10^-19 units
     pub fn new(i: i64, f: u64) -> Self {
-        let mut result = MaybeUninit::<Number>::uninit();
-        unsafe {
-            result.as_mut_ptr().write(Number {
-                x0: f as u32,
-                x1: (f >> 32) as u32,
-                x2: i as u32,
-                x3: (i >> 32) as i32,
-            });
-            result.assume_init()
-        } 
+        Number::from_fixed(i as i128 * SCALE + f as i128)
     }
 }
 
+// The operations return None when a finite result is out of the range of the
+// fixed point, NaN and the infinities are values of their own.
+impl Number {
+    pub(crate) fn op_strict_equal_internal(&self, other: &Self) -> bool {
+        !self.is_nan() && self == other
+    }
 
+    pub(crate) fn op_strict_equal_internal_integer(&self, other: &Integer) -> bool {
+        self.is_integral() && self.integer_part() == other.unwrap() as i64
+    }
 
-impl Number {
-    pub fn overflowing_add(&self, other: &Self) -> (Self, bool) {
-        let (res0, overflow0) = self.x0.overflowing_add(other.x0);
-        let (res1, overflow1) = self.x1.carrying_add(other.x1, overflow0);
-        let (res2, overflow2) = self.x2.carrying_add(other.x2, overflow1);
-        let (res3, overflow3) = self.x3.carrying_add(other.x3, overflow2);
+    pub(crate) fn op_less_than_internal(&self, other: &Self) -> Option<bool> {
+        // comparisons with NaN are false
+        Some(!self.is_nan() && !other.is_nan() && self.to_fixed() < other.to_fixed())
+    }
 
-        let res = Number {
-            x0: res0,
-            x1: res1,
-            x2: res2,
-            x3: res3,
-        };
+    pub(crate) fn op_less_than_or_equal_internal(&self, other: &Self) -> Option<bool> {
+        Some(!self.is_nan() && !other.is_nan() && self.to_fixed() <= other.to_fixed())
+    }
 
-        (res, overflow3)
+    pub(crate) fn op_add_internal(&self, other: &Self) -> Option<Self> {
+        if self.is_nan() || other.is_nan() {
+            return Some(NAN)
+        }
+
+        match (self.is_infinite(), other.is_infinite()) {
+            // Infinity + -Infinity is NaN
+            (true, true) if self != other => Some(NAN),
+            (true, _) => Some(*self),
+            (_, true) => Some(*other),
+            _ => Number::from_finite(self.to_fixed().checked_add(other.to_fixed())?),
+        }
     }
 
-    pub fn overflowing_sub(&self, other: &Self) -> (Self, bool) {
-        let (res0, borrow0) = self.x0.overflowing_sub(other.x0);
-        let (res1, borrow1) = self.x1.borrowing_sub(other.x1, borrow0);
-        let (res2, borrow2) = self.x2.borrowing_sub(other.x2, borrow1);
-        let (res3, borrow3) = self.x3.borrowing_sub(other.x3, borrow2);
+    pub(crate) fn op_sub_internal(&self, other: &Self) -> Option<Self> {
+        self.op_add_internal(&other.op_neg())
+    }
 
-        let res = Number {
-            x0: res0,
-            x1: res1,
-            x2: res2,
-            x3: res3,
-        };
+    pub(crate) fn op_mul_internal(&self, other: &Self) -> Option<Self> {
+        if self.is_nan() || other.is_nan() {
+            return Some(NAN)
+        }
+
+        let negative = (self.to_fixed() < 0) != (other.to_fixed() < 0);
+
+        if self.is_infinite() || other.is_infinite() {
+            // Infinity * 0 is NaN
+            if self == &NUMBER_ZERO || other == &NUMBER_ZERO {
+                return Some(NAN)
+            }
+            return Some(Number::infinity(negative))
+        }
 
-        (res, borrow3)
+        // the exact product of the magnitudes, scaled back by 10^19
+        let product = wide_mul(self.to_fixed().unsigned_abs(), other.to_fixed().unsigned_abs());
+        Number::from_magnitude(wide_div_round(product, SCALE as u128)?, negative)
     }
 
-    pub(crate) fn op_strict_equal_internal(&self, other: &Self) -> bool {
-        self.x0 == other.x0
-            && self.x1 == other.x1
-            && self.x2 == other.x2
-            && self.x3 == other.x3
+    pub(crate) fn op_div_internal(&self, other: &Self) -> Option<Self> {
+        if self.is_nan() || other.is_nan() {
+            return Some(NAN)
+        }
+
+        let negative = (self.to_fixed() < 0) != (other.to_fixed() < 0);
+
+        match (self.is_infinite(), other.is_infinite()) {
+            // Infinity / Infinity is NaN
+            (true, true) => return Some(NAN),
+            (true, false) => return Some(Number::infinity(negative)),
+            (false, true) => return Some(NUMBER_ZERO),
+            _ => {}
+        }
+
+        if other == &NUMBER_ZERO {
+            // 0 / 0 is NaN
+            if self == &NUMBER_ZERO {
+                return Some(NAN)
+            }
+            return Some(Number::infinity(self.to_fixed() < 0))
+        }
+
+        // the exact dividend scaled up by 10^19, divided by the divisor
+        let dividend = wide_mul(self.to_fixed().unsigned_abs(), SCALE as u128);
+        Number::from_magnitude(wide_div_round(dividend, other.to_fixed().unsigned_abs())?, negative)
+    }
+
+    pub(crate) fn op_modulo_internal(&self, other: &Self) -> Option<Self> {
+        // Infinity % x and x % 0 are NaN, x % Infinity is x
+        if self.is_nan() || other.is_nan() || self.is_infinite() || other == &NUMBER_ZERO {
+            return Some(NAN)
+        }
+        if other.is_infinite() {
+            return Some(*self)
+        }
+
+        // both operands share the fixed point scale, so the remainder does too
+        Some(Number::from_fixed(self.to_fixed() % other.to_fixed()))
     }
-/* 
-    #[cfg(target_pointer_width="32")]
+
+    pub(crate) fn op_pow_internal(&self, other: &Self) -> Option<Self> {
+        if self.is_finite() && other.is_integral() {
+            // exponentiation by squaring, a negative exponent is the reciprocal
+            let mut exponent = other.integer_part().unsigned_abs();
+            let mut base = *self;
+            let mut result = Number::new(1, 0);
+            while exponent != 0 {
+                if exponent & 1 == 1 {
+                    result = result.op_mul_internal(&base)?;
+                }
+                exponent >>= 1;
+                if exponent != 0 {
+                    base = base.op_mul_internal(&base)?;
+                }
+            }
+
+            if other.to_fixed() < 0 {
+                return Number::new(1, 0).op_div_internal(&result)
+            }
+            return Some(result)
+        }
+
+        // x ** 0 is 1 even for NaN
+        if other == &NUMBER_ZERO {
+            return Some(Number::new(1, 0))
+        }
+
+        Number::from_f64(self.to_f64().powf(other.to_f64()))
+    }
+
+    pub(crate) fn op_neg(&self) -> Self {
+        match *self {
+            POSITIVE_INFINITY => NEGATIVE_INFINITY,
+            NEGATIVE_INFINITY => POSITIVE_INFINITY,
+            NAN => NAN,
+            _ => Number::from_fixed(-self.to_fixed()),
+        }
+    }
+
+    // ToInt32, the integer part truncated towards zero and wrapped to 32 bits
+    pub(crate) fn to_int32(&self) -> i32 {
+        if !self.is_finite() {
+            return 0
+        }
+
+        (self.to_fixed() / SCALE) as i64 as i32
+    }
+}
+
+impl Number {
+    // the value scaled by 10^19, which shares the layout of the i128
     #[cfg(target_endian="little")]
-    pub(crate) fn op_strict_equal_internal_integer(&self, other: &Integer) -> bool {
-        if self.x0 != 0 || self.x1 != 0 {
-            return false;
+    fn to_fixed(&self) -> i128 {
+        unsafe { transmute(*self) }
+    }
+
+    #[cfg(target_endian="little")]
+    fn from_fixed(fixed: i128) -> Self {
+        unsafe { transmute(fixed) }
+    }
+
+    // None for the fixed point values taken by NaN and the infinities
+    fn from_finite(fixed: i128) -> Option<Self> {
+        let number = Number::from_fixed(fixed);
+        if number.is_finite() {
+            Some(number)
+        } else {
+            None
         }
+    }
 
-        let integer_part: i64 = unsafe{transmute((self.x2, self.x3))};
-        integer_part == other.unwrap().try_into().unwrap()
+    fn from_magnitude(magnitude: u128, negative: bool) -> Option<Self> {
+        let magnitude = i128::try_from(magnitude).ok()?;
+        Number::from_finite(if negative { -magnitude } else { magnitude })
     }
-*/
-    pub(crate) fn op_strict_equal_internal_integer(&self, other: &Integer) -> bool {
-        if self.x0 != 0 || self.x1 != 0 {
-            return false;
+
+    fn infinity(negative: bool) -> Self {
+        if negative {
+            NEGATIVE_INFINITY
+        } else {
+            POSITIVE_INFINITY
         }
+    }
 
-        let integer_part: i64 = unsafe{transmute((self.x2, self.x3))};
-        integer_part == other.unwrap().try_into().unwrap()
+    pub fn is_nan(&self) -> bool {
+        *self == NAN
     }
 
-    pub(crate) fn op_less_than_internal(&self, other: &Self) -> Option<bool> {
-        unimplemented!("op_less_than_internal")
+    pub fn is_infinite(&self) -> bool {
+        *self == POSITIVE_INFINITY || *self == NEGATIVE_INFINITY
     }
 
-    pub(crate) fn op_less_than_internal_integer(&self, other: &Integer) -> Option<bool> {
-        unimplemented!("op_less_than_internal_integer")
+    pub fn is_finite(&self) -> bool {
+        !self.is_nan() && !self.is_infinite()
     }
 
-    pub(crate) fn op_add_internal(&self, other: &Self) -> Option<Self> {
-        unimplemented!("op_add_internal")
+    pub fn is_integral(&self) -> bool {
+        self.is_finite() && self.to_fixed() % SCALE == 0
     }
 
-    pub(crate) fn op_sub_internal(&self, other: &Self) -> Option<Self> {
-        unimplemented!("op_sub_internal")
+    // floor of the value
+    pub fn integer_part(&self) -> i64 {
+        self.to_fixed().div_euclid(SCALE) as i64
     }
 
-    pub(crate) fn op_add_internal_integer(&self, other: &Integer) -> Option<Self> {
-        unimplemented!("op_add_internal_integer")
+    fn to_f64(&self) -> f64 {
+        match *self {
+            POSITIVE_INFINITY => f64::INFINITY,
+            NEGATIVE_INFINITY => f64::NEG_INFINITY,
+            NAN => f64::NAN,
+            _ => self.to_fixed() as f64 / SCALE as f64,
+        }
     }
 
-    pub(crate) fn op_neg(&self) -> Self {
-        unimplemented!("op_neg")
+    // rounded to the nearest fixed point value, None for the finite values out of range
+    pub(crate) fn from_f64(x: f64) -> Option<Self> {
+        if x.is_nan() {
+            return Some(NAN)
+        }
+        if x.is_infinite() {
+            return Some(Number::infinity(x < 0.0))
+        }
+
+        let fixed = (x * SCALE as f64).round();
+        if fixed.abs() >= i128::MAX as f64 {
+            return None
+        }
+        Number::from_finite(fixed as i128)
+    }
+}
+
+// the 256 bit product of two magnitudes, as the high and the low halves
+fn wide_mul(x: u128, y: u128) -> (u128, u128) {
+    const LOW: u128 = u64::MAX as u128;
+    let (xh, xl) = (x >> 64, x & LOW);
+    let (yh, yl) = (y >> 64, y & LOW);
+
+    let low = xl * yl;
+    let (middle, carry) = (xh * yl).overflowing_add(xl * yh);
+    let (low, borrow) = low.overflowing_add(middle << 64);
+    let high = xh * yh + (middle >> 64) + ((carry as u128) << 64) + borrow as u128;
+    (high, low)
+}
+
+// the 256 bit dividend divided by the divisor, rounded half to even,
+// None when the quotient does not fit in 128 bits
+fn wide_div_round((high, low): (u128, u128), divisor: u128) -> Option<u128> {
+    if high >= divisor {
+        return None
     }
 
+    // long division, one bit of the low half at a time
+    let (mut quotient, mut remainder) = (0u128, high);
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127 == 1;
+        remainder = (remainder << 1) | ((low >> bit) & 1);
+        quotient <<= 1;
+        if carry || remainder >= divisor {
+            remainder = remainder.wrapping_sub(divisor);
+            quotient |= 1;
+        }
+    }
+
+    // round up past the half, or at the half when the quotient is odd
+    let half = divisor - remainder;
+    if remainder > half || (remainder == half && quotient & 1 == 1) {
+        quotient = quotient.checked_add(1)?;
+    }
+    Some(quotient)
 }
 
 impl ToString for Number {
     fn to_string(&self) -> String {
-        unimplemented!("Number::to_string")
+        match *self {
+            POSITIVE_INFINITY => "Infinity".to_string(),
+            NEGATIVE_INFINITY => "-Infinity".to_string(),
+            NAN => "NaN".to_string(),
+            _ if self.is_integral() => self.integer_part().to_string(),
+            _ => {
+                // the exact decimal digits, without the trailing zeros
+                let fixed = self.to_fixed();
+                let magnitude = fixed.unsigned_abs();
+                let fraction = format!("{:019}", magnitude % SCALE as u128);
+                format!("{}{}.{}", if fixed < 0 { "-" } else { "" }, magnitude / SCALE as u128, fraction.trim_end_matches('0'))
+            }
+        }
     }
 }
 
 impl Into<Reference> for Number {
     fn into(self) -> Reference {
-        unimplemented!("Number::into<Reference>")
+        Reference::Number(self)
     }
-}
\ No newline at end of file
+}
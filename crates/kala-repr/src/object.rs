@@ -64,17 +64,26 @@ impl Property {
         }
     }
 
+    // the return of an accessor ends the accessor call, not the function accessing the property
     pub fn get(&mut self, frame: &mut Frame) -> Completion {
         if self.getter != Slot::UNINITIALIZED {
-            self.getter.call(frame, &mut vec![])
+            match self.getter.call(frame, &mut vec![]) {
+                Completion::Return(slot) | Completion::Value(slot) => Completion::Value(slot),
+                Completion::Throw(error) => Completion::Throw(error),
+                _ => Completion::Value(Slot::UNDEFINED),
+            }
         } else {
             Completion::Value(self.data.clone())
         }
     }
 
+    // the assignment evaluates to the assigned value, whatever the setter returns
     pub fn set(&mut self, frame: &mut Frame, value: Slot) -> Completion {
         if self.setter != Slot::UNINITIALIZED {
-            self.setter.call(frame, &mut vec![value])
+            match self.setter.call(frame, &mut vec![value.clone()]) {
+                Completion::Throw(error) => Completion::Throw(error),
+                _ => Completion::Value(value),
+            }
         } else {
             self.data = value;
             Completion::Value(self.data.clone())
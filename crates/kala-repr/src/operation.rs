@@ -1,42 +1,86 @@
 use std::{mem::ManuallyDrop, str::FromStr, rc::Rc};
 
-use crate::{slot::{Slot, MASK, SlotTag::{self}, SlotConstant, SlotInteger, SlotReference}, reference::Reference, constant::Constant, integer::Integer, number::Number, object::Property};
+use crate::{slot::{Slot, MASK, SlotTag::{self}, SlotConstant, SlotInteger, SlotReference}, reference::Reference, constant::Constant, integer::Integer, number::{Number, NAN}, bigint::Bigint, object::Property, completion::Completion};
+
+// the errors thrown by the arithmetic operators
+const INVALID_OPERANDS: &str = "TypeError: invalid operands for an arithmetic operation";
+const MIXED_BIGINT: &str = "TypeError: cannot mix BigInt and other types, use explicit conversions";
+const OUT_OF_RANGE: &str = "RangeError: the result is out of the range of a number";
+const DIVISION_BY_ZERO: &str = "RangeError: BigInt division by zero";
+const INVALID_EXPONENT: &str = "RangeError: BigInt exponent is negative or too large";
 
 impl Slot {
-    pub fn op_add(&self, other: &Self) -> Slot {
-        match self.op_add_internal(other) {
+    pub fn op_add(&self, other: &Self) -> Completion {
+        Slot::arithmetic_completion(self.op_add_internal(other))
+    }
+
+    pub fn op_sub(&self, other: &Self) -> Completion {
+        Slot::arithmetic_completion(self.op_sub_internal(other))
+    }
+
+    pub fn op_mul(&self, other: &Self) -> Completion {
+        Slot::arithmetic_completion(self.op_mul_internal(other))
+    }
+
+    pub fn op_div(&self, other: &Self) -> Completion {
+        Slot::arithmetic_completion(self.op_div_internal(other))
+    }
+
+    pub fn op_modulo(&self, other: &Self) -> Completion {
+        Slot::arithmetic_completion(self.op_modulo_internal(other))
+    }
+
+    pub fn op_pow(&self, other: &Self) -> Completion {
+        Slot::arithmetic_completion(self.op_pow_internal(other))
+    }
+
+    fn arithmetic_completion(result: Result<Slot, &'static str>) -> Completion {
+        match result {
+            Ok(slot) => Completion::Value(slot),
+            Err(error) => Completion::Throw(Slot::new_string(error)),
+        }
+    }
+
+    pub fn op_bit_and(&self, other: &Self) -> Slot {
+        match self.op_bitwise_internal(other, |x, y| (x & y) as i64) {
             Some(slot) => slot,
             None => Slot::UNDEFINED, // TODO: error
         }
     }
 
-    pub fn op_sub(&self, other: &Self) -> Slot {
-        match self.op_sub_internal(other) {
+    pub fn op_bit_or(&self, other: &Self) -> Slot {
+        match self.op_bitwise_internal(other, |x, y| (x | y) as i64) {
             Some(slot) => slot,
             None => Slot::UNDEFINED, // TODO: error
         }
     }
 
-    pub fn op_mul(&self, other: &Self) -> Slot {
-        match self.op_mul_internal(other) {
+    pub fn op_bit_xor(&self, other: &Self) -> Slot {
+        match self.op_bitwise_internal(other, |x, y| (x ^ y) as i64) {
             Some(slot) => slot,
             None => Slot::UNDEFINED, // TODO: error
         }
     }
 
-    pub fn op_div(&self, other: &Self) -> Slot {
-        unimplemented!("division")
+    pub fn op_left_shift(&self, other: &Self) -> Slot {
+        match self.op_bitwise_internal(other, |x, y| x.wrapping_shl(y as u32 & 31) as i64) {
+            Some(slot) => slot,
+            None => Slot::UNDEFINED, // TODO: error
+        }
     }
 
-    pub fn op_modulo(&self, other: &Self) -> Slot {
-        match self.op_modulo_internal(other) {
+    pub fn op_right_shift(&self, other: &Self) -> Slot {
+        match self.op_bitwise_internal(other, |x, y| (x >> (y as u32 & 31)) as i64) {
             Some(slot) => slot,
             None => Slot::UNDEFINED, // TODO: error
         }
     }
 
-    pub fn op_pow(&self, other: &Self) -> Slot {
-        unimplemented!("power")
+    pub fn op_unsigned_right_shift(&self, other: &Self) -> Slot {
+        match self.op_bitwise_internal(other, |x, y| ((x as u32) >> (y as u32 & 31)) as i64) {
+            Some(slot) => slot,
+            None => Slot::UNDEFINED, // TODO: error
+        }
     }
 
     pub fn op_neg(&self) -> Slot {
@@ -47,10 +91,14 @@ impl Slot {
             },
             SlotTag::Reference => {
                 match self.unwrap_reference() {
-                    Reference::Number(number) => Slot{reference: ManuallyDrop::new(SlotReference::new(number.op_neg().into()))},
+                    Reference::Number(number) => Slot::from_number(number.op_neg()),
+                    Reference::Bigint(bigint) => Slot{reference: ManuallyDrop::new(SlotReference::new(bigint.op_neg().into()))},
                     _ => unimplemented!("wrapped object"),
                 }
             }
+            SlotTag::Pointer => {
+                self.unwrap_pointer().op_neg()
+            }
             _ => Slot::UNDEFINED, // TODO: error
         }
     }
@@ -58,7 +106,7 @@ impl Slot {
     fn op_strict_equal_internal(&self, other: &Self) -> bool {
         // Fast path
         if unsafe{self.raw == other.raw} {
-            return true
+            return !self.is_nan()
         }
 
         // Check if heap
@@ -140,7 +188,7 @@ impl Slot {
                 self.unwrap_pointer().op_less_than_internal(other)
             },
             (_, SlotTag::Pointer) => {
-                other.unwrap_pointer().op_less_than_internal(self).map(|x| !x)
+                self.op_less_than_internal(other.unwrap_pointer())
             },
             (SlotTag::Constant, SlotTag::Constant) => {
                 self.unwrap_constant().op_less_than_internal(&other.unwrap_constant())
@@ -159,21 +207,15 @@ impl Slot {
                     (Reference::String(left), Reference::String(right)) => {
                         Some(*left < *right)
                     }
+                    (Reference::Bigint(left), Reference::Bigint(right)) => {
+                        Some(left.op_less_than_internal(right))
+                    }
                     _ => None,
                 }
             }
 
-            (SlotTag::Reference, SlotTag::Integer) => {
-                match self.unwrap_reference() {
-                    Reference::Number(number) => number.op_less_than_internal_integer(&other.unwrap_integer()),
-                    _ => None
-                }
-            }  
-            (SlotTag::Integer, SlotTag::Reference) => {
-                match other.unwrap_reference() {
-                    Reference::Number(number) => unimplemented!("wrapped number object"),
-                    _ => None
-                }
+            (SlotTag::Reference, SlotTag::Integer) | (SlotTag::Integer, SlotTag::Reference) => {
+                self.to_number()?.op_less_than_internal(&other.to_number()?)
             }
             (SlotTag::Reference, SlotTag::Constant) => {
                 match self.unwrap_reference() {
@@ -199,10 +241,10 @@ impl Slot {
     pub fn op_less_than_or_equal_internal(&self, other: &Self) -> Option<bool> {
         match (self.get_tag(), other.get_tag()) {
             (SlotTag::Pointer, _) => {
-                self.unwrap_pointer().op_less_than_internal(other)
+                self.unwrap_pointer().op_less_than_or_equal_internal(other)
             },
             (_, SlotTag::Pointer) => {
-                other.unwrap_pointer().op_less_than_internal(self).map(|x| !x)
+                self.op_less_than_or_equal_internal(other.unwrap_pointer())
             },
             (SlotTag::Constant, SlotTag::Constant) => {
                 unimplemented!("comparison of constants")
@@ -217,11 +259,7 @@ impl Slot {
             (SlotTag::Reference, SlotTag::Reference) => {
                 match (self.unwrap_reference(), other.unwrap_reference()) {
                     (Reference::Number(left), Reference::Number(right)) => {
-                        if left.op_strict_equal_internal(right) {
-                            return Some(true)
-                        }
-
-                        left.op_less_than_internal(right)
+                        left.op_less_than_or_equal_internal(right)
                     }
                     (Reference::Constant(left), Reference::Constant(right)) => {
                         if left.op_strict_equal_internal(right) {
@@ -233,22 +271,16 @@ impl Slot {
                     (Reference::String(left), Reference::String(right)) => {
                         Some(*left <= *right)
                     }
+                    (Reference::Bigint(left), Reference::Bigint(right)) => {
+                        Some(!right.op_less_than_internal(left))
+                    }
                 
                     _ => None,
                 }
             }
 
-            (SlotTag::Reference, SlotTag::Integer) => {
-                match self.unwrap_reference() {
-                    Reference::Number(number) => number.op_less_than_internal_integer(&other.unwrap_integer()),
-                    _ => None
-                }
-            }
-            (SlotTag::Integer, SlotTag::Reference) => {
-                match other.unwrap_reference() {
-                    Reference::Number(number) => unimplemented!("wrapped number object"),
-                    _ => None
-                }
+            (SlotTag::Reference, SlotTag::Integer) | (SlotTag::Integer, SlotTag::Reference) => {
+                self.to_number()?.op_less_than_or_equal_internal(&other.to_number()?)
             }
             (SlotTag::Reference, SlotTag::Constant) => {
                 match self.unwrap_reference() {
@@ -288,8 +320,9 @@ impl Slot {
         }
     }
 
+    // x > y is y < x, and x >= y is y <= x
     pub fn op_greater_than(&self, other: &Self) -> Slot {
-        match other.op_less_than_or_equal_internal(&self) {
+        match other.op_less_than_internal(&self) {
             Some(true) => Slot::TRUE,
             Some(false) => Slot::FALSE,
             None => Slot::UNDEFINED, // TODO: error
@@ -297,14 +330,14 @@ impl Slot {
     }
 
     pub fn op_greater_than_or_equal(&self, other: &Self) -> Slot {
-        match other.op_less_than_internal(&self) {
+        match other.op_less_than_or_equal_internal(&self) {
             Some(true) => Slot::TRUE,
             Some(false) => Slot::FALSE,
             None => Slot::UNDEFINED, // TODO: error
         }
     }
 
-    pub fn op_add_internal(&self, other: &Self) -> Option<Slot> {
+    pub fn op_add_internal(&self, other: &Self) -> Result<Slot, &'static str> {
         match (self.get_tag(), other.get_tag()) {
             (SlotTag::Pointer, _) => {
                 self.unwrap_pointer().op_add_internal(other)
@@ -313,19 +346,18 @@ impl Slot {
                 other.unwrap_pointer().op_add_internal(self)
             },
             (SlotTag::Constant, SlotTag::Constant) => {
-                self.unwrap_constant().op_add_internal(&other.unwrap_constant()).map(Into::into)
+                self.unwrap_constant().op_add_internal(&other.unwrap_constant()).map(Into::into).ok_or(INVALID_OPERANDS)
             }
             (SlotTag::Integer, SlotTag::Integer) => {
-                self.unwrap_integer().op_add_internal(&other.unwrap_integer()).map(Into::into)
+                match self.unwrap_integer().op_add_internal(&other.unwrap_integer()) {
+                    Some(result) => Ok(result.into()),
+                    None => self.op_numeric_internal(other, Number::op_add_internal, |x, y| Ok(x.op_add_internal(y))),
+                }
             }
             (SlotTag::Reference, SlotTag::Reference) => {
                 match (self.unwrap_reference(), other.unwrap_reference()) {
-                    (Reference::Number(left), Reference::Number(right)) => {
-                        let result = left.op_add_internal(right)?.into();
-                        Some(Slot{ reference: ManuallyDrop::new(SlotReference::new(result)) })
-                    }
                     (Reference::Constant(left), Reference::Constant(right)) => {
-                        left.op_add_internal(right).map(Into::into)
+                        left.op_add_internal(right).map(Into::into).ok_or(INVALID_OPERANDS)
                     }
                     (Reference::String(left), Reference::String(right)) => {
                         unimplemented!("string concatenation")
@@ -335,136 +367,256 @@ impl Slot {
                         string.into()
                         */
                     }
-                    _ => None,
+                    _ => self.op_numeric_internal(other, Number::op_add_internal, |x, y| Ok(x.op_add_internal(y))),
                 }
             }
 
-            (SlotTag::Reference, SlotTag::Integer) => {
-                match self.unwrap_reference() {
-                    Reference::Number(number) => {
-                        let result = number.op_add_internal_integer(&other.unwrap_integer())?.into();
-                        Some(Slot{ reference: ManuallyDrop::new(SlotReference::new(result)) })
-                    }
-                    _ => None
-                }
-            }
-            (SlotTag::Integer, SlotTag::Reference) => {
-                match other.unwrap_reference() {
-                    Reference::Number(number) => unimplemented!("wrapped number object"),
-                    _ => None
-                }
+            (SlotTag::Reference, SlotTag::Integer) | (SlotTag::Integer, SlotTag::Reference) => {
+                self.op_numeric_internal(other, Number::op_add_internal, |x, y| Ok(x.op_add_internal(y)))
             }
             (SlotTag::Reference, SlotTag::Constant) => {
                 match self.unwrap_reference() {
-                    Reference::Constant(constant) => constant.op_add_internal(&other.unwrap_constant()).map(Into::into),
-                    _ => None
+                    Reference::Constant(constant) => constant.op_add_internal(&other.unwrap_constant()).map(Into::into).ok_or(INVALID_OPERANDS),
+                    _ => self.op_numeric_internal(other, Number::op_add_internal, |x, y| Ok(x.op_add_internal(y))),
                 }
             }
             (SlotTag::Constant, SlotTag::Reference) => {
                 match other.unwrap_reference() {
-                    Reference::Constant(constant) => self.unwrap_constant().op_add_internal(constant).map(Into::into),
-                    _ => None 
+                    Reference::Constant(constant) => self.unwrap_constant().op_add_internal(constant).map(Into::into).ok_or(INVALID_OPERANDS),
+                    _ => self.op_numeric_internal(other, Number::op_add_internal, |x, y| Ok(x.op_add_internal(y))),
                 }
             }
 
             (SlotTag::Integer, SlotTag::Constant) => {
-                None 
+                Err(INVALID_OPERANDS)
             }
             (SlotTag::Constant, SlotTag::Integer) => {
-                None
+                Err(INVALID_OPERANDS)
             }
         }
     }
 
-    fn op_sub_internal(&self, other: &Self) -> Option<Slot> {
+    fn op_sub_internal(&self, other: &Self) -> Result<Slot, &'static str> {
         match (self.get_tag(), other.get_tag()) {
             (SlotTag::Pointer, _) => {
                 self.unwrap_pointer().op_sub_internal(other)
             },
             (_, SlotTag::Pointer) => {
-                other.unwrap_pointer().op_sub_internal(self)
+                self.op_sub_internal(other.unwrap_pointer())
             },
             (SlotTag::Constant, SlotTag::Constant) => {
-                self.unwrap_constant().op_sub_internal(&other.unwrap_constant()).map(Into::into)
+                self.unwrap_constant().op_sub_internal(&other.unwrap_constant()).map(Into::into).ok_or(INVALID_OPERANDS)
             }
             (SlotTag::Integer, SlotTag::Integer) => {
-                self.unwrap_integer().op_sub_internal(&other.unwrap_integer()).map(Into::into)
+                match self.unwrap_integer().op_sub_internal(&other.unwrap_integer()) {
+                    Some(result) => Ok(result.into()),
+                    None => self.op_numeric_internal(other, Number::op_sub_internal, |x, y| Ok(x.op_sub_internal(y))),
+                }
             }
             (SlotTag::Reference, SlotTag::Reference) => {
                 match (self.unwrap_reference(), other.unwrap_reference()) {
-                    (Reference::Number(left), Reference::Number(right)) => {
-                        let result = left.op_sub_internal(right)?.into();
-                        Some(Slot{ reference: ManuallyDrop::new(SlotReference::new(result)) })
-                    }
                     (Reference::Constant(left), Reference::Constant(right)) => {
-                        left.op_sub_internal(right).map(Into::into)
+                        left.op_sub_internal(right).map(Into::into).ok_or(INVALID_OPERANDS)
                     }
-                    _ => None,
+                    _ => self.op_numeric_internal(other, Number::op_sub_internal, |x, y| Ok(x.op_sub_internal(y))),
                 }
             }
 
-            (SlotTag::Reference, SlotTag::Integer) => {
-                match self.unwrap_reference() {
-                    Reference::Number(number) => {
-                        let result = number.op_add_internal_integer(&other.unwrap_integer())?.into();
-                        Some(Slot{ reference: ManuallyDrop::new(SlotReference::new(result)) })
-                    }
-                    _ => None
-                }
-            }
-            (SlotTag::Integer, SlotTag::Reference) => {
-                match other.unwrap_reference() {
-                    Reference::Number(number) => unimplemented!("wrapped number object"),
-                    _ => None
-                }
+            (SlotTag::Reference, SlotTag::Integer) | (SlotTag::Integer, SlotTag::Reference) => {
+                self.op_numeric_internal(other, Number::op_sub_internal, |x, y| Ok(x.op_sub_internal(y)))
             }
             (SlotTag::Reference, SlotTag::Constant) => {
                 match self.unwrap_reference() {
-                    Reference::Constant(constant) => constant.op_add_internal(&other.unwrap_constant()).map(Into::into),
-                    _ => None
+                    Reference::Constant(constant) => constant.op_sub_internal(&other.unwrap_constant()).map(Into::into).ok_or(INVALID_OPERANDS),
+                    _ => self.op_numeric_internal(other, Number::op_sub_internal, |x, y| Ok(x.op_sub_internal(y))),
                 }
             }
             (SlotTag::Constant, SlotTag::Reference) => {
                 match other.unwrap_reference() {
-                    Reference::Constant(constant) => self.unwrap_constant().op_add_internal(constant).map(Into::into),
-                    _ => None 
+                    Reference::Constant(constant) => self.unwrap_constant().op_sub_internal(constant).map(Into::into).ok_or(INVALID_OPERANDS),
+                    _ => self.op_numeric_internal(other, Number::op_sub_internal, |x, y| Ok(x.op_sub_internal(y))),
                 }
             }
 
             (SlotTag::Integer, SlotTag::Constant) => {
-                None 
+                Err(INVALID_OPERANDS)
             }
             (SlotTag::Constant, SlotTag::Integer) => {
-                None
+                Err(INVALID_OPERANDS)
             } 
         }
     }
 
-    fn op_mul_internal(&self, other: &Self) -> Option<Slot> {
+    fn op_mul_internal(&self, other: &Self) -> Result<Slot, &'static str> {
         match (self.get_tag(), other.get_tag()) {
+            (SlotTag::Pointer, _) => {
+                self.unwrap_pointer().op_mul_internal(other)
+            },
+            (_, SlotTag::Pointer) => {
+                self.op_mul_internal(other.unwrap_pointer())
+            },
             (SlotTag::Integer, SlotTag::Integer) => {
                 let (lo, hi) = self.unwrap_integer().overflowing_mul(other.unwrap_integer());
 
-                if hi == 0 {
-                    Integer::new(lo as i64).map(Into::into)
-                } else {
-                    unimplemented!("integer mul overflow")
-                    //Some(Reference::Number(Number::new(lo as i64, hi as u64)).into())
+                // the product fits if the high half is the sign extension of the low half
+                match Integer::new(lo as i64) {
+                    Some(result) if hi == lo >> (isize::BITS - 1) => Ok(result.into()),
+                    _ => self.op_numeric_internal(other, Number::op_mul_internal, |x, y| Ok(x.op_mul_internal(y))),
                 }
             },
-            _ => None,
+            _ => self.op_numeric_internal(other, Number::op_mul_internal, |x, y| Ok(x.op_mul_internal(y))),
+        }
+    }
+
+    fn op_div_internal(&self, other: &Self) -> Result<Slot, &'static str> {
+        match (self.get_tag(), other.get_tag()) {
+            (SlotTag::Pointer, _) => {
+                self.unwrap_pointer().op_div_internal(other)
+            },
+            (_, SlotTag::Pointer) => {
+                self.op_div_internal(other.unwrap_pointer())
+            },
+            (SlotTag::Integer, SlotTag::Integer) => {
+                let x = self.unwrap_integer().unwrap();
+                let y = other.unwrap_integer().unwrap();
+
+                // exact quotients stay integers
+                if y != 0 && x % y == 0 {
+                    if let Some(result) = Integer::new((x / y) as i64) {
+                        return Ok(result.into())
+                    }
+                }
+
+                self.op_numeric_internal(other, Number::op_div_internal, |x, y| x.op_div_internal(y).ok_or(DIVISION_BY_ZERO))
+            },
+            _ => self.op_numeric_internal(other, Number::op_div_internal, |x, y| x.op_div_internal(y).ok_or(DIVISION_BY_ZERO)),
         }
     }
 
-    fn op_modulo_internal(&self, other: &Self) -> Option<Slot> {
+    fn op_modulo_internal(&self, other: &Self) -> Result<Slot, &'static str> {
         match (self.get_tag(), other.get_tag()) {
+            (SlotTag::Pointer, _) => {
+                self.unwrap_pointer().op_modulo_internal(other)
+            },
+            (_, SlotTag::Pointer) => {
+                self.op_modulo_internal(other.unwrap_pointer())
+            },
             (SlotTag::Integer, SlotTag::Integer) => {
-                Some(Slot::new_integer((self.unwrap_integer().unwrap()%other.unwrap_integer().unwrap()).try_into().unwrap()))
+                let y = other.unwrap_integer().unwrap();
+
+                // x % 0 is NaN
+                if y == 0 {
+                    return Ok(Slot::from_number(NAN))
+                }
+
+                Integer::new((self.unwrap_integer().unwrap() % y) as i64).map(Into::into).ok_or(OUT_OF_RANGE)
             },
+            _ => self.op_numeric_internal(other, Number::op_modulo_internal, |x, y| x.op_modulo_internal(y).ok_or(DIVISION_BY_ZERO)),
+        }
+    }
+
+    fn op_pow_internal(&self, other: &Self) -> Result<Slot, &'static str> {
+        match (self.get_tag(), other.get_tag()) {
+            (SlotTag::Pointer, _) => {
+                self.unwrap_pointer().op_pow_internal(other)
+            },
+            (_, SlotTag::Pointer) => {
+                self.op_pow_internal(other.unwrap_pointer())
+            },
+            (SlotTag::Integer, SlotTag::Integer) => {
+                // negative exponents and overflowing results are not integers
+                let result = u32::try_from(other.unwrap_integer().unwrap()).ok()
+                    .and_then(|exponent| (self.unwrap_integer().unwrap() as i64).checked_pow(exponent))
+                    .and_then(Integer::new);
+
+                match result {
+                    Some(result) => Ok(result.into()),
+                    None => self.op_numeric_internal(other, Number::op_pow_internal, |x, y| x.op_pow_internal(y).ok_or(INVALID_EXPONENT)),
+                }
+            },
+            _ => self.op_numeric_internal(other, Number::op_pow_internal, |x, y| x.op_pow_internal(y).ok_or(INVALID_EXPONENT)),
+        }
+    }
+
+    // the bitwise operators work on the operands truncated to 32 bits(ToInt32)
+    fn op_bitwise_internal(&self, other: &Self, op: fn(i32, i32) -> i64) -> Option<Slot> {
+        let x = self.to_int32()?;
+        let y = other.to_int32()?;
+        Integer::new(op(x, y)).map(Into::into)
+    }
+
+    fn to_int32(&self) -> Option<i32> {
+        match self.get_tag() {
+            SlotTag::Integer => Some(self.unwrap_integer().unwrap() as i32),
+            _ => Some(self.to_number()?.to_int32()),
+        }
+    }
+
+    // the value of an integer or a number, None for the other values
+    fn to_number(&self) -> Option<Number> {
+        match self.get_tag() {
+            SlotTag::Pointer => self.unwrap_pointer().to_number(),
+            SlotTag::Integer => Some(self.unwrap_integer().into()),
+            SlotTag::Reference => {
+                match self.unwrap_reference() {
+                    Reference::Number(number) => Some(*number),
+                    _ => None,
+                }
+            }
+            SlotTag::Constant => None,
+        }
+    }
+
+    // numbers with an integer value are stored as integers when they fit
+    pub(crate) fn from_number(number: Number) -> Slot {
+        if number.is_integral() {
+            if let Some(integer) = Integer::new(number.integer_part()) {
+                return integer.into()
+            }
+        }
+
+        Slot{ reference: ManuallyDrop::new(SlotReference::new(number.into())) }
+    }
+
+    // NaN is the only value that is not strictly equal to itself
+    fn is_nan(&self) -> bool {
+        match self.get_tag() {
+            SlotTag::Pointer => self.unwrap_pointer().is_nan(),
+            SlotTag::Reference if self.is_uninitialized() => false,
+            SlotTag::Reference => matches!(self.unwrap_reference(), Reference::Number(number) if number.is_nan()),
+            _ => false,
+        }
+    }
+
+    // the value of a bigint, None for the other values
+    fn to_bigint(&self) -> Option<Bigint> {
+        match self.get_tag() {
+            SlotTag::Pointer => self.unwrap_pointer().to_bigint(),
+            SlotTag::Reference => {
+                match self.unwrap_reference() {
+                    Reference::Bigint(bigint) => Some(bigint.clone()),
+                    _ => None,
+                }
+            }
             _ => None,
         }
     }
 
+    // arithmetic on the operands as numbers, for the operands that are not
+    // both integers or whose integer result does not fit in an integer,
+    // or as bigints when both operands are bigints
+    fn op_numeric_internal(&self, other: &Self, op: fn(&Number, &Number) -> Option<Number>, bigint_op: fn(&Bigint, &Bigint) -> Result<Bigint, &'static str>) -> Result<Slot, &'static str> {
+        if let (Some(x), Some(y)) = (self.to_number(), other.to_number()) {
+            return op(&x, &y).map(Slot::from_number).ok_or(OUT_OF_RANGE)
+        }
+
+        match (self.to_bigint(), other.to_bigint()) {
+            (Some(x), Some(y)) => bigint_op(&x, &y).map(|bigint| Slot{ reference: ManuallyDrop::new(SlotReference::new(bigint.into())) }),
+            (Some(_), None) | (None, Some(_)) => Err(MIXED_BIGINT),
+            (None, None) => Err(INVALID_OPERANDS),
+        }
+    }
+
     pub fn op_not(&self) -> Slot {
         match self.get_tag() {
             SlotTag::Pointer => {
@@ -549,4 +701,72 @@ impl Slot {
             _ => None, // TODO: wrapped objects
         }
     }
+
+    // Adds a data property, for the assignments to a property the object does not have.
+    // None for the values that cannot hold properties.
+    pub fn insert_property(&mut self, name: &Rc<str>, value: Slot) -> Option<()> {
+        match self.get_tag() {
+            SlotTag::Reference => {
+                match self.unwrap_mut_reference() {
+                    Reference::Object(object) => {
+                        object.properties.push(Property::data(name.clone(), value));
+                        Some(())
+                    }
+                    _ => None,
+                }
+            },
+            SlotTag::Pointer => {
+                self.unwrap_mut_pointer().insert_property(name, value)
+            }
+            _ => None, // TODO: wrapped objects
+        }
+    }
+
+    // Freezes an array, so that its elements cannot be assigned anymore.
+    // None for the values that cannot be frozen.
+    pub fn freeze(&mut self) -> Option<()> {
+        match self.get_tag() {
+            SlotTag::Reference => {
+                match self.unwrap_mut_reference() {
+                    Reference::Array(array) => {
+                        array.frozen = true;
+                        Some(())
+                    }
+                    _ => None,
+                }
+            },
+            SlotTag::Pointer => {
+                self.unwrap_mut_pointer().freeze()
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        match self.get_tag() {
+            SlotTag::Reference => {
+                match self.unwrap_reference() {
+                    Reference::Array(array) => array.frozen,
+                    _ => false,
+                }
+            },
+            SlotTag::Pointer => self.unwrap_pointer().is_frozen(),
+            _ => false,
+        }
+    }
+
+    // Keys of the own properties, in the insertion order.
+    // Empty for non-object values.
+    pub fn property_keys(&self) -> Vec<Rc<str>> {
+        match self.get_tag() {
+            SlotTag::Reference => {
+                match self.unwrap_reference() {
+                    Reference::Object(object) => object.properties.iter().map(|property| property.key.clone()).collect(),
+                    _ => Vec::new(),
+                }
+            },
+            SlotTag::Pointer => self.unwrap_pointer().property_keys(),
+            _ => Vec::new(),
+        }
+    }
 }
\ No newline at end of file
@@ -3,13 +3,14 @@ use std::{any::Any, ops::{Index, IndexMut}, ptr, cell::{Ref, RefCell}, mem::Manu
 
 use crate::{number::NUMBER_ZERO, object::Property, slot::Slot, function::Function, error::Error, completion::Completion};
 
-use super::{number::Number, object::Object, constant::Constant, array::Array};
+use super::{bigint::Bigint, number::Number, object::Object, constant::Constant, array::Array};
 
 #[derive(Clone)]
 pub enum Reference {
     Object(Object),
     // Integer(Integer),
     Number(Number),
+    Bigint(Bigint),
     Constant(Constant),
     String(Rc<str>),
     Array(Array),
@@ -38,6 +39,7 @@ impl Debug for Reference {
                 write!(f, "[Object]")
             }
             Reference::Number(number) => write!(f, "Number({:?})", number),
+            Reference::Bigint(bigint) => write!(f, "Bigint({})", bigint.to_string()),
             Reference::Constant(constant) => write!(f, "Constant({:?})", constant),
             Reference::String(string) => write!(f, "String({:?})", string),
             Reference::Array(array) => write!(f, "[Array]"),
@@ -52,8 +54,9 @@ impl Debug for Reference {
 impl Reference {
     pub fn is_falsy(&self) -> bool {
         match self {
-            Reference::Object(object) => object.is_empty(),
-            Reference::Number(number) => number == &NUMBER_ZERO,
+            // objects are truthy even when empty
+            Reference::Number(number) => number == &NUMBER_ZERO || number.is_nan(),
+            Reference::Bigint(bigint) => bigint.is_zero(),
             Reference::Constant(constant) => constant.is_falsy(),
             Reference::String(string) => string.is_empty(),
             _ => false,
@@ -70,6 +73,7 @@ impl Reference {
     pub(crate) fn op_strict_equal_internal(&self, other: &Self) -> bool {
         match (self, other) {
             (Reference::Number(number), Reference::Number(other)) => number.op_strict_equal_internal(other),
+            (Reference::Bigint(bigint), Reference::Bigint(other)) => bigint.op_strict_equal_internal(other),
             (Reference::Constant(constant), Reference::Constant(other)) => constant.op_strict_equal_internal(other),
             (Reference::String(string), Reference::String(other)) => *string == *other,
             _ => ptr::addr_of!(self) == ptr::addr_of!(other),
@@ -82,6 +86,7 @@ impl ToString for Reference {
         match self {
             Reference::Object(object) => unimplemented!("object to string"), 
             Reference::Number(number) => number.to_string(),
+            Reference::Bigint(bigint) => bigint.to_string(),
             Reference::Constant(constant) => constant.to_string(),
             Reference::String(string) => string.to_string(),
             Reference::Array(array) => unimplemented!("array to string"), 
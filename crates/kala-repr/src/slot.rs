@@ -1,7 +1,7 @@
 use core::{panic};
 use std::{mem::{ManuallyDrop, transmute}, rc::{Rc, Weak}, cell::{Cell, RefCell}, any::Any, ops::{Index, IndexMut}, fmt::{Debug, LowerHex}};
 
-use crate::{array::Array, object::{Object, Property}, number::Number, function::{Function, Stack, Frame}, completion::Completion};
+use crate::{array::Array, object::{Object, Property}, number::Number, bigint::Bigint, function::{Function, Stack, Frame}, completion::Completion};
 
 use super::{reference::Reference, integer::Integer, constant::Constant};
 
@@ -90,7 +90,7 @@ impl ToString for Slot {
             SlotTag::Reference => unsafe { self.reference.0.as_ptr().as_ref().unwrap().to_string() },
             SlotTag::Integer => unsafe { self.integer.0.to_string() },
             SlotTag::Constant => unsafe { self.constant.0.to_string() },
-            _ => unreachable!(),
+            SlotTag::Pointer => self.unwrap_pointer().to_string(),
         }
     }
 }
@@ -203,7 +203,7 @@ impl Slot {
 
     pub fn new_array(array: Vec<Slot>) -> Self {
         Self {
-            reference: ManuallyDrop::new(SlotReference(Rc::new(Cell::new(Reference::Array(Array(array)))))),
+            reference: ManuallyDrop::new(SlotReference(Rc::new(Cell::new(Reference::Array(Array { elements: array, frozen: false }))))),
         }
     }
 
@@ -221,12 +221,24 @@ impl Slot {
         }
     }
 
+    // i + f / 10^19, the fraction in the 10^-19 units of the decimal literals
     pub fn new_number(i: i64, f: u64) -> Self {
         Self {
             reference: ManuallyDrop::new(SlotReference(Rc::new(Cell::new(Reference::Number(Number::new(i, f)))))),
         }
     }
 
+    // rounded to the nearest fixed point value, None when it is out of the range of a number
+    pub fn new_float(f: f64) -> Option<Self> {
+        Number::from_f64(f).map(Self::from_number)
+    }
+
+    pub fn new_bigint(negative: bool, limbs: &[u64]) -> Self {
+        Self {
+            reference: ManuallyDrop::new(SlotReference(Rc::new(Cell::new(Reference::Bigint(Bigint::new(negative, limbs)))))),
+        }
+    }
+
     pub fn new_native_function(name: impl Into<Rc<str>>, function: Rc<RefCell<dyn FnMut(&mut [Slot]) -> Completion>>) -> Self {
         Self {
             reference: ManuallyDrop::new(SlotReference(Rc::new(Cell::new(Reference::NativeFunction(name.into(), function))))),
@@ -295,6 +307,7 @@ impl Slot {
         }
 
         match self.get_tag() {
+            SlotTag::Pointer => self.unwrap_pointer().call(frame, arguments),
             SlotTag::Reference => match self.unwrap_reference() {
                 Reference::Function(function) => (function.function)(frame, arguments.clone()),
                 Reference::NativeFunction(_, function) => function.borrow_mut()(&mut arguments[..]),
@@ -312,11 +325,7 @@ impl Slot {
         match self.get_tag() {
             SlotTag::Pointer => self.unwrap_pointer().is_nullish(),
             SlotTag::Constant => unsafe { self.constant.0.is_nullish() },
-            SlotTag::Reference => unsafe { match self.unwrap_reference() {
-                Reference::Constant(constant) => constant.is_nullish(),
-                Reference::Object(object) => object.is_empty(),
-                _ => false,
-            } },
+            SlotTag::Reference => self.unwrap_reference().is_nullish(),
             SlotTag::Integer => false,
         }
     }
@@ -327,10 +336,10 @@ impl Slot {
         }
 
         match self.get_tag() {
+            SlotTag::Pointer => self.unwrap_pointer().is_falsy(),
             SlotTag::Constant => unsafe { self.constant.0.is_falsy() },
-            SlotTag::Reference => !self.unwrap_reference().is_falsy(),
-            SlotTag::Integer => self.unwrap_integer() != Integer(0),
-            _ => true,
+            SlotTag::Reference => self.unwrap_reference().is_falsy(),
+            SlotTag::Integer => self.unwrap_integer().unwrap() == 0,
         }
     }
 
@@ -351,6 +360,7 @@ impl Slot {
                 Reference::Array(array) => unimplemented!("wrapped array object"),
                 Reference::Constant(constant) => unimplemented!("wrapped constant object"),
                 Reference::Number(number) => unimplemented!("wrapped number object"),
+                Reference::Bigint(bigint) => unimplemented!("wrapped bigint object"),
                 Reference::String(string) => unimplemented!("wrapped string object"),
                 Reference::Function(function) => unimplemented!("wrapped function object"),
                 Reference::Error(error) => unimplemented!("wrapped error object"),
@@ -373,6 +383,7 @@ impl Slot {
                 Reference::Array(array) => unimplemented!("wrapped array object"),
                 Reference::Constant(constant) => unimplemented!("wrapped constant object"),
                 Reference::Number(number) => unimplemented!("wrapped number object"),
+                Reference::Bigint(bigint) => unimplemented!("wrapped bigint object"),
                 Reference::String(string) => unimplemented!("wrapped string object"),
                 Reference::Function(function) => unimplemented!("wrapped function object"),
                 Reference::Error(error) => unimplemented!("wrapped error object"),
@@ -384,6 +395,14 @@ impl Slot {
         }
     }
 
+    // the value of a variable, read through the heap cell of an escaping variable
+    pub fn get(&self) -> Slot {
+        match self.get_tag() {
+            SlotTag::Pointer => self.unwrap_pointer().clone(),
+            _ => self.clone(),
+        }
+    }
+
     pub fn set(&mut self, slot: Slot) {
         match self.get_tag() {
             SlotTag::Pointer => *self.unwrap_mut_pointer() = slot,
@@ -0,0 +1,273 @@
+use std::rc::Rc;
+
+use jessie_ast::*;
+use utils::Map;
+
+// Constant folding of the arithmetic scripts made by the generator, evaluating the AST directly with the JavaScript
+// semantics, as the expected results of eval_script.
+// Folding gives up with None outside of the subset: values other than integers and booleans(including the inexact
+// quotients and the negative powers), integers out of the exactly representable range, and NaN.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Folded {
+    Integer(i64),
+    Boolean(bool),
+}
+
+#[derive(Clone)]
+enum Value {
+    Integer(i64),
+    Boolean(bool),
+    Function(Rc<Function>),
+}
+
+enum Flow {
+    Normal,
+    Return(Value),
+}
+
+// 2^53, the integers above are not exact in JavaScript
+const MAX_SAFE: i64 = 1 << 53;
+
+// The generator declares every name once, so a flat map is enough for the variables
+struct Folder {
+    values: Map<Value>,
+}
+
+pub fn fold_script(script: &Script) -> Option<Folded> {
+    let mut folder = Folder { values: Map::default() };
+    let mut result = None;
+    for statement in script.statements.statements.iter() {
+        match statement {
+            Statement::ExprStatement(expr) => result = Some(folder.expr(expr)?),
+            _ => return None,
+        }
+    }
+    match result? {
+        Value::Integer(int) => Some(Folded::Integer(int)),
+        Value::Boolean(bool) => Some(Folded::Boolean(bool)),
+        Value::Function(_) => None,
+    }
+}
+
+fn integer(int: Option<i64>) -> Option<Value> {
+    int.filter(|int| int.abs() <= MAX_SAFE).map(Value::Integer)
+}
+
+impl Folder {
+    fn block(&mut self, block: &Block) -> Option<Flow> {
+        for statement in block.statements.iter() {
+            if let Flow::Return(value) = self.statement(statement)? {
+                return Some(Flow::Return(value))
+            }
+        }
+        Some(Flow::Normal)
+    }
+
+    fn statement(&mut self, statement: &Statement) -> Option<Flow> {
+        match statement {
+            Statement::LocalDeclaration(declaration) => {
+                let (Declaration::Const(declarations) | Declaration::Let(declarations)) = &**declaration else { return None };
+                for declaration in declarations.iter() {
+                    let Pattern::Variable(variable) = &declaration.pattern else { return None };
+                    let value = self.expr(declaration.value.as_ref()?)?;
+                    self.values.insert(variable.name.clone(), value);
+                }
+                Some(Flow::Normal)
+            },
+            Statement::IfStatement(statement) => {
+                if self.boolean(&statement.condition)? {
+                    self.block(&statement.consequent)
+                } else {
+                    match &statement.alternate {
+                        ElseArm::NoElse => Some(Flow::Normal),
+                        ElseArm::Else(block) => self.block(block),
+                        ElseArm::ElseIf(_) => None,
+                    }
+                }
+            },
+            Statement::Block(block) => self.block(block),
+            Statement::ExprStatement(expr) => self.expr(expr).map(|_| Flow::Normal),
+            Statement::Return(expr) => self.expr(expr).map(Flow::Return),
+            _ => None,
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) -> Option<Value> {
+        match expr {
+            Expr::DataLiteral(literal) => match &literal.data {
+                DataLiteral::Integer(int) => integer(Some(*int)),
+                DataLiteral::True => Some(Value::Boolean(true)),
+                DataLiteral::False => Some(Value::Boolean(false)),
+                _ => None,
+            },
+            Expr::Variable(variable) => self.values.get(&variable.name).cloned(),
+            Expr::ParenedExpr(expr) => self.expr(expr),
+            Expr::Function(function) => Some(Value::Function(Rc::new((**function).clone()))),
+            Expr::Assignment(assignment) => {
+                let Assignment(op, LValue::Variable(variable), value) = &**assignment else { return None };
+                let value = match op {
+                    AssignOp::Assign => self.expr(value)?,
+                    AssignOp::AssignOr | AssignOp::AssignAnd | AssignOp::AssignCoalesce => {
+                        let current = self.values.get(&variable.name).cloned()?;
+                        let assigns = match op {
+                            AssignOp::AssignOr => !truthy(&current)?,
+                            AssignOp::AssignAnd => truthy(&current)?,
+                            // integers and booleans are never nullish
+                            _ => false,
+                        };
+                        if !assigns {
+                            return Some(current)
+                        }
+                        self.expr(value)?
+                    },
+                    op => arithmetic(&compound_operator(op), self.integer_variable(&variable.name)?, self.integer(value)?)?,
+                };
+                self.values.insert(variable.name.clone(), value.clone());
+                Some(value)
+            },
+            Expr::CondExpr(cond) => {
+                let CondExpr(condition, consequent, alternate) = &**cond;
+                if self.boolean(condition)? { self.expr(consequent) } else { self.expr(alternate) }
+            },
+            Expr::BinaryExpr(binary) => self.binary(binary),
+            Expr::UnaryExpr(unary) => {
+                let mut value = self.expr(&unary.expr)?;
+                // the operator closest to the operand applies first
+                for op in unary.op.iter().rev() {
+                    value = match (op, value) {
+                        (UnaryOp::Not, value) => Value::Boolean(!truthy(&value)?),
+                        // -0 folds as 0, they are strictly equal
+                        (UnaryOp::Neg, Value::Integer(int)) => Value::Integer(-int),
+                        _ => return None,
+                    };
+                }
+                Some(value)
+            },
+            Expr::CallExpr(call) => {
+                let mut callee = self.expr(&call.expr)?;
+                for op in call.post_ops.iter() {
+                    let CallPostOp::Call(args) = op else { return None };
+                    let Value::Function(function) = callee else { return None };
+                    let args = args.iter().map(|arg| self.expr(arg)).collect::<Option<Vec<_>>>()?;
+                    callee = self.call(&function, args)?;
+                }
+                Some(callee)
+            },
+            _ => None,
+        }
+    }
+
+    fn call(&mut self, function: &Function, args: Vec<Value>) -> Option<Value> {
+        if args.len() != function.parameters.len() {
+            return None
+        }
+        for (parameter, arg) in function.parameters.iter().zip(args) {
+            let Pattern::Variable(variable) = parameter else { return None };
+            self.values.insert(variable.name.clone(), arg);
+        }
+        match &function.body {
+            ExprOrBlock::Expr(expr) => self.expr(expr),
+            ExprOrBlock::Block(block) => match self.block(block)? {
+                Flow::Return(value) => Some(value),
+                Flow::Normal => None,
+            },
+        }
+    }
+
+    fn binary(&mut self, binary: &BinaryExpr) -> Option<Value> {
+        let BinaryExpr(op, left, right) = binary;
+        let value = match op {
+            BinaryOp::And => {
+                let left = self.expr(left)?;
+                if truthy(&left)? { self.expr(right)? } else { left }
+            },
+            BinaryOp::Or => {
+                let left = self.expr(left)?;
+                if truthy(&left)? { left } else { self.expr(right)? }
+            },
+            // integers and booleans are never nullish
+            BinaryOp::Coalesce => self.expr(left)?,
+            BinaryOp::StrictEqual | BinaryOp::StrictNotEqual => {
+                let equal = match (self.expr(left)?, self.expr(right)?) {
+                    (Value::Integer(left), Value::Integer(right)) => left == right,
+                    (Value::Boolean(left), Value::Boolean(right)) => left == right,
+                    _ => return None,
+                };
+                Value::Boolean(equal == (*op == BinaryOp::StrictEqual))
+            },
+            _ => arithmetic(op, self.integer(left)?, self.integer(right)?)?,
+        };
+        Some(value)
+    }
+
+    fn integer(&mut self, expr: &Expr) -> Option<i64> {
+        match self.expr(expr)? {
+            Value::Integer(int) => Some(int),
+            _ => None,
+        }
+    }
+
+    fn integer_variable(&self, name: &Rc<str>) -> Option<i64> {
+        match self.values.get(name)? {
+            Value::Integer(int) => Some(*int),
+            _ => None,
+        }
+    }
+
+    fn boolean(&mut self, expr: &Expr) -> Option<bool> {
+        let value = self.expr(expr)?;
+        truthy(&value)
+    }
+}
+
+// x op= y folds as x = x op y
+fn compound_operator(op: &AssignOp) -> BinaryOp {
+    match op {
+        AssignOp::Assign | AssignOp::AssignOr | AssignOp::AssignAnd | AssignOp::AssignCoalesce => unreachable!("not a compound assignment"),
+        AssignOp::AssignAdd => BinaryOp::Add,
+        AssignOp::AssignSub => BinaryOp::Sub,
+        AssignOp::AssignMul => BinaryOp::Mul,
+        AssignOp::AssignDiv => BinaryOp::Div,
+        AssignOp::AssignMod => BinaryOp::Mod,
+        AssignOp::AssignExp => BinaryOp::Pow,
+        AssignOp::AssignLShift => BinaryOp::BitLeftShift,
+        AssignOp::AssignRShift => BinaryOp::BitRightShift,
+        AssignOp::AssignURShift => BinaryOp::BitUnsignedRightShift,
+        AssignOp::AssignBitAnd => BinaryOp::BitAnd,
+        AssignOp::AssignBitXor => BinaryOp::BitXor,
+        AssignOp::AssignBitOr => BinaryOp::BitOr,
+    }
+}
+
+// the bitwise operators work on the operands truncated to 32 bits
+fn arithmetic(op: &BinaryOp, left: i64, right: i64) -> Option<Value> {
+    let value = match op {
+        BinaryOp::LessThan => Value::Boolean(left < right),
+        BinaryOp::LessThanEqual => Value::Boolean(left <= right),
+        BinaryOp::GreaterThan => Value::Boolean(left > right),
+        BinaryOp::GreaterThanEqual => Value::Boolean(left >= right),
+        BinaryOp::Add => integer(left.checked_add(right))?,
+        BinaryOp::Sub => integer(left.checked_sub(right))?,
+        BinaryOp::Mul => integer(left.checked_mul(right))?,
+        BinaryOp::Div if right != 0 && left % right == 0 => integer(left.checked_div(right))?,
+        BinaryOp::Mod if right != 0 => Value::Integer(left % right),
+        BinaryOp::Pow if right >= 0 => integer(left.checked_pow(u32::try_from(right).ok()?))?,
+        BinaryOp::BitAnd => Value::Integer((left as i32 & right as i32) as i64),
+        BinaryOp::BitOr => Value::Integer((left as i32 | right as i32) as i64),
+        BinaryOp::BitXor => Value::Integer((left as i32 ^ right as i32) as i64),
+        BinaryOp::BitLeftShift => Value::Integer((left as i32).wrapping_shl(right as u32 & 31) as i64),
+        BinaryOp::BitRightShift => Value::Integer(((left as i32) >> (right as u32 & 31)) as i64),
+        BinaryOp::BitUnsignedRightShift => Value::Integer(((left as i32 as u32) >> (right as u32 & 31)) as i64),
+        _ => return None,
+    };
+    Some(value)
+}
+
+fn truthy(value: &Value) -> Option<bool> {
+    match value {
+        Value::Integer(int) => Some(*int != 0),
+        Value::Boolean(bool) => Some(*bool),
+        Value::Function(_) => Some(true),
+    }
+}
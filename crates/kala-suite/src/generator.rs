@@ -0,0 +1,752 @@
+use std::{rc::Rc, cell::RefCell};
+
+use jessie_ast::*;
+use jessie_parser::printer::{Precedence, precedence, binary_precedence, next_precedence, starts_with_brace};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+// Random jessie-ast programs for the property tests.
+// Every variable is declared before it is used and every name is declared only once, so that the printed programs
+// parse and pass the scope resolver. The arithmetic scripts stay within what both the interpreter and fold.rs
+// implement, for the differential tests against constant folding.
+// The programs are in the shape the parser produces for their printed source: concise arrow bodies are blocks with a
+// single return statement, and the operands that the printer would parenthesize are wrapped in ParenedExpr already.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Any,
+    Integer,
+    Boolean,
+    // integer to integer arrow function
+    Function,
+}
+
+struct Binding {
+    name: Rc<str>,
+    kind: Kind,
+    assignable: bool,
+}
+
+pub struct Generator {
+    rng: StdRng,
+    // variables in scope, innermost block last
+    scopes: Vec<Vec<Binding>>,
+    names: usize,
+    depth: usize,
+    max_depth: usize,
+    in_function: bool,
+    in_loop: bool,
+}
+
+const MEMBERS: [&str; 5] = ["a", "length", "x1", "$", "_b"];
+
+const KEYS: [&str; 6] = ["a", "b2", "if", "a-b", "", "é"];
+
+const CHARS: [char; 12] = ['a', 'Z', ' ', '\'', '"', '\\', '\n', '\t', '`', '$', '{', 'é'];
+
+const BINARY_OPS: [BinaryOp; 21] = [
+    BinaryOp::Or, BinaryOp::Coalesce, BinaryOp::And, BinaryOp::BitOr, BinaryOp::BitXor, BinaryOp::BitAnd,
+    BinaryOp::StrictEqual, BinaryOp::StrictNotEqual, BinaryOp::LessThan, BinaryOp::LessThanEqual, BinaryOp::GreaterThan, BinaryOp::GreaterThanEqual,
+    BinaryOp::BitLeftShift, BinaryOp::BitRightShift, BinaryOp::BitUnsignedRightShift,
+    BinaryOp::Add, BinaryOp::Sub, BinaryOp::Mul, BinaryOp::Div, BinaryOp::Mod, BinaryOp::Pow,
+];
+
+const UNARY_OPS: [UnaryOp; 5] = [UnaryOp::TypeOf, UnaryOp::Pos, UnaryOp::Neg, UnaryOp::BitNot, UnaryOp::Not];
+
+// the assignment operators the parser reads
+const ASSIGN_OPS: [AssignOp; 16] = [
+    AssignOp::Assign, AssignOp::AssignAdd, AssignOp::AssignSub, AssignOp::AssignMul, AssignOp::AssignDiv, AssignOp::AssignMod, AssignOp::AssignExp,
+    AssignOp::AssignLShift, AssignOp::AssignRShift, AssignOp::AssignURShift, AssignOp::AssignBitAnd, AssignOp::AssignBitXor, AssignOp::AssignBitOr,
+    AssignOp::AssignOr, AssignOp::AssignAnd, AssignOp::AssignCoalesce,
+];
+
+const BITWISE_OPS: [BinaryOp; 6] = [
+    BinaryOp::BitOr, BinaryOp::BitXor, BinaryOp::BitAnd, BinaryOp::BitLeftShift, BinaryOp::BitRightShift, BinaryOp::BitUnsignedRightShift,
+];
+
+impl Generator {
+    pub fn new(seed: u64) -> Self {
+        Generator {
+            rng: StdRng::seed_from_u64(seed),
+            scopes: vec![],
+            names: 0,
+            depth: 0,
+            max_depth: 3,
+            in_function: false,
+            in_loop: false,
+        }
+    }
+
+    pub fn with_max_depth(self, max_depth: usize) -> Self {
+        Generator { max_depth, ..self }
+    }
+
+    ///////////////////////
+    // Scoping
+
+    fn fresh(&mut self, prefix: &str) -> Rc<str> {
+        self.names += 1;
+        format!("{}{}", prefix, self.names).into()
+    }
+
+    fn declare(&mut self, name: Rc<str>, kind: Kind, assignable: bool) {
+        self.scopes.last_mut().unwrap().push(Binding { name, kind, assignable })
+    }
+
+    fn lookup(&mut self, kind: Kind, assignable: bool) -> Option<Rc<str>> {
+        let candidates = self.scopes.iter().flatten()
+            .filter(|binding| (kind == Kind::Any || binding.kind == kind) && (!assignable || binding.assignable))
+            .map(|binding| binding.name.clone())
+            .collect::<Vec<_>>();
+        candidates.choose(&mut self.rng).cloned()
+    }
+
+    fn variable(name: Rc<str>) -> Variable {
+        Variable::new(name)
+    }
+
+    fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn scoped<T>(&mut self, bindings: Vec<Binding>, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.scopes.push(bindings);
+        let result = f(self);
+        self.scopes.pop();
+        result
+    }
+
+    fn leaf(&self) -> bool {
+        self.depth >= self.max_depth
+    }
+
+    ///////////////////////
+    // Scripts
+
+    pub fn script(&mut self) -> Script {
+        let statements = self.scoped(vec![], |gen| {
+            let len = gen.rng.gen_range(1..6);
+            gen.statements(len)
+        });
+        Script { statements }
+    }
+
+    // (() => { ...; return value; })();
+    // The value is an integer or a boolean computed from constants, local variables and arrow functions.
+    pub fn arithmetic_script(&mut self) -> Script {
+        let body = self.scoped(vec![], |gen| {
+            let len = gen.rng.gen_range(1..6);
+            let mut statements = (0..len).map(|_| gen.arithmetic_statement()).collect::<Vec<_>>();
+            let kind = if gen.rng.gen_bool(0.7) { Kind::Integer } else { Kind::Boolean };
+            statements.push(Statement::Return(Box::new(gen.arithmetic_expr(kind))));
+            block(statements)
+        });
+        let function = arrow(Box::new([]), ExprOrBlock::Block(body));
+        let call = call_expr(Expr::Function(Box::new(function)), vec![CallPostOp::Call(Box::new([]))]);
+        Script { statements: block(vec![expr_statement(call)]) }
+    }
+
+    ///////////////////////
+    // Statements
+
+    fn statements(&mut self, len: usize) -> Block {
+        let mut statements = (0..len).map(|_| self.statement()).collect::<Vec<_>>();
+        // return, break and continue only end a block
+        let mut terminators = vec![];
+        if self.in_function {
+            terminators.push(if self.rng.gen_bool(0.5) { Statement::ReturnEmpty } else { Statement::Return(Box::new(self.expr())) });
+        }
+        if self.in_loop {
+            terminators.push(Statement::Break);
+            terminators.push(Statement::Continue);
+        }
+        if !terminators.is_empty() && self.rng.gen_bool(0.3) {
+            statements.push(terminators.choose(&mut self.rng).unwrap().clone());
+        }
+        block(statements)
+    }
+
+    fn block(&mut self, bindings: Vec<Binding>) -> Block {
+        self.scoped(bindings, |gen| gen.nested(|gen| {
+            let len = if gen.leaf() { 0 } else { gen.rng.gen_range(0..3) };
+            gen.statements(len)
+        }))
+    }
+
+    fn statement(&mut self) -> Statement {
+        let choice = if self.leaf() { self.rng.gen_range(0..4) } else { self.rng.gen_range(0..12) };
+        match choice {
+            0 | 1 => expr_statement(self.expr()),
+            2 => self.variable_declaration(),
+            3 => Statement::Throw(Box::new(self.expr())),
+            4 => self.function_declaration(),
+            5 => Statement::IfStatement(Box::new(self.if_statement())),
+            6 => {
+                let condition = self.expr();
+                let body = self.loop_body(vec![]);
+                Statement::WhileStatement(Box::new(WhileStatement { condition, body }))
+            },
+            7 => {
+                let iterable = self.expr();
+                let is_const = self.rng.gen_bool(0.7);
+                let (pattern, bindings) = self.nested(|gen| gen.binding_pattern(!is_const));
+                let mut body = self.loop_body(bindings);
+                let binding = VariableDeclaration { pattern: pattern.clone(), type_ann: None, value: None };
+                let declaration = if is_const { Declaration::Const(Box::new([binding])) } else { Declaration::Let(Box::new([binding])) };
+                body.declarations = std::iter::once(declaration).chain(body.declarations.iter().cloned()).collect();
+                Statement::ForOfStatement(Box::new(ForOfStatement { is_const, pattern, iterable, body }))
+            },
+            8 => self.switch_statement(),
+            9 => self.try_statement(),
+            10 => Statement::Block(Box::new(self.block(vec![]))),
+            _ => self.variable_declaration(),
+        }
+    }
+
+    fn variable_declaration(&mut self) -> Statement {
+        let is_const = self.rng.gen_bool(0.5);
+        let len = self.rng.gen_range(1..3);
+        let mut declarations = vec![];
+        let mut declared = vec![];
+        for _ in 0..len {
+            let value = self.expr();
+            let (pattern, bindings) = self.nested(|gen| gen.binding_pattern(!is_const));
+            let value = match (&pattern, is_const) {
+                // let without initializer
+                (Pattern::Variable(_), false) if self.rng.gen_bool(0.3) => None,
+                _ => Some(value),
+            };
+            declarations.push(VariableDeclaration { pattern, type_ann: None, value });
+            declared.extend(bindings);
+        }
+        // visible from the next statement on
+        for binding in declared {
+            self.declare(binding.name, binding.kind, binding.assignable);
+        }
+        let declaration = if is_const { Declaration::Const(declarations.into_boxed_slice()) } else { Declaration::Let(declarations.into_boxed_slice()) };
+        Statement::LocalDeclaration(Box::new(declaration))
+    }
+
+    fn function_declaration(&mut self) -> Statement {
+        let name = self.fresh("f");
+        // hoisted, visible from the body as well
+        self.declare(name.clone(), Kind::Any, false);
+        let mut function = self.function(FunctionName::Named(name));
+        function.body = match function.body {
+            ExprOrBlock::Expr(expr) => ExprOrBlock::Block(block(vec![Statement::Return(Box::new(expr))])),
+            body => body,
+        };
+        Statement::LocalDeclaration(Box::new(Declaration::Function(Rc::new(RefCell::new(function)))))
+    }
+
+    fn if_statement(&mut self) -> IfStatement {
+        let condition = self.expr();
+        let consequent = self.block(vec![]);
+        let alternate = match self.rng.gen_range(0..3) {
+            0 => ElseArm::NoElse,
+            1 => ElseArm::Else(self.block(vec![])),
+            _ => ElseArm::ElseIf(Box::new(self.nested(|gen| gen.if_statement()))),
+        };
+        IfStatement { condition, consequent, alternate }
+    }
+
+    fn loop_body(&mut self, bindings: Vec<Binding>) -> Block {
+        let in_loop = std::mem::replace(&mut self.in_loop, true);
+        let body = self.block(bindings);
+        self.in_loop = in_loop;
+        body
+    }
+
+    fn switch_statement(&mut self) -> Statement {
+        let discriminant = self.expr();
+        let len = self.rng.gen_range(1..4);
+        let default = self.rng.gen_range(0..len + 1);
+        let clauses = (0..len).map(|index| {
+            let mut labels = vec![CaseLabel::Case(self.nested(|gen| gen.expr()))];
+            if index == default {
+                labels.push(CaseLabel::Default);
+            }
+            // every clause ends with a break
+            let mut body = self.block(vec![]);
+            let mut statements = body.statements.to_vec();
+            if !matches!(statements.last(), Some(Statement::Break | Statement::Continue | Statement::Return(_) | Statement::ReturnEmpty)) {
+                statements.push(Statement::Break);
+            }
+            body = Block::new(body.declarations, statements.into_boxed_slice());
+            SwitchClause { labels: labels.into_boxed_slice(), body }
+        }).collect::<Vec<_>>();
+        Statement::SwitchStatement(Box::new(SwitchStatement { discriminant, clauses: clauses.into_boxed_slice() }))
+    }
+
+    fn try_statement(&mut self) -> Statement {
+        let try_block = self.block(vec![]);
+        let (catch, finally) = match self.rng.gen_range(0..3) {
+            0 => (true, false),
+            1 => (false, true),
+            _ => (true, true),
+        };
+        let catch = catch.then(|| {
+            let name = self.fresh("e");
+            let param = Pattern::Variable(Box::new(Self::variable(name.clone())));
+            let mut body = self.block(vec![Binding { name, kind: Kind::Any, assignable: true }]);
+            let binding = VariableDeclaration { pattern: param.clone(), type_ann: None, value: None };
+            body.declarations = std::iter::once(Declaration::Let(Box::new([binding]))).chain(body.declarations.iter().cloned()).collect();
+            CatchClause { param, body }
+        });
+        let finally = finally.then(|| self.block(vec![]));
+        Statement::TryStatement(Box::new(TryStatement { block: try_block, catch, finally }))
+    }
+
+    ///////////////////////
+    // Patterns
+
+    // The pattern with the variables it declares
+    fn binding_pattern(&mut self, assignable: bool) -> (Pattern, Vec<Binding>) {
+        let mut bindings = vec![];
+        let pattern = self.pattern(assignable, &mut bindings);
+        (pattern, bindings)
+    }
+
+    fn pattern(&mut self, assignable: bool, bindings: &mut Vec<Binding>) -> Pattern {
+        let choice = if self.leaf() { 0 } else { self.rng.gen_range(0..5) };
+        match choice {
+            0 | 1 => {
+                let name = self.fresh("v");
+                bindings.push(Binding { name: name.clone(), kind: Kind::Any, assignable });
+                Pattern::Variable(Box::new(Self::variable(name)))
+            },
+            2 => {
+                let len = self.rng.gen_range(0..3);
+                let mut elements = self.nested(|gen| (0..len).map(|_| gen.element_pattern(assignable, bindings)).collect::<Vec<_>>());
+                if self.rng.gen_bool(0.3) {
+                    let name = self.fresh("v");
+                    bindings.push(Binding { name: name.clone(), kind: Kind::Any, assignable });
+                    elements.push(Pattern::Rest(Box::new(Pattern::Variable(Box::new(Self::variable(name))))));
+                }
+                Pattern::ArrayPattern(Box::new(ArrayPattern(elements.into_boxed_slice())))
+            },
+            _ => {
+                let len = self.rng.gen_range(0..3);
+                let mut props = vec![];
+                for _ in 0..len {
+                    let prop = match self.rng.gen_range(0..3) {
+                        0 => {
+                            let name = self.fresh("v");
+                            bindings.push(Binding { name: name.clone(), kind: Kind::Any, assignable });
+                            PropParam::Shorthand(Box::new(Field { name: name.clone() }), Box::new(Self::variable(name)))
+                        },
+                        // the keys of record patterns are identifiers only
+                        1 => {
+                            let key = Field { name: self.member() };
+                            PropParam::KeyValue(Box::new(key), self.nested(|gen| gen.element_pattern(assignable, bindings)))
+                        },
+                        _ => {
+                            let key = self.nested(|gen| gen.expr());
+                            PropParam::Computed(key, self.nested(|gen| gen.element_pattern(assignable, bindings)))
+                        },
+                    };
+                    props.push(prop);
+                }
+                if self.rng.gen_bool(0.3) {
+                    let name = self.fresh("v");
+                    bindings.push(Binding { name: name.clone(), kind: Kind::Any, assignable });
+                    props.push(PropParam::Rest(Box::new(Self::variable(name))));
+                }
+                Pattern::RecordPattern(Box::new(RecordPattern(props.into_boxed_slice())))
+            },
+        }
+    }
+
+    // a pattern with an optional default value
+    fn element_pattern(&mut self, assignable: bool, bindings: &mut Vec<Binding>) -> Pattern {
+        let pattern = self.pattern(assignable, bindings);
+        if self.rng.gen_bool(0.3) {
+            let default = self.nested(|gen| gen.expr());
+            return pattern.with_default(default).unwrap()
+        }
+        pattern
+    }
+
+    ///////////////////////
+    // Expressions
+
+    pub fn expr(&mut self) -> Expr {
+        if self.leaf() {
+            return self.primary()
+        }
+        self.nested(|gen| match gen.rng.gen_range(0..14) {
+            0 | 1 => gen.primary(),
+            2 => {
+                let len = gen.rng.gen_range(0..4);
+                Expr::Array(Box::new(Array(gen.elements(len))))
+            },
+            3 => gen.record(),
+            4 => Expr::Function(Box::new(gen.function(FunctionName::Arrow))),
+            5 => Expr::Function(Box::new(gen.function(FunctionName::Anonymous))),
+            6 => gen.assignment(),
+            7 => cond_expr(gen.expr(), gen.expr(), gen.expr()),
+            8 | 9 => {
+                let op = BINARY_OPS.choose(&mut gen.rng).unwrap().clone();
+                binary_expr(op, gen.expr(), gen.expr())
+            },
+            10 => {
+                let op = UNARY_OPS.choose(&mut gen.rng).unwrap().clone();
+                unary_expr(op, gen.expr())
+            },
+            11 | 12 => gen.call(),
+            _ => Expr::QuasiExpr(Box::new(gen.quasi())),
+        })
+    }
+
+    fn primary(&mut self) -> Expr {
+        match self.rng.gen_range(0..3) {
+            0 => match self.lookup(Kind::Any, false) {
+                Some(name) => Expr::Variable(Box::new(Self::variable(name))),
+                None => self.literal(),
+            },
+            _ => self.literal(),
+        }
+    }
+
+    fn literal(&mut self) -> Expr {
+        let literal = match self.rng.gen_range(0..8) {
+            0 => DataLiteral::Null,
+            1 => DataLiteral::Undefined,
+            2 => if self.rng.gen_bool(0.5) { DataLiteral::True } else { DataLiteral::False },
+            3 | 4 => DataLiteral::Integer(self.rng.gen_range(0..1000)),
+            5 => DataLiteral::Decimal(self.rng.gen_range(0..100), self.rng.gen_range(1..10) * 1_000_000_000_000_000_000),
+            6 => DataLiteral::Bigint(false, Box::new([self.rng.gen_range(1..u64::MAX)])),
+            _ => DataLiteral::String(self.string()),
+        };
+        literal.into()
+    }
+
+    fn member(&mut self) -> Rc<str> {
+        MEMBERS.choose(&mut self.rng).unwrap().to_string().into()
+    }
+
+    fn key(&mut self) -> Rc<str> {
+        KEYS.choose(&mut self.rng).unwrap().to_string().into()
+    }
+
+    fn string(&mut self) -> Rc<str> {
+        let len = self.rng.gen_range(0..5);
+        (0..len).map(|_| *CHARS.choose(&mut self.rng).unwrap()).collect::<String>().into()
+    }
+
+    // array elements, which can be spread
+    fn elements(&mut self, len: usize) -> Box<[Expr]> {
+        (0..len).map(|_| {
+            let expr = self.expr();
+            if self.rng.gen_bool(0.2) { Expr::Spread(Box::new(expr)) } else { expr }
+        }).collect()
+    }
+
+    // call arguments, only the last one can be spread
+    fn arguments(&mut self, len: usize) -> Box<[Expr]> {
+        let mut args = (0..len).map(|_| self.expr()).collect::<Vec<_>>();
+        if let Some(last) = args.pop() {
+            args.push(if self.rng.gen_bool(0.2) { Expr::Spread(Box::new(last)) } else { last });
+        }
+        args.into_boxed_slice()
+    }
+
+    fn record(&mut self) -> Expr {
+        let len = self.rng.gen_range(0..4);
+        let props = (0..len).map(|_| match self.rng.gen_range(0..7) {
+            0 | 1 => {
+                let key = Field { name: self.key() };
+                PropDef::KeyValue(Box::new(key), self.expr())
+            },
+            2 => match self.lookup(Kind::Any, false) {
+                Some(name) => PropDef::Shorthand(Box::new(Field { name: name.clone() }), Box::new(Self::variable(name))),
+                None => PropDef::Spread(self.expr()),
+            },
+            3 => PropDef::Spread(self.expr()),
+            4 => PropDef::Computed(self.expr(), self.expr()),
+            5 => {
+                let name = self.member();
+                let mut method = self.function(FunctionName::Named(name));
+                method.body = match method.body {
+                    ExprOrBlock::Expr(expr) => ExprOrBlock::Block(block(vec![Statement::Return(Box::new(expr))])),
+                    body => body,
+                };
+                PropDef::MethodDef(Box::new(method))
+            },
+            _ => {
+                let name = self.member();
+                if self.rng.gen_bool(0.5) {
+                    let body = self.function_body(vec![], true);
+                    PropDef::Getter(Box::new(function(FunctionName::Named(name), Box::new([]), body)))
+                } else {
+                    let param = self.fresh("p");
+                    let parameters = Box::new([Pattern::Variable(Box::new(Self::variable(param.clone())))]);
+                    let body = self.function_body(vec![Binding { name: param, kind: Kind::Any, assignable: false }], true);
+                    PropDef::Setter(Box::new(function(FunctionName::Named(name), parameters, body)))
+                }
+            },
+        }).collect();
+        Expr::Record(Box::new(Record(props, Box::new([]), Span::default())))
+    }
+
+    fn function(&mut self, name: FunctionName) -> Function {
+        let len = self.rng.gen_range(0..3);
+        let mut bindings = vec![];
+        let parameters = self.nested(|gen| (0..len).map(|_| gen.element_pattern(false, &mut bindings)).collect::<Box<[_]>>());
+        let is_block = name != FunctionName::Arrow || self.rng.gen_bool(0.5);
+        let body = self.function_body(bindings, is_block);
+        function(name, parameters, body)
+    }
+
+    fn function_body(&mut self, bindings: Vec<Binding>, is_block: bool) -> ExprOrBlock {
+        let in_function = std::mem::replace(&mut self.in_function, true);
+        let in_loop = std::mem::replace(&mut self.in_loop, false);
+        let body = if is_block {
+            ExprOrBlock::Block(self.block(bindings))
+        } else {
+            // concise bodies are parsed into a block with a single return statement
+            ExprOrBlock::Block(self.scoped(bindings, |gen| block(vec![Statement::Return(Box::new(gen.expr()))])))
+        };
+        self.in_function = in_function;
+        self.in_loop = in_loop;
+        body
+    }
+
+    // The target is a variable, or a property of a variable or of a call result
+    fn assignment(&mut self) -> Expr {
+        let op = ASSIGN_OPS.choose(&mut self.rng).unwrap().clone();
+        let lvalue = match (self.lookup(Kind::Any, true), self.lookup(Kind::Any, false)) {
+            (Some(name), _) if self.rng.gen_bool(0.5) => LValue::Variable(Box::new(Self::variable(name))),
+            (_, Some(name)) => {
+                let len = self.rng.gen_range(1..3);
+                let post_ops = (0..len).map(|_| if self.rng.gen_bool(0.5) {
+                    LValueCallPostOp::Member(self.member())
+                } else {
+                    LValueCallPostOp::Index(self.expr())
+                }).collect();
+                let mut expr = Expr::Variable(Box::new(Self::variable(name)));
+                if self.rng.gen_bool(0.3) {
+                    let len = self.rng.gen_range(0..3);
+                    expr = call_expr(expr, vec![CallPostOp::Call(self.arguments(len))]);
+                }
+                LValue::CallLValue(Box::new(CallLValue { expr, post_ops, span: Span::default() }))
+            },
+            (_, None) => return self.primary(),
+        };
+        Expr::Assignment(Box::new(Assignment(op, lvalue, self.expr())))
+    }
+
+    fn call(&mut self) -> Expr {
+        let expr = self.primary();
+        let len = self.rng.gen_range(1..4);
+        let mut post_ops = vec![];
+        let mut optional = false;
+        for _ in 0..len {
+            let op = match self.rng.gen_range(0..8) {
+                0 => CallPostOp::Index(self.expr()),
+                1 | 2 => CallPostOp::Member(self.member()),
+                3 | 4 => {
+                    let len = self.rng.gen_range(0..3);
+                    CallPostOp::Call(self.arguments(len))
+                },
+                5 => CallPostOp::OptionalIndex(self.expr()),
+                6 => CallPostOp::OptionalMember(self.member()),
+                // a tagged template cannot follow an optional chain
+                _ if !optional => CallPostOp::QuasiExpr(Box::new(self.quasi())),
+                _ => {
+                    let len = self.rng.gen_range(0..3);
+                    CallPostOp::OptionalCall(self.arguments(len))
+                },
+            };
+            optional |= op.is_optional();
+            post_ops.push(op);
+        }
+        call_expr(expr, post_ops)
+    }
+
+    fn quasi(&mut self) -> QuasiExpr {
+        let len = self.rng.gen_range(0..3);
+        let exprs = (0..len).map(|_| self.expr()).collect::<Box<[_]>>();
+        let strings = (0..len + 1).map(|_| self.string()).collect();
+        QuasiExpr { strings, exprs, span: Span::default() }
+    }
+
+    ///////////////////////
+    // Arithmetic
+
+    fn arithmetic_statement(&mut self) -> Statement {
+        match self.rng.gen_range(0..7) {
+            0 | 1 => {
+                let kind = if self.rng.gen_bool(0.7) { Kind::Integer } else { Kind::Boolean };
+                let value = self.arithmetic_expr(kind);
+                let name = self.fresh("c");
+                self.declare(name.clone(), kind, false);
+                declaration(true, name, value)
+            },
+            2 => {
+                let value = self.arithmetic_expr(Kind::Integer);
+                let name = self.fresh("l");
+                self.declare(name.clone(), Kind::Integer, true);
+                declaration(false, name, value)
+            },
+            3 => {
+                let param = self.fresh("p");
+                let body = self.scoped(vec![Binding { name: param.clone(), kind: Kind::Integer, assignable: false }], |gen| gen.arithmetic_expr(Kind::Integer));
+                let parameters = Box::new([Pattern::Variable(Box::new(Self::variable(param)))]);
+                let name = self.fresh("f");
+                self.declare(name.clone(), Kind::Function, false);
+                let body = block(vec![Statement::Return(Box::new(body))]);
+                declaration(true, name, Expr::Function(Box::new(arrow(parameters, ExprOrBlock::Block(body)))))
+            },
+            4 | 5 => match self.lookup(Kind::Integer, true) {
+                Some(name) => {
+                    let op = ASSIGN_OPS.choose(&mut self.rng).unwrap().clone();
+                    let value = self.arithmetic_expr(Kind::Integer);
+                    let assignment = Assignment(op, LValue::Variable(Box::new(Self::variable(name))), value);
+                    expr_statement(Expr::Assignment(Box::new(assignment)))
+                },
+                None => self.arithmetic_statement(),
+            },
+            _ => {
+                let condition = self.arithmetic_expr(Kind::Boolean);
+                let consequent = self.arithmetic_block();
+                let alternate = if self.rng.gen_bool(0.5) { ElseArm::Else(self.arithmetic_block()) } else { ElseArm::NoElse };
+                Statement::IfStatement(Box::new(IfStatement { condition, consequent, alternate }))
+            },
+        }
+    }
+
+    fn arithmetic_block(&mut self) -> Block {
+        self.scoped(vec![], |gen| gen.nested(|gen| {
+            let len = if gen.leaf() { 0 } else { gen.rng.gen_range(0..3) };
+            block((0..len).map(|_| gen.arithmetic_statement()).collect())
+        }))
+    }
+
+    fn arithmetic_expr(&mut self, kind: Kind) -> Expr {
+        if !self.leaf() && self.rng.gen_bool(0.7) {
+            return self.nested(|gen| match kind {
+                Kind::Boolean => gen.boolean_operation(),
+                _ => gen.integer_operation(),
+            })
+        }
+        match self.lookup(kind, false) {
+            Some(name) if self.rng.gen_bool(0.5) => Expr::Variable(Box::new(Self::variable(name))),
+            _ => match kind {
+                Kind::Boolean => if self.rng.gen_bool(0.5) { DataLiteral::True } else { DataLiteral::False },
+                _ => DataLiteral::Integer(self.rng.gen_range(0..20)),
+            }.into(),
+        }
+    }
+
+    fn integer_operation(&mut self) -> Expr {
+        let binary = |gen: &mut Self, op| binary_expr(op, gen.arithmetic_expr(Kind::Integer), gen.arithmetic_expr(Kind::Integer));
+        match self.rng.gen_range(0..11) {
+            0 | 1 => binary(self, BinaryOp::Add),
+            2 | 3 => binary(self, BinaryOp::Sub),
+            4 => binary(self, BinaryOp::Mul),
+            5 => {
+                let op = [BinaryOp::Div, BinaryOp::Mod, BinaryOp::Pow].choose(&mut self.rng).unwrap().clone();
+                binary(self, op)
+            },
+            6 | 7 => {
+                let op = BITWISE_OPS.choose(&mut self.rng).unwrap().clone();
+                binary(self, op)
+            },
+            8 => unary_expr(UnaryOp::Neg, self.arithmetic_expr(Kind::Integer)),
+            9 => cond_expr(self.arithmetic_expr(Kind::Boolean), self.arithmetic_expr(Kind::Integer), self.arithmetic_expr(Kind::Integer)),
+            _ => match self.lookup(Kind::Function, false) {
+                Some(name) => call_expr(Expr::Variable(Box::new(Self::variable(name))), vec![CallPostOp::Call(Box::new([self.arithmetic_expr(Kind::Integer)]))]),
+                None => binary(self, BinaryOp::Coalesce),
+            },
+        }
+    }
+
+    fn boolean_operation(&mut self) -> Expr {
+        let binary = |gen: &mut Self, op, kind| binary_expr(op, gen.arithmetic_expr(kind), gen.arithmetic_expr(kind));
+        match self.rng.gen_range(0..7) {
+            0 => binary(self, BinaryOp::And, Kind::Boolean),
+            1 => binary(self, BinaryOp::Or, Kind::Boolean),
+            2 => unary_expr(UnaryOp::Not, self.arithmetic_expr(Kind::Boolean)),
+            3 => {
+                let op = [BinaryOp::LessThan, BinaryOp::LessThanEqual, BinaryOp::GreaterThan, BinaryOp::GreaterThanEqual].choose(&mut self.rng).unwrap().clone();
+                binary(self, op, Kind::Integer)
+            },
+            4 => {
+                let op = if self.rng.gen_bool(0.5) { BinaryOp::StrictEqual } else { BinaryOp::StrictNotEqual };
+                let kind = if self.rng.gen_bool(0.5) { Kind::Integer } else { Kind::Boolean };
+                binary(self, op, kind)
+            },
+            5 => cond_expr(self.arithmetic_expr(Kind::Boolean), self.arithmetic_expr(Kind::Boolean), self.arithmetic_expr(Kind::Boolean)),
+            _ => binary(self, BinaryOp::Coalesce, Kind::Boolean),
+        }
+    }
+}
+
+fn block(statements: Vec<Statement>) -> Block {
+    let declarations = statements.iter().filter_map(|statement| match statement {
+        Statement::LocalDeclaration(declaration) => Some(*declaration.clone()),
+        _ => None,
+    }).collect();
+    Block::new(declarations, statements.into_boxed_slice())
+}
+
+fn declaration(is_const: bool, name: Rc<str>, value: Expr) -> Statement {
+    let declaration = VariableDeclaration { pattern: Pattern::Variable(Box::new(Variable::new(name))), type_ann: None, value: Some(value) };
+    Statement::LocalDeclaration(Box::new(if is_const { Declaration::Const(Box::new([declaration])) } else { Declaration::Let(Box::new([declaration])) }))
+}
+
+fn function(name: FunctionName, parameters: Box<[Pattern]>, body: ExprOrBlock) -> Function {
+    Function { name, parameters, signature: None, body, scope: None, span: Span::default(), comments: Box::new([]) }
+}
+
+fn arrow(parameters: Box<[Pattern]>, body: ExprOrBlock) -> Function {
+    function(FunctionName::Arrow, parameters, body)
+}
+
+// a statement starting with { or function would be read as a block or a function declaration
+fn expr_statement(expr: Expr) -> Statement {
+    let expr = match expr {
+        Expr::Function(func) if func.name != FunctionName::Arrow => Expr::ParenedExpr(Box::new(Expr::Function(func))),
+        expr if starts_with_brace(&expr) => Expr::ParenedExpr(Box::new(expr)),
+        expr => expr,
+    };
+    Statement::ExprStatement(Box::new(expr))
+}
+
+// Parenthesizes the operands binding looser than min, as the printer does.
+// A function expression is parenthesized in any leftmost position, not only at the start of the statement,
+// as the generator does not know yet where the enclosing expression will be placed.
+fn operand(expr: Expr, min: Precedence, leftmost: bool) -> Expr {
+    let is_function = matches!(&expr, Expr::Function(func) if func.name != FunctionName::Arrow);
+    if precedence(&expr) < min || (leftmost && is_function) {
+        Expr::ParenedExpr(Box::new(expr))
+    } else {
+        expr
+    }
+}
+
+fn binary_expr(op: BinaryOp, left: Expr, right: Expr) -> Expr {
+    let (left, right) = if op == BinaryOp::Pow {
+        // right associative, and the left operand cannot be a unary expression
+        (operand(left, Precedence::Call, true), operand(right, Precedence::Power, false))
+    } else {
+        let level = binary_precedence(&op);
+        (operand(left, level, true), operand(right, next_precedence(level), false))
+    };
+    Expr::BinaryExpr(Box::new(BinaryExpr(op, left, right)))
+}
+
+fn cond_expr(condition: Expr, consequent: Expr, alternate: Expr) -> Expr {
+    Expr::CondExpr(Box::new(CondExpr(operand(condition, Precedence::OrElse, true), consequent, alternate)))
+}
+
+fn unary_expr(op: UnaryOp, expr: Expr) -> Expr {
+    Expr::UnaryExpr(Box::new(UnaryExpr { op: Box::new([op]), expr: operand(expr, Precedence::Call, false), span: Span::default() }))
+}
+
+fn call_expr(expr: Expr, post_ops: Vec<CallPostOp>) -> Expr {
+    Expr::CallExpr(Box::new(CallExpr { expr: operand(expr, Precedence::Call, true), post_ops: post_ops.into_boxed_slice(), span: Span::default() }))
+}
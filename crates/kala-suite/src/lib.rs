@@ -1,3 +1,5 @@
 pub mod module;
 pub mod script;
+pub mod generator;
+pub mod fold;
 pub mod tests;
\ No newline at end of file
@@ -21,4 +21,47 @@
     x.y = 4;
     return x.y;
 })();
-4;
\ No newline at end of file
+4;
+
+(() => {
+    let v = 3;
+    let x = {
+        get y() {
+            return v;
+        },
+        set y(v_) {
+            v = v_;
+            return 0;
+        }
+    };
+    const before = x.y;
+    const assigned = (x.y = 7);
+    return before * 100 + assigned * 10 + v;
+})();
+377;
+
+(() => {
+    let v = 3;
+    let sets = 0;
+    let x = {
+        get y() {
+            return v;
+        },
+        set y(v_) {
+            sets += 1;
+            v = v_;
+        }
+    };
+    x.y ||= 5;
+    x.y ??= 6;
+    x.y &&= 7;
+    return sets * 10 + x.y;
+})();
+17;
+
+(() => {
+    const set = 4;
+    const o = { get: 1, set };
+    return o.get * 10 + o.set;
+})();
+14;
@@ -62,4 +62,43 @@ false;
 (() => {
     return 3 >= 4;
 })();
-false;
\ No newline at end of file
+false;
+
+(() => {
+    return 3 > 2;
+})();
+true;
+
+(() => {
+    return 2 >= 2;
+})();
+true;
+
+(() => {
+    return 2 > 2;
+})();
+false;
+
+(() => {
+    const x = false;
+    return !!x;
+})();
+false;
+
+(() => {
+    const x = 3;
+    return - -x;
+})();
+3;
+
+(() => {
+    const x = 7 / 2;
+    return x % 1.5 <= x - 3 && -x < -3;
+})();
+true;
+
+(() => {
+    const x = 2;
+    return 1 + 2 ** 3 ** x;
+})();
+513;
@@ -46,4 +46,162 @@
     x.y -= 1;
     return x.y;
 })();
-2;
\ No newline at end of file
+2;
+
+(() => {
+    let x = { y: 3 };
+    x.y *= 5;
+    x.y %= 4;
+    return x.y;
+})();
+3;
+
+(() => {
+    let x = [2];
+    x[0] **= 3;
+    x[0] **= 2;
+    return x[0];
+})();
+64;
+
+(() => {
+    let x = 5;
+    x <<= 3;
+    x >>= 1;
+    return x;
+})();
+20;
+
+(() => {
+    let x = -1;
+    x >>>= 28;
+    return x;
+})();
+15;
+
+(() => {
+    let x = 12;
+    x &= 10;
+    x |= 1;
+    x ^= 3;
+    return x;
+})();
+10;
+
+(() => {
+    let calls = 0;
+    const o = { y: 3 };
+    const f = () => { calls += 1; return o; };
+    f().y += 1;
+    return calls * 10 + o.y;
+})();
+14;
+
+(() => {
+    let x = 3;
+    x += (() => { x = 10; return 1; })();
+    return x;
+})();
+4;
+
+
+(() => {
+    let calls = 0;
+    const compute = () => { calls += 1; return 5; };
+    const cache = { x: null, y: 0 };
+    cache.x ??= compute();
+    cache.x ??= compute();
+    cache.y ||= compute();
+    cache.y &&= 0;
+    return calls * 100 + cache.x * 10 + cache.y;
+})();
+250;
+
+(() => {
+    let x = 0;
+    let y = 2;
+    x &&= 3;
+    y &&= 3;
+    x ||= 4;
+    return x * 10 + y;
+})();
+43;
+
+(() => {
+    let x = 7;
+    x /= 2;
+    let y = 12;
+    y /= -4;
+    return x * 2 + y;
+})();
+4;
+
+(() => {
+    let x = 1;
+    x /= 4;
+    return x;
+})();
+0.25;
+
+(() => {
+    let x = -3;
+    x *= 2;
+    x *= -7;
+    return x;
+})();
+42;
+
+(() => {
+    let x = 4;
+    x **= -1;
+    let y = 16;
+    y **= 0.5;
+    return x * y;
+})();
+1;
+
+(() => {
+    let x = 1.5;
+    x |= 0;
+    let y = -7.5;
+    y >>= 1;
+    return x * 10 + y;
+})();
+6;
+
+(() => {
+    let x = 2;
+    x **= 62;
+    return x === 2 ** 31 * 2 ** 31;
+})();
+true;
+
+(() => {
+    const o = {};
+    o.x ??= 5;
+    o.y &&= 1;
+    o.z = 2;
+    o.z += 1;
+    return o.x * 10 + o.z + (o.y === undefined ? 100 : 0);
+})();
+153;
+
+(() => {
+    const o = { a: 1 };
+    try {
+        o.a.b = 2;
+    } catch (e) {
+        return 1;
+    }
+    return 0;
+})();
+1;
+
+(() => {
+    const o = { a: 1 };
+    o.a = (o.b = 2);
+    o.a += (o.c = 5);
+    o.a ||= (o.d = 0);
+    return o.a * 10 + o.b + o.c;
+})();
+77;
@@ -59,4 +59,14 @@
     const o = {x:3};
     return f;
 })()();
-3;
\ No newline at end of file
+3;
+
+(() => {
+    let x = 1;
+    const f = () => x;
+    let y = x;
+    y = 5;
+    x = 2;
+    return f() * 10 + y;
+})();
+25;
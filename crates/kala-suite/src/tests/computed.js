@@ -0,0 +1,40 @@
+//0
+(() => {
+    const key = 'answer';
+    const table = { [key]: 42 };
+    return table.answer;
+})();
+42;
+
+//1
+(() => {
+    const prefix = 'a';
+    const table = { [`${prefix}b`]: 1, [1 + 1]: 2 };
+    const { ab, [2]: two } = table;
+    return ab + two;
+})();
+3;
+
+//2
+(() => {
+    const k = 'x';
+    const table = { x: 1, [k]: 5 };
+    return table.x;
+})();
+5;
+
+//3
+(() => {
+    const name = 'twice';
+    const table = { [name](n) { return n * 2; } };
+    return table.twice(3);
+})();
+6;
+
+//4
+(() => {
+    const k = 'b';
+    const { [k]: found = 0, ...rest } = { a: 1, b: 2 };
+    return found * 10 + rest.a + (rest.b ?? 0);
+})();
+21;
@@ -0,0 +1,176 @@
+//0
+(() => {
+    let sum = 0;
+    for (const x of [1, 2, 3, 4]) {
+        sum = sum + x;
+    }
+    return sum;
+})();
+10;
+
+//1
+(() => {
+    let count = 0;
+    let last = "";
+    for (const c of "aéb") {
+        count = count + 1;
+        last = c;
+    }
+    if (last === "b") {
+        return count;
+    }
+    return 0;
+})();
+3;
+
+//2
+(() => {
+    let sum = 0;
+    for (const x of [1, 2, 3, 4, 5]) {
+        if (x === 2) {
+            continue;
+        }
+        if (x === 4) {
+            break;
+        }
+        sum = sum + x;
+    }
+    return sum;
+})();
+4;
+
+//3
+(() => {
+    for (const x of [1, 2, 3]) {
+        if (x === 2) {
+            return x;
+        }
+    }
+    return 0;
+})();
+2;
+
+//4
+(() => {
+    let first = () => 0;
+    let last = () => 0;
+    for (const x of [10, 20, 30]) {
+        const f = () => x;
+        if (x === 10) {
+            first = f;
+        }
+        last = f;
+    }
+    return first() + last();
+})();
+40;
+
+//5
+(() => {
+    let sum = 0;
+    for (let x of [1, 2]) {
+        x = x * 10;
+        sum = sum + x;
+    }
+    return sum;
+})();
+30;
+
+//6
+(() => {
+    let count = 0;
+    for (const x of []) {
+        count = count + 1;
+    }
+    return count;
+})();
+0;
+
+//7
+(() => {
+    let sum = 0;
+    for (const [a, b] of [[1, 2], [3, 4]]) {
+        sum = sum + a * b;
+    }
+    return sum;
+})();
+14;
+
+//8
+(() => {
+    let sum = 0;
+    for (const { a, b = 5 } of [{ a: 1, b: 2 }, { a: 10 }]) {
+        sum = sum + a * b;
+    }
+    return sum;
+})();
+52;
+
+//9
+(() => {
+    const range = {
+        "@@iterator": () => {
+            let i = 0;
+            return {
+                next: () => {
+                    i = i + 1;
+                    return { value: i, done: i > 3 };
+                },
+            };
+        },
+    };
+    let sum = 0;
+    for (const x of range) {
+        sum = sum * 10 + x;
+    }
+    return sum;
+})();
+123;
+
+//10
+(() => {
+    // return() is called on break, return and throw, 9 marks the calls
+    let log = 0;
+    const counter = () => ({
+        "@@iterator": () => {
+            let i = 0;
+            return {
+                next() {
+                    i = i + 1;
+                    return { value: i, done: i > 3 };
+                },
+                "return": () => {
+                    log = log * 10 + 9;
+                    return { done: true };
+                },
+            };
+        },
+    });
+    for (const x of counter()) {
+        log = log * 10 + x;
+    }
+    for (const x of counter()) {
+        if (x === 2) {
+            break;
+        }
+        log = log * 10 + x;
+    }
+    const find = () => {
+        for (const x of counter()) {
+            if (x === 2) {
+                return x;
+            }
+        }
+    };
+    const found = find();
+    log = log * 10 + found;
+    try {
+        for (const x of counter()) {
+            throw 5;
+        }
+    } catch (e) {
+        log = log * 10 + e;
+    }
+    return log;
+})();
+123199295;
@@ -0,0 +1,55 @@
+//0
+(() => {
+    const x = {
+        three() {
+            return 3;
+        }
+    };
+    return x.three();
+})();
+3;
+
+//1
+(() => {
+    let count = 0;
+    const counter = {
+        increment() {
+            count += 1;
+        },
+        add(n) {
+            count += n;
+        },
+        get count() {
+            return count;
+        },
+    };
+    counter.increment();
+    counter.add(5);
+    return counter.count;
+})();
+6;
+
+//2
+(() => {
+    const x = {
+        twice(n) { return n * 2; },
+    };
+    return x.twice(4);
+})();
+8;
+
+//3
+(() => {
+    const store = {
+        value: 1,
+        get() {
+            return store.value;
+        },
+        set(v) {
+            store.value = v;
+        },
+    };
+    store.set(7);
+    return store.get() * 10 + store.value;
+})();
+77;
@@ -0,0 +1,9 @@
+import * as math from "math";
+import { double, triple as thrice } from "math";
+import base from "base";
+
+const offset = math.offset;
+
+export default function main() {
+    return offset + double(base) + thrice(1);
+}
@@ -0,0 +1,145 @@
+//0
+0xff;
+255;
+
+//1
+0o17 + 0O1;
+16;
+
+//2
+0b1010;
+10;
+
+//3
+1_000_000;
+1000000;
+
+//4
+1e3;
+1000;
+
+//5
+2.5e1;
+25;
+
+//6
+(() => {
+    const x = 3;
+    return x-1;
+})();
+2;
+
+//7
+-1;
+0 - 1;
+
+//8
+0xFF_FF;
+65535;
+
+//9
+150e-1 + 0.0;
+15;
+
+//10
+576460752303423487 + 1 === 2 ** 59;
+true;
+
+//11
+-576460752303423488 - 1 < -576460752303423488;
+true;
+
+//12
+1e18 > 0 && 1000000000000000000 - 999999999999999999;
+1;
+
+//13
+2 ** 60 / 576460752303423488;
+2;
+
+//14
+1152921504606846976 === 2 ** 30 * 2 ** 30;
+true;
+
+//15
+0x1_0000_0000_0000_0000n === 18446744073709551616n && -0n === 0n && 1n !== 1;
+true;
+
+//16
+0n ? 1 : -340282366920938463463374607431768211457n ? 2 : 3;
+2;
+
+//17
+9223372036854775807 > 2 ** 62 && -9223372036854775807 < -(2 ** 62);
+true;
+
+//18
+0.0000000000000000001 > 0 && 0.1234567890123456789 > 0.123456789 && 1e-19 < 0.1;
+true;
+
+//19
+0.000001 * 1000000 === 1 && 1e18 * 1e-18 === 1 && 0.1 + 0.2 === 0.3;
+true;
+
+//20
+2 / 3 === 0.6666666666666666667 && 1 / 3 === 0.3333333333333333333;
+true;
+
+//21
+2 ** 63 === 9223372036854775807 + 1 && 2 ** 63 / 2 === 2 ** 62;
+true;
+
+//22
+(() => {
+    try {
+        return 2 ** 64;
+    } catch (e) {
+        return e;
+    }
+})();
+"RangeError: the result is out of the range of a number";
+
+//23
+((x) => x !== x && !(x < 1) && !(x >= 1) && !x)(0 / 0) && (x => x !== x)(1 % 0) && ((x) => x !== x)(1 / 0 - 1 / 0);
+true;
+
+//24
+1 / 0 === 2 / 0 && -1 / 0 < 0 && 1 / (1 / 0) === 0;
+true;
+
+//25
+10n + 1n === 11n && 10n - 11n === -1n && 18446744073709551615n * 18446744073709551615n === 340282366920938463426481119284349108225n;
+true;
+
+//26
+7n / 2n === 3n && -7n / 2n === -3n && -7n % 2n === -1n && 340282366920938463463374607431768211457n / 18446744073709551616n === 18446744073709551616n && 2n ** 100n === 1267650600228229401496703205376n && 1n < 2n;
+true;
+
+//27
+(() => {
+    let x = 1n;
+    x += 2n;
+    x *= x;
+    return x;
+})();
+9n;
+
+//28
+(() => {
+    try {
+        return 1n + 1;
+    } catch (e) {
+        return e;
+    }
+})();
+"TypeError: cannot mix BigInt and other types, use explicit conversions";
+
+//29
+(() => {
+    try {
+        return 1n / 0n;
+    } catch (e) {
+        return e;
+    }
+})();
+"RangeError: BigInt division by zero";
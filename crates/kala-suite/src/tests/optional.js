@@ -0,0 +1,77 @@
+//0
+(() => {
+    const user = { profile: { name: 'kala' } };
+    return user?.profile?.name;
+})();
+'kala';
+
+//1
+(() => {
+    const user = undefined;
+    return user?.profile.name;
+})();
+undefined;
+
+//2
+(() => {
+    const user = { profile: null };
+    return user.profile?.name.length;
+})();
+undefined;
+
+//3
+(() => {
+    const items = [1, 2, 3];
+    const none = null;
+    return items?.[1] + (none?.[0] ?? 10);
+})();
+12;
+
+//4
+(() => {
+    const counter = { increment: (x) => x + 1 };
+    const missing = {};
+    return counter.increment?.(1) + (missing.increment?.(1) ?? 0);
+})();
+2;
+
+//5
+(() => {
+    let called = 0;
+    const f = () => { called += 1; return 0; };
+    const none = undefined;
+    none?.[f()];
+    none?.(f());
+    return called;
+})();
+0;
+
+//6
+(() => {
+    const x = true;
+    return x?.5:1;
+})();
+0.5;
+
+//7
+(() => {
+    const a = { b: undefined };
+    try {
+        a.b.c();
+    } catch (e) {
+        return e;
+    }
+    return 0;
+})();
+"TypeError: cannot read properties of undefined";
+
+//8
+(() => {
+    const a = { b: null };
+    try {
+        return a?.b[0];
+    } catch (e) {
+        return e;
+    }
+})();
+"TypeError: cannot read properties of null";
@@ -0,0 +1,76 @@
+//0
+(() => {
+    const opts = { owner: { addr: 7 } };
+    const { limit = 10, owner: { addr } } = opts;
+    return limit + addr;
+})();
+17;
+
+//1
+(() => {
+    const { a: renamed, b: other = 2 } = { a: 1 };
+    return renamed * 10 + other;
+})();
+12;
+
+//2
+(() => {
+    const [x, [y, z] = [5, 6], w = 4] = [1];
+    return x + y + z + w;
+})();
+16;
+
+//3
+(() => {
+    const [first, ...rest] = [1, 2, 3];
+    return first + rest[0] + rest[1];
+})();
+6;
+
+//4
+(() => {
+    const { a, ...others } = { a: 1, b: 2, c: 3 };
+    return a + others.b + others.c;
+})();
+6;
+
+//5
+(() => {
+    function make({ limit = 10, step }) {
+        return limit + step;
+    }
+    return make({ step: 1 }) + make({ limit: 1, step: 1 });
+})();
+13;
+
+//6
+(() => {
+    const sum = (x, { y, z = 3 } = {}, ...more) => x + y + z + more[0];
+    return sum(1, { y: 2 }, 4);
+})();
+10;
+
+//7
+(() => {
+    const f = (a, b = a + 1) => a * b;
+    return f(3);
+})();
+12;
+
+//8
+(() => {
+    let count = 0;
+    for (const [k, v] of [[1, 2], [3, 4]]) {
+        count += k * v;
+    }
+    return count;
+})();
+14;
+
+//9
+(() => {
+    const fallback = 3;
+    const { a = fallback } = {};
+    return a;
+})();
+3;
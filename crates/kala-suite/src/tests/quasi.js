@@ -0,0 +1,60 @@
+//0
+(() => {
+    const x = 3;
+    return `x is ${x}`;
+})();
+"x is 3";
+
+//1
+`plain`;
+"plain";
+
+//2
+(() => {
+    const a = 1;
+    const b = 2;
+    return `${a}+${b}=${a+b}!`;
+})();
+"1+2=3!";
+
+//3
+(() => {
+    const o = {x: 1};
+    return `${`nested ${o.x}`} and {braces}`;
+})();
+"nested 1 and {braces}";
+
+//4
+(() => {
+    const tag = (strings, a, b) => a * b;
+    return tag`${3} times ${4}`;
+})();
+12;
+
+//5
+(() => {
+    const tag = (strings) => strings[1];
+    const x = 0;
+    return tag`a${x}b`;
+})();
+"b";
+
+//6
+(() => {
+    const tag = (strings, x) => () => x;
+    return tag`${5}`();
+})();
+5;
+
+//7
+(() => {
+    const tag = (strings) => strings;
+    const s = tag`a${1}b`;
+    try {
+        s[0] = "z";
+    } catch (e) {
+        return s[0];
+    }
+    return "not frozen";
+})();
+"a";
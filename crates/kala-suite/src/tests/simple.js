@@ -78,4 +78,53 @@ undefined;
 (() => {
     return 3;
 })();
-3;
\ No newline at end of file
+3;
+
+(function(a, b, c) {
+    return a * 100 + b * 10 + c;
+})(1, 2, 3);
+123;
+
+
+(function() {
+    const f = (x) => {
+        if (x === 1) {
+            return 10;
+        } else if (x === 2) {
+            return 20;
+        } else {
+            return 30;
+        }
+    };
+    return f(1) + f(2) + f(3);
+})();
+60;
+
+
+(function() {
+    let truthy = 0;
+    if ({}) {
+        truthy += 1;
+    }
+    if (27) {
+        truthy += 10;
+    }
+    if (0) {
+        truthy += 100;
+    }
+    return truthy;
+})();
+11;
+
+(function() {
+    const x = 27;
+    const y = 0;
+    return (x || 18) + (y || 5) + (x && 2);
+})();
+34;
+
+(function() {
+    const o = {};
+    return (o ?? 1) === o;
+})();
+true;
@@ -0,0 +1,56 @@
+//0
+"a\"b";
+'a"b';
+
+//1
+'it\'s';
+"it's";
+
+//2
+"\x41B\u{43}";
+"ABC";
+
+//3
+"😀";
+"😀";
+
+//4
+"\u{1F600}";
+"😀";
+
+//5
+"back\\slash";
+'back\\slash';
+
+//6
+"line \
+continuation";
+"line continuation";
+
+//7
+`tab\tand \${literal} \u{48}i`;
+"tab	and ${literal} Hi";
+
+//8
+(() => {
+    const $state = 1;
+    const café = 2;
+    const _x$1 = 3;
+    return $state + café + _x$1;
+})();
+6;
+
+//9
+(() => {
+    const abc = 4;
+    return abc;
+})();
+4;
+
+//10
+(() => {
+    const constant = 5;
+    const ifé = 6;
+    return constant + ifé;
+})();
+11;
@@ -0,0 +1,90 @@
+//0
+(() => {
+    const f = (x) => {
+        switch (x) {
+            case 1:
+                return 10;
+            case 2:
+                return 20;
+            default:
+                return 0;
+        }
+    };
+    return f(1) + f(2) + f(3);
+})();
+30;
+
+//1
+(() => {
+    let x = 0;
+    switch (2) {
+        case 1:
+            x = 1;
+            break;
+        case 2:
+            x = 2;
+            break;
+    }
+    return x;
+})();
+2;
+
+//2
+(() => {
+    let x = 0;
+    switch ("b") {
+        case "a":
+        case "b": {
+            x = 5;
+            break;
+        }
+        default:
+            x = 1;
+            break;
+    }
+    return x;
+})();
+5;
+
+//3
+(() => {
+    let x = 0;
+    switch (3) {
+        case 1:
+            x = 1;
+            break;
+    }
+    return x;
+})();
+0;
+
+//4
+(() => {
+    try {
+        switch (1) {
+            case 1:
+                throw 7;
+        }
+    } catch (e) {
+        return e;
+    }
+    return 0;
+})();
+7;
+
+//5
+(() => {
+    let sum = 0;
+    for (const x of [1, 2, 3, 4]) {
+        switch (x) {
+            case 2:
+                continue;
+            default:
+                sum = sum + x;
+                break;
+        }
+        sum = sum + 100;
+    }
+    return sum;
+})();
+308;
@@ -1,11 +1,25 @@
-use std::{fs, rc::Rc, cell::RefCell};
+use std::{fs, rc::Rc, cell::RefCell, convert::Infallible};
 
-use jessie_parser::{lexer::lex_jessie, scope, JessieParserState};
+use jessie_parser::{lexer::{lex_jessie, lex_jessie_with_spans, lex_with_level}, incremental::{IncrementalScript, TextEdit, ReparseError}, printer, conformance::check_conformance, scope, JessieParserState, GrammarLevel};
 use kala_interpreter::{eval_script, statement::eval_statement, interpreter::Interpreter};
+use jessie_ast::{Statement, Declaration, Expr, Script, Variable, Visit, VisitMut, TypeAnn, TypeDeclaration, NamedType};
+use rand::{Rng, SeedableRng, rngs::StdRng};
 use kala_repr::{slot::Slot, completion::Completion, object::Property, function::Frame};
 use utils::Map;
 
-use crate::module::inmemory_state;
+use crate::{module::inmemory_state, generator::Generator, fold::{fold_script, Folded}};
+
+// the spans are left out, so that the scripts parsed from differently formatted sources compare equal
+fn parse_at(code: &str, level: GrammarLevel) -> Result<Script, String> {
+    let (tokenstream, _) = lex_with_level(code, level)?;
+    let mut state = JessieParserState { level, ..JessieParserState::new(tokenstream) };
+    jessie_parser::script(&mut state).map_err(|err| format!("{:?}", err))
+}
+
+fn parse(code: &str) -> Script {
+    parse_at(code, GrammarLevel::Jessie).unwrap_or_else(|err| panic!("{}\n{}", err, code))
+}
+
 #[test]
 fn test_simple() {
     let code = fs::read_to_string("src/tests/simple.js").unwrap();
@@ -41,6 +55,406 @@ fn test_accessor() {
     test_cases(code);
 }
 
+#[test]
+fn test_method() {
+    let code = fs::read_to_string("src/tests/method.js").unwrap();
+    test_cases(code);
+}
+
+#[test]
+fn test_computed() {
+    let code = fs::read_to_string("src/tests/computed.js").unwrap();
+    test_cases(code);
+}
+
+#[test]
+fn test_optional() {
+    let code = fs::read_to_string("src/tests/optional.js").unwrap();
+    test_cases(code);
+}
+
+#[test]
+fn test_pattern() {
+    let code = fs::read_to_string("src/tests/pattern.js").unwrap();
+    test_cases(code);
+}
+
+#[test]
+fn test_try() {
+    let code = fs::read_to_string("src/tests/try.js").unwrap();
+    test_cases(code);
+}
+
+#[test]
+fn test_for() {
+    let code = fs::read_to_string("src/tests/for.js").unwrap();
+    test_cases(code);
+}
+
+#[test]
+fn test_quasi() {
+    let code = fs::read_to_string("src/tests/quasi.js").unwrap();
+    test_cases(code);
+}
+
+#[test]
+fn test_string() {
+    let code = fs::read_to_string("src/tests/string.js").unwrap();
+    test_cases(code);
+}
+
+#[test]
+fn test_number() {
+    let code = fs::read_to_string("src/tests/number.js").unwrap();
+    test_cases(code);
+}
+
+#[test]
+fn test_switch() {
+    let code = fs::read_to_string("src/tests/switch.js").unwrap();
+    test_cases(code);
+}
+
+#[test]
+fn test_spans() {
+    let code = "const x = `a${1}b`;\nconst y = (x;\n".to_string();
+    let (tokenstream, spans) = lex_jessie_with_spans(code).unwrap();
+    assert_eq!(tokenstream.len(), spans.len());
+    let mut state = JessieParserState::with_spans(tokenstream, spans);
+    let err = jessie_parser::script(&mut state).unwrap_err();
+    let span = err.span().unwrap();
+    assert_eq!((span.line, span.column), (2, 13));
+
+    // unresolved variables are reported at the use site
+    let code = "const x = 1;\nfunction f() {\n  return x + y;\n}\n".to_string();
+    let (tokenstream, spans) = lex_jessie_with_spans(code).unwrap();
+    let mut state = JessieParserState::with_spans(tokenstream, spans);
+    let mut script = jessie_parser::script(&mut state).unwrap();
+    let statement_span = script.statements.span_of(1);
+    assert_eq!((statement_span.line, statement_span.column), (2, 1));
+
+    let mut scope_state = jessie_scope::ScopeState::new(Map::<Slot>::default());
+    let err = jessie_scope::scope_script(&mut scope_state, &mut script).unwrap_err();
+    assert_eq!((err.span.line, err.span.column), (3, 14));
+}
+
+#[test]
+fn test_printer() {
+    // printing and parsing back gives the same AST, and printing again gives the same source
+    let files = ["accessor.js", "arithmetic.js", "assign.js", "capture.js", "computed.js", "for.js", "method.js", "number.js", "optional.js", "pattern.js", "quasi.js", "simple.js", "state.js", "string.js", "switch.js", "try.js"];
+    for file in files {
+        let script = parse(&fs::read_to_string(format!("src/tests/{}", file)).unwrap());
+        let printed = printer::print_script(&script);
+        let reparsed = parse(&printed);
+        assert_eq!(reparsed, script, "{}", file);
+        assert_eq!(printer::print_script(&reparsed), printed, "{}", file);
+    }
+
+    let code = fs::read_to_string("src/tests/module.js").unwrap();
+    let module = jessie_parser::module(JessieParserState::new(lex_jessie(code).unwrap())).unwrap();
+    let printed = printer::print_module(&module);
+    assert_eq!(jessie_parser::module(JessieParserState::new(lex_jessie(&printed).unwrap())).unwrap(), module);
+}
+
+#[test]
+fn test_tessie() {
+    let code = "type Pair<T> = [T, T];\ninterface Named extends Base<string> { name: string; nick?: string | undefined; greet(other: Named): void; }\nconst n: number = 1, p: Pair<Array<number>> = [[n], [2]];\nfunction f<T>(x: T, y: number = 1, ...rest: T[]): T | null { return y > 0 ? x : null; }\nconst g = (x: number, {a}: { a: 'a' | 'b' }): (() => number)[] => [() => x];\nconst h = (x: string) => x;\nconst c = n ? (n) : n < n >> (1);\n";
+    let script = parse_at(code, GrammarLevel::Tessie).unwrap_or_else(|err| panic!("{}", err));
+    let statements = &script.statements.statements;
+    assert!(matches!(&statements[0], Statement::TypeDeclaration(decl) if matches!(&**decl, TypeDeclaration::Alias(alias) if alias.ty == TypeAnn::Tuple(Box::new([named("T", []), named("T", [])])))));
+    assert!(matches!(&statements[1], Statement::TypeDeclaration(decl) if matches!(&**decl, TypeDeclaration::Interface(interface) if interface.members.len() == 3 && interface.members[1].optional)));
+
+    fn named<const N: usize>(name: &str, args: [TypeAnn; N]) -> TypeAnn {
+        TypeAnn::Named(Box::new(NamedType { name: name.into(), args: Box::new(args) }))
+    }
+    let Statement::LocalDeclaration(decl) = &statements[2] else { unreachable!() };
+    let Declaration::Const(decls) = &**decl else { unreachable!() };
+    assert_eq!(decls[0].type_ann, Some(TypeAnn::Number));
+    // the closing >> is split into two
+    assert_eq!(decls[1].type_ann, Some(named("Pair", [named("Array", [TypeAnn::Number])])));
+
+    let Statement::LocalDeclaration(decl) = &statements[3] else { unreachable!() };
+    let Declaration::Function(function) = &**decl else { unreachable!() };
+    let signature = function.borrow().signature.clone().unwrap();
+    assert_eq!(&*signature.type_params, [Rc::from("T")]);
+    assert_eq!(&*signature.parameters, [Some(named("T", [])), Some(TypeAnn::Number), Some(TypeAnn::Array(Box::new(named("T", []))))]);
+    assert_eq!(signature.return_type, Some(TypeAnn::Union(Box::new([named("T", []), TypeAnn::Null]))));
+
+    let arrow_signature = |index: usize| match &statements[index] {
+        Statement::LocalDeclaration(decl) => match &**decl {
+            Declaration::Const(decls) => match &decls[0].value {
+                Some(Expr::Function(function)) => function.signature.clone(),
+                value => panic!("{:?}", value),
+            },
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+    let signature = arrow_signature(4).unwrap();
+    assert_eq!(signature.parameter(0), Some(&TypeAnn::Number));
+    assert!(matches!(&signature.return_type, Some(TypeAnn::Array(element)) if matches!(&**element, TypeAnn::Function(_))));
+    assert_eq!(arrow_signature(5).unwrap().return_type, None);
+    // a parenthesized expression followed by the : of a conditional is not an arrow function, and the >> split while
+    // trying is merged back
+    assert!(matches!(&statements[6], Statement::LocalDeclaration(decl) if matches!(&**decl, Declaration::Const(decls) if matches!(decls[0].value, Some(Expr::CondExpr(_))))));
+
+    // printing and parsing back gives the same AST, apart from the spans
+    let printed = printer::print_script(&script);
+    assert_eq!(parse_at(&printed, GrammarLevel::Tessie).map(|reparsed| reparsed.content_hash()), Ok(script.content_hash()), "{}", printed);
+    assert!(printed.contains("function f<T>(x: T, y: number = 1, ...rest: T[]): T | null {"), "{}", printed);
+    assert!(printed.contains("(x: number, { a }: { a: \"a\" | \"b\"; }): (() => number)[] =>"), "{}", printed);
+
+    // annotations are Tessie only, and type is still an identifier in Jessie
+    for code in ["const x: number = 1;", "function f(x: number) {}", "const f = (x: number) => x;", "interface A {}"] {
+        assert!(parse_at(code, GrammarLevel::Jessie).is_err(), "{}", code);
+    }
+    parse_at("const type = 1; type;", GrammarLevel::Jessie).unwrap();
+    parse_at("const type = 1; type;", GrammarLevel::Tessie).unwrap();
+
+    // types are erased at runtime
+    let mut script = parse_at("(function (): number {\n    type N = number;\n    const add = (a: N, b: N): N => a + b;\n    const total: N = add(1, 2);\n    return total;\n})();", GrammarLevel::Tessie).unwrap();
+    let mut scope_state = jessie_scope::ScopeState::new(Map::<Slot>::default());
+    jessie_scope::scope_script(&mut scope_state, &mut script).unwrap();
+    match eval_script(scope_state.used_builtins(), script) {
+        Completion::Value(value) => assert_eq!(value, Slot::new_integer(3)),
+        completion => panic!("{:?}", completion),
+    }
+}
+
+#[test]
+fn test_visit() {
+    struct Names(Vec<String>);
+
+    impl Visit for Names {
+        type Error = Infallible;
+
+        fn visit_variable(&mut self, var: &Variable) -> Result<(), Infallible> {
+            self.0.push(var.name.to_string());
+            Ok(())
+        }
+    }
+
+    struct Rename;
+
+    impl VisitMut for Rename {
+        type Error = Infallible;
+
+        fn visit_variable(&mut self, var: &mut Variable) -> Result<(), Infallible> {
+            var.name = format!("{}{}", var.name, var.name).into();
+            Ok(())
+        }
+    }
+
+    let code = "const f = (a, [b = c], {e: [g]}) => a + b; f(1, [], {d: `${h}`, [i]: j.k});";
+    let mut script = parse(code);
+
+    let mut names = Names(vec![]);
+    let Ok(()) = names.visit_script(&script);
+    assert_eq!(names.0, ["f", "a", "b", "c", "g", "a", "b", "f", "h", "i", "j"]);
+
+    let Ok(()) = Rename.visit_script(&mut script);
+    assert_eq!(printer::print_script(&script), "const ff = (aa, [bb = cc], { e: [gg] }) => aa + bb;\nff(1, [], { d: `${hh}`, [ii]: jj.k });\n");
+}
+
+#[test]
+fn test_content_hash() {
+    // sources differing only in formatting hash the same
+    let same = [
+        ("const x = 1_000 + 'a';", "// comment\nconst x=(1000)+\"a\" ;"),
+        ("function f(a, {b}) { return a?.[b]; }", "function f(a,{b}){\n  return (a)?.[b];\n}"),
+        ("let y = 0x10n; y += .5;", "let y = 16n;\n\n/* c */ y += 0.5;"),
+    ];
+    for (left, right) in same {
+        assert_eq!(parse(left).content_hash(), parse(right).content_hash(), "{} / {}", left, right);
+    }
+
+    let different = [
+        ("const x = a + (b + c);", "const x = a + b + c;"),
+        ("const x = 1;", "let x = 1;"),
+        ("f(a, b);", "f(a)(b);"),
+        ("const s = `a${b}`;", "const s = `${b}a`;"),
+        ("x = 1;", "x += 1;"),
+    ];
+    for (left, right) in different {
+        assert_ne!(parse(left).content_hash(), parse(right).content_hash(), "{} / {}", left, right);
+    }
+
+    // the hash of a function does not depend on where it is declared, or on it being scoped
+    let mut script = parse("const g = 1;\nfunction f(x) { return x + g; }");
+    let other = parse("function f(x) {return x+g;}");
+    let function = |script: &Script| match &script.statements.statements.last() {
+        Some(Statement::LocalDeclaration(decl)) => match decl.as_ref() {
+            Declaration::Function(function) => function.borrow().content_hash(),
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+    assert_eq!(function(&script), function(&other));
+    assert_ne!(function(&script).to_string(), script.content_hash().to_string());
+    let hash = script.content_hash();
+    jessie_scope::scope_script(&mut jessie_scope::ScopeState::new(Map::<Slot>::default()), &mut script).unwrap();
+    assert_eq!(script.content_hash(), hash);
+    assert_eq!(hash.to_string().len(), 64);
+
+    let code = fs::read_to_string("src/tests/module.js").unwrap();
+    let module = jessie_parser::module(JessieParserState::new(lex_jessie(code).unwrap())).unwrap();
+    let printed = printer::print_module(&module);
+    assert_eq!(jessie_parser::module(JessieParserState::new(lex_jessie(&printed).unwrap())).unwrap().content_hash(), module.content_hash());
+}
+
+#[test]
+fn test_generated_roundtrip() {
+    // a generated script prints, parses back to the same script, and passes the scope resolver
+    for seed in 0..300 {
+        let script = Generator::new(seed).script();
+        let printed = printer::print_script(&script);
+        let mut reparsed = parse(&printed);
+        assert_eq!(printer::print_script(&reparsed), printed, "seed {}", seed);
+        assert_eq!(reparsed.content_hash(), script.content_hash(), "seed {}\n{}", seed, printed);
+        let mut scope_state = jessie_scope::ScopeState::new(Map::<Slot>::default());
+        jessie_scope::scope_script(&mut scope_state, &mut reparsed).unwrap_or_else(|err| panic!("seed {}: {}\n{}", seed, err, printed));
+    }
+}
+
+#[test]
+fn test_generated_eval() {
+    // the interpreter agrees with constant folding on generated arithmetic
+    let mut folded = 0;
+    for seed in 0..300 {
+        let script = Generator::new(seed).arithmetic_script();
+        let Some(expected) = fold_script(&script) else { continue };
+        folded += 1;
+        let printed = printer::print_script(&script);
+        let mut reparsed = parse(&printed);
+        assert_eq!(reparsed.content_hash(), script.content_hash(), "seed {}\n{}", seed, printed);
+        let mut scope_state = jessie_scope::ScopeState::new(Map::<Slot>::default());
+        jessie_scope::scope_script(&mut scope_state, &mut reparsed).unwrap_or_else(|err| panic!("seed {}: {}\n{}", seed, err, printed));
+        let expected = match expected {
+            Folded::Integer(int) => Slot::new_integer(int),
+            Folded::Boolean(boolean) => Slot::new_boolean(boolean),
+        };
+        match eval_script(scope_state.used_builtins(), reparsed) {
+            Completion::Value(value) => assert_eq!(value, expected, "seed {}\n{}", seed, printed),
+            completion => panic!("seed {}: {:?}\n{}", seed, completion, printed),
+        }
+    }
+    // most of the scripts stay within the folded subset
+    assert!(folded > 150, "{}", folded);
+}
+
+#[test]
+fn test_conformance() {
+    // the checked test scripts are all Jessie
+    for file in ["capture.js", "pattern.js", "switch.js", "try.js"] {
+        let code = fs::read_to_string(format!("src/tests/{}", file)).unwrap();
+        assert_eq!(check_conformance(&code), [], "{}", file);
+    }
+}
+
+#[test]
+fn test_incremental() {
+    // random edits on the test scripts, every edit must give the same result as a full reparse
+    let fragments = [" ", "\n", "x", "1", ";", "const y = 2;\n", "/* c */", "// c\n", "`a${x}b`", "(", ")", "{", "}", "=>", "else {}", "'s'", "\u{e9}", ""];
+    let mut rng = StdRng::seed_from_u64(20261018);
+
+    for file in ["simple.js", "capture.js", "pattern.js", "quasi.js", "switch.js", "try.js"] {
+        let code = fs::read_to_string(format!("src/tests/{}", file)).unwrap();
+        let mut incremental = IncrementalScript::new(code);
+        for _ in 0..100 {
+            let source = incremental.source().to_string();
+            let boundaries: Vec<usize> = source.char_indices().map(|(i, _)| i).chain([source.len()]).collect();
+            let start = rng.gen_range(0..boundaries.len());
+            let end = (start + rng.gen_range(0..4)).min(boundaries.len() - 1);
+            let range = boundaries[start]..boundaries[end];
+            let replacement = fragments[rng.gen_range(0..fragments.len())].to_string();
+
+            // apply the edit, then undo it, so that the source stays mostly valid
+            let undo = TextEdit { range: range.start..range.start + replacement.len(), replacement: source[range.clone()].to_string() };
+            for edit in [TextEdit { range, replacement }, undo] {
+                let reparsed = incremental.edit(edit.clone()).is_ok();
+                assert_reparsed(&incremental, reparsed, &edit);
+            }
+        }
+    }
+
+    // edits from and to an empty source
+    let mut incremental = IncrementalScript::new(String::new());
+    for edit in [TextEdit { range: 0..0, replacement: "const x = 1;".to_string() }, TextEdit { range: 0..12, replacement: String::new() }] {
+        assert!(incremental.edit(edit.clone()).is_ok(), "{:?}", edit);
+        assert_reparsed(&incremental, true, &edit);
+    }
+}
+
+// the spans take part in the comparison, so that the ones in the reused statements are checked to be shifted
+// reparsed is whether the edit succeeded, which must agree with the full reparse
+fn assert_reparsed(incremental: &IncrementalScript, reparsed: bool, edit: &TextEdit) {
+    let full = lex_jessie_with_spans(incremental.source()).map_err(ReparseError::Lexer).and_then(|(tokens, spans)| {
+        let mut state = JessieParserState::with_spans(tokens.clone(), spans.clone());
+        jessie_parser::script(&mut state).map(|script| (tokens, spans, script)).map_err(ReparseError::Parser)
+    });
+    match full {
+        Ok((tokens, spans, script)) => {
+            assert!(reparsed, "the edit failed but the full reparse succeeds {:?}", edit);
+            let (incremental_tokens, incremental_spans) = incremental.tokens().unwrap();
+            assert_eq!(incremental_tokens, &tokens[..], "{:?}", edit);
+            assert_eq!(incremental_spans, &spans[..], "{:?}", edit);
+            assert_eq!(incremental.script().unwrap(), &script, "{:?}", edit);
+        },
+        Err(err) => {
+            assert!(!reparsed, "the edit succeeded but the full reparse fails {:?}", edit);
+            assert_eq!(incremental.script().unwrap_err(), &err, "{:?}", edit);
+        },
+    }
+}
+
+#[test]
+fn test_module_import() {
+    let code = fs::read_to_string("src/tests/module.js").unwrap();
+    let tokenstream = lex_jessie(code).unwrap();
+    let state = JessieParserState::new(tokenstream);
+    let mut module = jessie_parser::module(state).unwrap();
+
+    // evaluating a module that was never scoped throws instead of panicking
+    let unscoped = kala_interpreter::module::eval_module(vec![], module.clone(), vec![]);
+    assert!(matches!(unscoped, Completion::Throw(_)), "unexpected completion {:?}", unscoped);
+
+    let mut scope_state = jessie_scope::ScopeState::new(Map::<Slot>::default());
+    jessie_scope::scope_module(&mut scope_state, &mut module).unwrap();
+
+    let sources: Vec<String> = module.imports().map(|import| import.source.clone()).collect();
+    assert_eq!(sources, vec!["math", "math", "base"]);
+
+    let multiply = |n: i64| Slot::new_native_function("multiply", Rc::new(RefCell::new(move |args: &mut [Slot]| {
+        Completion::Return(args[0].op_mul(&Slot::new_integer(n))?)
+    })));
+    let math = Slot::new_object(vec![
+        Property::data("offset", Slot::new_integer(100)),
+        Property::data("double", multiply(2)),
+        Property::data("triple", multiply(3)),
+    ]);
+    let base = Slot::new_object(vec![
+        Property::data("default", Slot::new_integer(5)),
+    ]);
+    let imports = sources.iter().map(|source| match source.as_str() {
+        "math" => math.clone(),
+        "base" => base.clone(),
+        _ => unreachable!(),
+    }).collect();
+
+    let main = kala_interpreter::module::eval_module(scope_state.used_builtins(), module, imports);
+    let main = match main {
+        Completion::Value(main) => main,
+        completion => panic!("unexpected completion {:?}", completion),
+    };
+    let result = main.call(&mut Frame::empty(), &mut vec![]);
+    assert_eq!(result, Completion::Return(Slot::new_integer(113)));
+
+    // a second default export is rejected before evaluation
+    let tokenstream = lex_jessie("export default const a = 1;\nexport default function b() {}\n".to_string()).unwrap();
+    let err = jessie_parser::module(JessieParserState::new(tokenstream)).unwrap_err();
+    assert!(format!("{:?}", err).contains("module must not have more than one default export"), "{:?}", err);
+}
+
 pub fn state_test_cases(code: String) {
     let tokenstream = lex_jessie(code).unwrap();
 
@@ -104,4 +518,4 @@ pub fn test_cases(code: String) {
         let expected = eval_statement(&mut interpreter, &case[1]);
         assert_eq!(actual, expected);
     }
-}
\ No newline at end of file
+}
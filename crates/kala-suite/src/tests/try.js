@@ -0,0 +1,95 @@
+//0
+(() => {
+    try {
+        throw 3;
+    } catch (e) {
+        return e+1;
+    }
+})();
+4;
+
+//1
+(() => {
+    let x = 1;
+    try {
+        x = 2;
+    } finally {
+        x = x+1;
+    }
+    return x;
+})();
+3;
+
+//2
+(() => {
+    let x = 0;
+    try {
+        throw 1;
+    } catch (e) {
+        x = e;
+    } finally {
+        x = x+10;
+    }
+    return x;
+})();
+11;
+
+//3
+(() => {
+    try {
+        return 1;
+    } finally {
+        return 2;
+    }
+})();
+2;
+
+//4
+(() => {
+    try {
+        throw 1;
+    } finally {
+        return 2;
+    }
+})();
+2;
+
+//5
+(() => {
+    function fail() {
+        throw 5;
+    }
+    try {
+        fail();
+        return 0;
+    } catch (e) {
+        return e;
+    }
+})();
+5;
+
+//6
+(() => {
+    try {
+        try {
+            throw 1;
+        } finally {
+            console.log('inner finally');
+        }
+    } catch (e) {
+        return e+6;
+    }
+})();
+7;
+
+//7
+(() => {
+    let f = undefined;
+    try {
+        throw 8;
+    } catch (e) {
+        f = () => e;
+    }
+    return f();
+})();
+8;